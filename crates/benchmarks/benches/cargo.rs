@@ -12,6 +12,15 @@ mod toml_edit {
     fn manifest(sample: &Data<'static>) -> manifest::Manifest {
         ::toml_edit::de::from_str(sample.content()).unwrap()
     }
+
+    #[divan::bench(args=MANIFESTS)]
+    fn clone(bencher: divan::Bencher, sample: &Data<'static>) {
+        let document = sample
+            .content()
+            .parse::<::toml_edit::DocumentMut>()
+            .unwrap();
+        bencher.bench(|| document.clone());
+    }
 }
 
 mod toml {
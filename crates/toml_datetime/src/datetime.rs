@@ -1,3 +1,4 @@
+use std::cmp::Ordering;
 use std::error;
 use std::fmt;
 use std::str::{self, FromStr};
@@ -77,7 +78,7 @@ use serde::{de, ser};
 /// [Local Date-Time]: https://toml.io/en/v1.0.0#local-date-time
 /// [Local Date]: https://toml.io/en/v1.0.0#local-date
 /// [Local Time]: https://toml.io/en/v1.0.0#local-time
-#[derive(PartialEq, Eq, PartialOrd, Ord, Copy, Clone, Debug)]
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Copy, Clone, Debug)]
 pub struct Datetime {
     /// Optional date.
     /// Required for: *Offset Date-Time*, *Local Date-Time*, *Local Date*.
@@ -97,6 +98,55 @@ pub struct Datetime {
 #[non_exhaustive]
 pub struct DatetimeParseError {}
 
+/// Error returned when converting between a `Datetime` and another datetime library's types
+///
+/// The conversions require the kinds to match, e.g. only an *Offset Date-Time* converts to
+/// `chrono::DateTime<FixedOffset>`, and the values to be representable by both types.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "chrono")] {
+/// use toml_datetime::Datetime;
+///
+/// let expiry = "2024-07-30T12:00:00Z".parse::<Datetime>().unwrap();
+/// let expiry = chrono::DateTime::<chrono::FixedOffset>::try_from(expiry).unwrap();
+/// assert_eq!(expiry.timestamp(), 1_722_340_800);
+///
+/// let local = "2024-07-30T12:00:00".parse::<Datetime>().unwrap();
+/// assert!(chrono::DateTime::<chrono::FixedOffset>::try_from(local).is_err());
+/// # }
+/// ```
+#[cfg(any(feature = "chrono", feature = "time"))]
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct DatetimeConversionError {
+    reason: &'static str,
+}
+
+#[cfg(any(feature = "chrono", feature = "time"))]
+impl DatetimeConversionError {
+    fn new(reason: &'static str) -> Self {
+        Self { reason }
+    }
+}
+
+/// Error returned when a `Datetime`, or one of its parts, has fields out of range or a
+/// combination of fields that TOML can't represent
+///
+/// See [`Datetime::validate`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct DatetimeValidationError {
+    reason: &'static str,
+}
+
+impl DatetimeValidationError {
+    fn new(reason: &'static str) -> Self {
+        Self { reason }
+    }
+}
+
 // Currently serde itself doesn't have a datetime type, so we map our `Datetime`
 // to a special value in the serde data model. Namely one with these special
 // fields/struct names.
@@ -124,7 +174,7 @@ pub const NAME: &str = "$__toml_private_Datetime";
 /// > ```
 ///
 /// [Local Date]: https://toml.io/en/v1.0.0#local-date
-#[derive(PartialEq, Eq, PartialOrd, Ord, Copy, Clone, Debug)]
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Copy, Clone, Debug)]
 pub struct Date {
     /// Year: four digits
     pub year: u16,
@@ -154,7 +204,7 @@ pub struct Date {
 /// > must be truncated, not rounded.
 ///
 /// [Local Time]: https://toml.io/en/v1.0.0#local-time
-#[derive(PartialEq, Eq, PartialOrd, Ord, Copy, Clone, Debug)]
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Copy, Clone, Debug)]
 pub struct Time {
     /// Hour: 0 to 23
     pub hour: u8,
@@ -168,7 +218,7 @@ pub struct Time {
 
 /// A parsed TOML time offset
 ///
-#[derive(PartialEq, Eq, PartialOrd, Ord, Copy, Clone, Debug)]
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Copy, Clone, Debug)]
 pub enum Offset {
     /// > A suffix which, when applied to a time, denotes a UTC offset of 00:00;
     /// > often spoken "Zulu" from the ICAO phonetic alphabet representation of
@@ -235,6 +285,323 @@ impl From<Time> for Datetime {
     }
 }
 
+impl Datetime {
+    /// Compare two datetimes by the moment they represent
+    ///
+    /// Offset date-times are compared as instants, regardless of their offsets, while local
+    /// date-times, dates and times are compared to those of the same kind.  Comparing different
+    /// kinds, like an offset and a local date-time, returns `None`.
+    ///
+    /// Unlike this, [`Ord`] compares the fields one by one.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::cmp::Ordering;
+    /// use toml_datetime::Datetime;
+    ///
+    /// let utc = "1979-05-27T07:32:00Z".parse::<Datetime>().unwrap();
+    /// let pdt = "1979-05-27T00:32:00-07:00".parse::<Datetime>().unwrap();
+    /// let local = "1979-05-27T07:32:00".parse::<Datetime>().unwrap();
+    /// assert_eq!(utc.chronological_cmp(&pdt), Some(Ordering::Equal));
+    /// assert_eq!(utc.chronological_cmp(&local), None);
+    /// ```
+    pub fn chronological_cmp(&self, other: &Self) -> Option<Ordering> {
+        match (self.offset, other.offset) {
+            (Some(_), Some(_)) => Some(self.utc_instant()?.cmp(&other.utc_instant()?)),
+            (None, None)
+                if self.date.is_some() == other.date.is_some()
+                    && self.time.is_some() == other.time.is_some() =>
+            {
+                Some((self.date, self.time).cmp(&(other.date, other.time)))
+            }
+            _ => None,
+        }
+    }
+
+    /// Seconds and nanoseconds since the Unix epoch of an offset date-time
+    fn utc_instant(&self) -> Option<(i64, u32)> {
+        let date = self.date?;
+        let time = self.time?;
+        let offset = match self.offset? {
+            Offset::Z => 0,
+            Offset::Custom { minutes } => i64::from(minutes),
+        };
+        let seconds = date.days_since_epoch() * 86_400
+            + i64::from(time.hour) * 3_600
+            + i64::from(time.minute) * 60
+            + i64::from(time.second)
+            - offset * 60;
+        Some((seconds, time.nanosecond))
+    }
+}
+
+impl Date {
+    // Based on http://howardhinnant.github.io/date_algorithms.html#days_from_civil
+    fn days_since_epoch(&self) -> i64 {
+        let month = i64::from(self.month);
+        let year = i64::from(self.year) - i64::from(month <= 2);
+        let era = year.div_euclid(400);
+        let year_of_era = year - era * 400;
+        let month_from_march = (month + 9) % 12;
+        let day_of_year = (153 * month_from_march + 2) / 5 + i64::from(self.day) - 1;
+        let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+        era * 146_097 + day_of_era - 719_468
+    }
+}
+
+/// Builds a [`Datetime`] field by field, see [`Datetime::builder`]
+#[derive(Copy, Clone, Debug, Default)]
+pub struct DatetimeBuilder {
+    date: Option<(u16, u8, u8)>,
+    time: Option<(u8, u8, u8)>,
+    nanosecond: Option<u32>,
+    offset: Option<Offset>,
+}
+
+impl DatetimeBuilder {
+    /// Set the date
+    pub fn date(mut self, year: u16, month: u8, day: u8) -> Self {
+        self.date = Some((year, month, day));
+        self
+    }
+
+    /// Set the time, without fractional seconds
+    pub fn time(mut self, hour: u8, minute: u8, second: u8) -> Self {
+        self.time = Some((hour, minute, second));
+        self
+    }
+
+    /// Set the fractional seconds of the time
+    pub fn nanosecond(mut self, nanosecond: u32) -> Self {
+        self.nanosecond = Some(nanosecond);
+        self
+    }
+
+    /// Set the offset, making this an *Offset Date-Time*
+    pub fn offset(mut self, offset: Offset) -> Self {
+        self.offset = Some(offset);
+        self
+    }
+
+    /// Check the fields and build the datetime
+    ///
+    /// The fields must be in range and form one of the kinds of datetime TOML has, see
+    /// [`Datetime::validate`].  Fractional seconds need a time.
+    pub fn build(self) -> Result<Datetime, DatetimeValidationError> {
+        let date = self
+            .date
+            .map(|(year, month, day)| Date::new(year, month, day))
+            .transpose()?;
+        let time = match (self.time, self.nanosecond) {
+            (Some((hour, minute, second)), nanosecond) => {
+                Some(Time::new(hour, minute, second, nanosecond.unwrap_or(0))?)
+            }
+            (None, Some(_)) => {
+                return Err(DatetimeValidationError::new(
+                    "fractional seconds require a time",
+                ))
+            }
+            (None, None) => None,
+        };
+        let datetime = Datetime {
+            date,
+            time,
+            offset: self.offset,
+        };
+        datetime.validate()?;
+        Ok(datetime)
+    }
+}
+
+impl Datetime {
+    /// Build a datetime field by field, with each field checked
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use toml_datetime::{Datetime, Offset};
+    ///
+    /// let release = Datetime::builder()
+    ///     .date(1979, 5, 27)
+    ///     .time(7, 32, 0)
+    ///     .offset(Offset::Z)
+    ///     .build()
+    ///     .unwrap();
+    /// assert_eq!(release.to_string(), "1979-05-27T07:32:00Z");
+    ///
+    /// assert!(Datetime::builder().date(1979, 13, 1).build().is_err());
+    /// // A date can't have an offset
+    /// assert!(Datetime::builder().date(1979, 5, 27).offset(Offset::Z).build().is_err());
+    /// ```
+    pub fn builder() -> DatetimeBuilder {
+        DatetimeBuilder::default()
+    }
+
+    /// A *Local Date*, see [`Date::new`]
+    pub fn new_local_date(year: u16, month: u8, day: u8) -> Result<Self, DatetimeValidationError> {
+        Date::new(year, month, day).map(Self::from)
+    }
+
+    /// A *Local Time*, see [`Time::new`]
+    pub fn new_local_time(
+        hour: u8,
+        minute: u8,
+        second: u8,
+        nanosecond: u32,
+    ) -> Result<Self, DatetimeValidationError> {
+        Time::new(hour, minute, second, nanosecond).map(Self::from)
+    }
+
+    /// A *Local Date-Time*, from a checked date and time
+    pub fn new_local_datetime(date: Date, time: Time) -> Self {
+        Self {
+            date: Some(date),
+            time: Some(time),
+            offset: None,
+        }
+    }
+
+    /// An *Offset Date-Time*, from a checked date, time and offset
+    pub fn new_offset_datetime(date: Date, time: Time, offset: Offset) -> Self {
+        Self {
+            date: Some(date),
+            time: Some(time),
+            offset: Some(offset),
+        }
+    }
+
+    /// Parse a datetime, like [`FromStr`], also rejecting out of range fields
+    ///
+    /// [`FromStr`] accepts some values, like an hour of 24, that [`Datetime::validate`] rejects.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use toml_datetime::Datetime;
+    ///
+    /// assert!(Datetime::try_from_str("1979-05-27T07:32:00Z").is_ok());
+    /// assert!(Datetime::try_from_str("1979-05-27T24:00:00").is_err());
+    /// ```
+    pub fn try_from_str(s: &str) -> Result<Self, DatetimeParseError> {
+        let datetime = s.parse::<Self>()?;
+        datetime.validate().map_err(|_| DatetimeParseError {})?;
+        Ok(datetime)
+    }
+
+    /// Check that the fields are in range and form one of the kinds of datetime TOML has
+    ///
+    /// The fields are public, so nothing stops them from holding a month of 13, or an offset
+    /// without a time, which fail to render as TOML that parses again.  See [`Date::new`],
+    /// [`Time::new`] and [`Offset::from_minutes`] for the ranges, and the table on [`Datetime`]
+    /// for the kinds.
+    pub fn validate(&self) -> Result<(), DatetimeValidationError> {
+        match (self.date, self.time, self.offset) {
+            (Some(_), Some(_), _) | (Some(_), None, None) | (None, Some(_), None) => {}
+            (None, None, _) => return Err(DatetimeValidationError::new("missing date and time")),
+            (_, _, Some(_)) => {
+                return Err(DatetimeValidationError::new(
+                    "an offset requires both a date and a time",
+                ))
+            }
+        }
+        if let Some(date) = self.date {
+            Date::new(date.year, date.month, date.day)?;
+        }
+        if let Some(time) = self.time {
+            Time::new(time.hour, time.minute, time.second, time.nanosecond)?;
+        }
+        if let Some(Offset::Custom { minutes }) = self.offset {
+            Offset::from_minutes(minutes)?;
+        }
+        Ok(())
+    }
+}
+
+impl Date {
+    /// A date, checking that it exists
+    ///
+    /// The year is limited to the four digits TOML allows.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use toml_datetime::Date;
+    ///
+    /// assert!(Date::new(2024, 2, 29).is_ok());
+    /// assert!(Date::new(2023, 2, 29).is_err());
+    /// ```
+    pub fn new(year: u16, month: u8, day: u8) -> Result<Self, DatetimeValidationError> {
+        if year > 9999 {
+            return Err(DatetimeValidationError::new("year must be at most 9999"));
+        }
+        if !(1..=12).contains(&month) {
+            return Err(DatetimeValidationError::new("month must be 1 to 12"));
+        }
+        if day < 1 || day > days_in_month(year, month) {
+            return Err(DatetimeValidationError::new(
+                "day must be within the days of the month",
+            ));
+        }
+        Ok(Self { year, month, day })
+    }
+}
+
+impl Time {
+    /// A time of day, checking that it exists
+    ///
+    /// A second of 60 is allowed for leap seconds.
+    pub fn new(
+        hour: u8,
+        minute: u8,
+        second: u8,
+        nanosecond: u32,
+    ) -> Result<Self, DatetimeValidationError> {
+        if hour > 23 {
+            return Err(DatetimeValidationError::new("hour must be 0 to 23"));
+        }
+        if minute > 59 {
+            return Err(DatetimeValidationError::new("minute must be 0 to 59"));
+        }
+        if second > 60 {
+            return Err(DatetimeValidationError::new("second must be 0 to 60"));
+        }
+        if nanosecond > 999_999_999 {
+            return Err(DatetimeValidationError::new(
+                "nanosecond must be 0 to 999_999_999",
+            ));
+        }
+        Ok(Self {
+            hour,
+            minute,
+            second,
+            nanosecond,
+        })
+    }
+}
+
+impl Offset {
+    /// An offset from UTC, checking that it is less than a day
+    pub fn from_minutes(minutes: i16) -> Result<Self, DatetimeValidationError> {
+        if !(-1439..=1439).contains(&minutes) {
+            return Err(DatetimeValidationError::new(
+                "offset must be less than 24 hours",
+            ));
+        }
+        Ok(Self::Custom { minutes })
+    }
+}
+
+fn days_in_month(year: u16, month: u8) -> u8 {
+    let is_leap_year = (year % 4 == 0) && ((year % 100 != 0) || (year % 400 == 0));
+    match month {
+        2 if is_leap_year => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
 impl fmt::Display for Datetime {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         if let Some(ref date) = self.date {
@@ -339,15 +706,7 @@ impl FromStr for Datetime {
             if date.month < 1 || date.month > 12 {
                 return Err(DatetimeParseError {});
             }
-            let is_leap_year =
-                (date.year % 4 == 0) && ((date.year % 100 != 0) || (date.year % 400 == 0));
-            let max_days_in_month = match date.month {
-                2 if is_leap_year => 29,
-                2 => 28,
-                4 | 6 | 9 | 11 => 30,
-                _ => 31,
-            };
-            if date.day < 1 || date.day > max_days_in_month {
+            if date.day < 1 || date.day > days_in_month(date.year, date.month) {
                 return Err(DatetimeParseError {});
             }
 
@@ -497,6 +856,399 @@ fn digit(chars: &mut str::Chars<'_>) -> Result<u8, DatetimeParseError> {
     }
 }
 
+#[cfg(feature = "chrono")]
+impl TryFrom<Date> for chrono::NaiveDate {
+    type Error = DatetimeConversionError;
+
+    fn try_from(date: Date) -> Result<Self, Self::Error> {
+        chrono::NaiveDate::from_ymd_opt(
+            i32::from(date.year),
+            u32::from(date.month),
+            u32::from(date.day),
+        )
+        .ok_or(DatetimeConversionError::new("invalid date"))
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl TryFrom<Time> for chrono::NaiveTime {
+    type Error = DatetimeConversionError;
+
+    fn try_from(time: Time) -> Result<Self, Self::Error> {
+        // chrono represents a leap second as a nanosecond overflow of the preceding second
+        let (second, nanosecond) = if time.second == 60 {
+            (59, time.nanosecond.checked_add(1_000_000_000))
+        } else {
+            (time.second, Some(time.nanosecond))
+        };
+        nanosecond
+            .and_then(|nanosecond| {
+                chrono::NaiveTime::from_hms_nano_opt(
+                    u32::from(time.hour),
+                    u32::from(time.minute),
+                    u32::from(second),
+                    nanosecond,
+                )
+            })
+            .ok_or(DatetimeConversionError::new("invalid time"))
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl TryFrom<Datetime> for chrono::NaiveDate {
+    type Error = DatetimeConversionError;
+
+    fn try_from(datetime: Datetime) -> Result<Self, Self::Error> {
+        match datetime {
+            Datetime {
+                date: Some(date),
+                time: None,
+                offset: None,
+            } => date.try_into(),
+            _ => Err(DatetimeConversionError::new("expected a local date")),
+        }
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl TryFrom<Datetime> for chrono::NaiveTime {
+    type Error = DatetimeConversionError;
+
+    fn try_from(datetime: Datetime) -> Result<Self, Self::Error> {
+        match datetime {
+            Datetime {
+                date: None,
+                time: Some(time),
+                offset: None,
+            } => time.try_into(),
+            _ => Err(DatetimeConversionError::new("expected a local time")),
+        }
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl TryFrom<Datetime> for chrono::NaiveDateTime {
+    type Error = DatetimeConversionError;
+
+    fn try_from(datetime: Datetime) -> Result<Self, Self::Error> {
+        match datetime {
+            Datetime {
+                date: Some(date),
+                time: Some(time),
+                offset: None,
+            } => Ok(chrono::NaiveDateTime::new(
+                date.try_into()?,
+                time.try_into()?,
+            )),
+            _ => Err(DatetimeConversionError::new("expected a local datetime")),
+        }
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl TryFrom<Datetime> for chrono::DateTime<chrono::FixedOffset> {
+    type Error = DatetimeConversionError;
+
+    fn try_from(datetime: Datetime) -> Result<Self, Self::Error> {
+        use chrono::TimeZone as _;
+
+        match datetime {
+            Datetime {
+                date: Some(date),
+                time: Some(time),
+                offset: Some(offset),
+            } => {
+                let minutes = match offset {
+                    Offset::Z => 0,
+                    Offset::Custom { minutes } => i32::from(minutes),
+                };
+                let offset = chrono::FixedOffset::east_opt(minutes * 60)
+                    .ok_or(DatetimeConversionError::new("invalid offset"))?;
+                let local = chrono::NaiveDateTime::new(date.try_into()?, time.try_into()?);
+                offset
+                    .from_local_datetime(&local)
+                    .single()
+                    .ok_or(DatetimeConversionError::new("invalid datetime"))
+            }
+            _ => Err(DatetimeConversionError::new("expected an offset datetime")),
+        }
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl TryFrom<chrono::NaiveDate> for Date {
+    type Error = DatetimeConversionError;
+
+    fn try_from(date: chrono::NaiveDate) -> Result<Self, Self::Error> {
+        use chrono::Datelike as _;
+
+        let year = u16::try_from(date.year())
+            .ok()
+            .filter(|year| *year <= 9999)
+            .ok_or(DatetimeConversionError::new("year is out of range"))?;
+        Ok(Date {
+            year,
+            month: date.month() as u8,
+            day: date.day() as u8,
+        })
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl From<chrono::NaiveTime> for Time {
+    fn from(time: chrono::NaiveTime) -> Self {
+        use chrono::Timelike as _;
+
+        let (second, nanosecond) = if time.nanosecond() >= 1_000_000_000 {
+            (60, time.nanosecond() - 1_000_000_000)
+        } else {
+            (time.second() as u8, time.nanosecond())
+        };
+        Time {
+            hour: time.hour() as u8,
+            minute: time.minute() as u8,
+            second,
+            nanosecond,
+        }
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl TryFrom<chrono::NaiveDate> for Datetime {
+    type Error = DatetimeConversionError;
+
+    fn try_from(date: chrono::NaiveDate) -> Result<Self, Self::Error> {
+        Ok(Date::try_from(date)?.into())
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl From<chrono::NaiveTime> for Datetime {
+    fn from(time: chrono::NaiveTime) -> Self {
+        Time::from(time).into()
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl TryFrom<chrono::NaiveDateTime> for Datetime {
+    type Error = DatetimeConversionError;
+
+    fn try_from(datetime: chrono::NaiveDateTime) -> Result<Self, Self::Error> {
+        Ok(Datetime {
+            date: Some(datetime.date().try_into()?),
+            time: Some(datetime.time().into()),
+            offset: None,
+        })
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl TryFrom<chrono::DateTime<chrono::FixedOffset>> for Datetime {
+    type Error = DatetimeConversionError;
+
+    fn try_from(datetime: chrono::DateTime<chrono::FixedOffset>) -> Result<Self, Self::Error> {
+        let seconds = datetime.offset().local_minus_utc();
+        if seconds % 60 != 0 {
+            return Err(DatetimeConversionError::new(
+                "offset is not a whole number of minutes",
+            ));
+        }
+        let mut converted = Datetime::try_from(datetime.naive_local())?;
+        converted.offset = Some(Offset::Custom {
+            minutes: (seconds / 60) as i16,
+        });
+        Ok(converted)
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl TryFrom<chrono::DateTime<chrono::Utc>> for Datetime {
+    type Error = DatetimeConversionError;
+
+    fn try_from(datetime: chrono::DateTime<chrono::Utc>) -> Result<Self, Self::Error> {
+        let mut converted = Datetime::try_from(datetime.naive_utc())?;
+        converted.offset = Some(Offset::Z);
+        Ok(converted)
+    }
+}
+
+#[cfg(feature = "time")]
+impl TryFrom<Date> for time::Date {
+    type Error = DatetimeConversionError;
+
+    fn try_from(date: Date) -> Result<Self, Self::Error> {
+        time::Month::try_from(date.month)
+            .and_then(|month| time::Date::from_calendar_date(i32::from(date.year), month, date.day))
+            .map_err(|_| DatetimeConversionError::new("invalid date"))
+    }
+}
+
+#[cfg(feature = "time")]
+impl TryFrom<Time> for time::Time {
+    type Error = DatetimeConversionError;
+
+    fn try_from(time: Time) -> Result<Self, Self::Error> {
+        // Leap seconds are not supported by `time`
+        time::Time::from_hms_nano(time.hour, time.minute, time.second, time.nanosecond)
+            .map_err(|_| DatetimeConversionError::new("invalid time"))
+    }
+}
+
+#[cfg(feature = "time")]
+impl TryFrom<Datetime> for time::Date {
+    type Error = DatetimeConversionError;
+
+    fn try_from(datetime: Datetime) -> Result<Self, Self::Error> {
+        match datetime {
+            Datetime {
+                date: Some(date),
+                time: None,
+                offset: None,
+            } => date.try_into(),
+            _ => Err(DatetimeConversionError::new("expected a local date")),
+        }
+    }
+}
+
+#[cfg(feature = "time")]
+impl TryFrom<Datetime> for time::Time {
+    type Error = DatetimeConversionError;
+
+    fn try_from(datetime: Datetime) -> Result<Self, Self::Error> {
+        match datetime {
+            Datetime {
+                date: None,
+                time: Some(time),
+                offset: None,
+            } => time.try_into(),
+            _ => Err(DatetimeConversionError::new("expected a local time")),
+        }
+    }
+}
+
+#[cfg(feature = "time")]
+impl TryFrom<Datetime> for time::PrimitiveDateTime {
+    type Error = DatetimeConversionError;
+
+    fn try_from(datetime: Datetime) -> Result<Self, Self::Error> {
+        match datetime {
+            Datetime {
+                date: Some(date),
+                time: Some(time),
+                offset: None,
+            } => Ok(time::PrimitiveDateTime::new(
+                date.try_into()?,
+                time.try_into()?,
+            )),
+            _ => Err(DatetimeConversionError::new("expected a local datetime")),
+        }
+    }
+}
+
+#[cfg(feature = "time")]
+impl TryFrom<Datetime> for time::OffsetDateTime {
+    type Error = DatetimeConversionError;
+
+    fn try_from(datetime: Datetime) -> Result<Self, Self::Error> {
+        match datetime {
+            Datetime {
+                date: Some(date),
+                time: Some(time),
+                offset: Some(offset),
+            } => {
+                let minutes = match offset {
+                    Offset::Z => 0,
+                    Offset::Custom { minutes } => i32::from(minutes),
+                };
+                let offset = time::UtcOffset::from_whole_seconds(minutes * 60)
+                    .map_err(|_| DatetimeConversionError::new("invalid offset"))?;
+                let local = time::PrimitiveDateTime::new(date.try_into()?, time.try_into()?);
+                Ok(local.assume_offset(offset))
+            }
+            _ => Err(DatetimeConversionError::new("expected an offset datetime")),
+        }
+    }
+}
+
+#[cfg(feature = "time")]
+impl TryFrom<time::Date> for Date {
+    type Error = DatetimeConversionError;
+
+    fn try_from(date: time::Date) -> Result<Self, Self::Error> {
+        let year = u16::try_from(date.year())
+            .ok()
+            .filter(|year| *year <= 9999)
+            .ok_or(DatetimeConversionError::new("year is out of range"))?;
+        Ok(Date {
+            year,
+            month: u8::from(date.month()),
+            day: date.day(),
+        })
+    }
+}
+
+#[cfg(feature = "time")]
+impl From<time::Time> for Time {
+    fn from(time: time::Time) -> Self {
+        Time {
+            hour: time.hour(),
+            minute: time.minute(),
+            second: time.second(),
+            nanosecond: time.nanosecond(),
+        }
+    }
+}
+
+#[cfg(feature = "time")]
+impl TryFrom<time::Date> for Datetime {
+    type Error = DatetimeConversionError;
+
+    fn try_from(date: time::Date) -> Result<Self, Self::Error> {
+        Ok(Date::try_from(date)?.into())
+    }
+}
+
+#[cfg(feature = "time")]
+impl From<time::Time> for Datetime {
+    fn from(time: time::Time) -> Self {
+        Time::from(time).into()
+    }
+}
+
+#[cfg(feature = "time")]
+impl TryFrom<time::PrimitiveDateTime> for Datetime {
+    type Error = DatetimeConversionError;
+
+    fn try_from(datetime: time::PrimitiveDateTime) -> Result<Self, Self::Error> {
+        Ok(Datetime {
+            date: Some(datetime.date().try_into()?),
+            time: Some(datetime.time().into()),
+            offset: None,
+        })
+    }
+}
+
+#[cfg(feature = "time")]
+impl TryFrom<time::OffsetDateTime> for Datetime {
+    type Error = DatetimeConversionError;
+
+    fn try_from(datetime: time::OffsetDateTime) -> Result<Self, Self::Error> {
+        let seconds = datetime.offset().whole_seconds();
+        if seconds % 60 != 0 {
+            return Err(DatetimeConversionError::new(
+                "offset is not a whole number of minutes",
+            ));
+        }
+        Ok(Datetime {
+            date: Some(datetime.date().try_into()?),
+            time: Some(datetime.time().into()),
+            offset: Some(Offset::Custom {
+                minutes: (seconds / 60) as i16,
+            }),
+        })
+    }
+}
+
 #[cfg(feature = "serde")]
 impl ser::Serialize for Datetime {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
@@ -682,3 +1434,21 @@ impl fmt::Display for DatetimeParseError {
 }
 
 impl error::Error for DatetimeParseError {}
+
+impl fmt::Display for DatetimeValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid datetime: {}", self.reason)
+    }
+}
+
+impl error::Error for DatetimeValidationError {}
+
+#[cfg(any(feature = "chrono", feature = "time"))]
+impl fmt::Display for DatetimeConversionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "failed to convert datetime: {}", self.reason)
+    }
+}
+
+#[cfg(any(feature = "chrono", feature = "time"))]
+impl error::Error for DatetimeConversionError {}
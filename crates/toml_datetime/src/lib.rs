@@ -16,7 +16,11 @@ mod datetime;
 
 pub use crate::datetime::Date;
 pub use crate::datetime::Datetime;
+pub use crate::datetime::DatetimeBuilder;
+#[cfg(any(feature = "chrono", feature = "time"))]
+pub use crate::datetime::DatetimeConversionError;
 pub use crate::datetime::DatetimeParseError;
+pub use crate::datetime::DatetimeValidationError;
 pub use crate::datetime::Offset;
 pub use crate::datetime::Time;
 
@@ -0,0 +1,117 @@
+#![cfg(feature = "chrono")]
+
+use chrono::{DateTime, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime, Timelike as _, Utc};
+use toml_datetime::Datetime;
+
+fn datetime(raw: &str) -> Datetime {
+    raw.parse().unwrap()
+}
+
+#[test]
+fn round_trip() {
+    for raw in [
+        "1979-05-27",
+        "07:32:00.999999",
+        "1979-05-27T07:32:00",
+        "1979-05-27T00:32:00.5-07:00",
+        "1979-05-27T07:32:00+05:30",
+    ] {
+        let dt = datetime(raw);
+        let converted = match (dt.date, dt.time, dt.offset) {
+            (Some(_), None, None) => Datetime::try_from(NaiveDate::try_from(dt).unwrap()),
+            (None, Some(_), None) => Ok(Datetime::from(NaiveTime::try_from(dt).unwrap())),
+            (Some(_), Some(_), None) => Datetime::try_from(NaiveDateTime::try_from(dt).unwrap()),
+            _ => Datetime::try_from(DateTime::<FixedOffset>::try_from(dt).unwrap()),
+        };
+        assert_eq!(converted.unwrap(), dt, "{raw}");
+    }
+}
+
+#[test]
+fn utc() {
+    let utc = DateTime::<Utc>::from_naive_utc_and_offset(
+        NaiveDate::from_ymd_opt(1979, 5, 27)
+            .unwrap()
+            .and_hms_opt(7, 32, 0)
+            .unwrap(),
+        Utc,
+    );
+    assert_eq!(
+        Datetime::try_from(utc).unwrap(),
+        datetime("1979-05-27T07:32:00Z")
+    );
+
+    let zulu = DateTime::<FixedOffset>::try_from(datetime("1979-05-27T07:32:00Z")).unwrap();
+    assert_eq!(zulu.with_timezone(&Utc), utc);
+}
+
+#[test]
+fn leap_second() {
+    let dt = datetime("1990-12-31T23:59:60");
+    let naive = NaiveDateTime::try_from(dt).unwrap();
+    assert_eq!(naive.second(), 59);
+    assert_eq!(naive.nanosecond(), 1_000_000_000);
+    assert_eq!(Datetime::try_from(naive).unwrap(), dt);
+
+    let time = datetime("23:59:60.5");
+    let naive = NaiveTime::try_from(time).unwrap();
+    assert_eq!(naive.nanosecond(), 1_500_000_000);
+    assert_eq!(Datetime::from(naive), time);
+}
+
+#[test]
+fn mismatched_kinds() {
+    let err = NaiveDate::try_from(datetime("1979-05-27T07:32:00")).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "failed to convert datetime: expected a local date"
+    );
+    let err = NaiveTime::try_from(datetime("1979-05-27")).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "failed to convert datetime: expected a local time"
+    );
+    let err = NaiveDateTime::try_from(datetime("1979-05-27T07:32:00Z")).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "failed to convert datetime: expected a local datetime"
+    );
+    let err = DateTime::<FixedOffset>::try_from(datetime("1979-05-27T07:32:00")).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "failed to convert datetime: expected an offset datetime"
+    );
+}
+
+#[test]
+fn out_of_range() {
+    let mut dt = datetime("1979-05-27");
+    dt.date.as_mut().unwrap().month = 13;
+    let err = NaiveDate::try_from(dt).unwrap_err();
+    assert_eq!(err.to_string(), "failed to convert datetime: invalid date");
+
+    let mut dt = datetime("07:32:00");
+    dt.time.as_mut().unwrap().second = 61;
+    let err = NaiveTime::try_from(dt).unwrap_err();
+    assert_eq!(err.to_string(), "failed to convert datetime: invalid time");
+
+    let date = NaiveDate::from_ymd_opt(10000, 1, 1).unwrap();
+    let err = Datetime::try_from(date).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "failed to convert datetime: year is out of range"
+    );
+
+    let offset = FixedOffset::east_opt(30).unwrap();
+    let odd = NaiveDate::from_ymd_opt(1979, 5, 27)
+        .unwrap()
+        .and_hms_opt(7, 32, 0)
+        .unwrap()
+        .and_local_timezone(offset)
+        .unwrap();
+    let err = Datetime::try_from(odd).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "failed to convert datetime: offset is not a whole number of minutes"
+    );
+}
@@ -0,0 +1,2 @@
+mod chrono_conversions;
+mod time_conversions;
@@ -0,0 +1,97 @@
+#![cfg(feature = "time")]
+
+use time::{Date, OffsetDateTime, PrimitiveDateTime, Time, UtcOffset};
+use toml_datetime::Datetime;
+
+fn datetime(raw: &str) -> Datetime {
+    raw.parse().unwrap()
+}
+
+#[test]
+fn round_trip() {
+    for raw in [
+        "1979-05-27",
+        "07:32:00.999999",
+        "1979-05-27T07:32:00",
+        "1979-05-27T00:32:00.5-07:00",
+        "1979-05-27T07:32:00+05:30",
+    ] {
+        let dt = datetime(raw);
+        let converted = match (dt.date, dt.time, dt.offset) {
+            (Some(_), None, None) => Datetime::try_from(Date::try_from(dt).unwrap()),
+            (None, Some(_), None) => Ok(Datetime::from(Time::try_from(dt).unwrap())),
+            (Some(_), Some(_), None) => {
+                Datetime::try_from(PrimitiveDateTime::try_from(dt).unwrap())
+            }
+            _ => Datetime::try_from(OffsetDateTime::try_from(dt).unwrap()),
+        };
+        assert_eq!(converted.unwrap(), dt, "{raw}");
+    }
+}
+
+#[test]
+fn utc() {
+    let zulu = OffsetDateTime::try_from(datetime("1979-05-27T07:32:00Z")).unwrap();
+    assert_eq!(zulu.offset(), UtcOffset::UTC);
+}
+
+#[test]
+fn leap_second() {
+    let err = PrimitiveDateTime::try_from(datetime("1990-12-31T23:59:60")).unwrap_err();
+    assert_eq!(err.to_string(), "failed to convert datetime: invalid time");
+    let err = Time::try_from(datetime("23:59:60")).unwrap_err();
+    assert_eq!(err.to_string(), "failed to convert datetime: invalid time");
+}
+
+#[test]
+fn mismatched_kinds() {
+    let err = Date::try_from(datetime("1979-05-27T07:32:00")).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "failed to convert datetime: expected a local date"
+    );
+    let err = Time::try_from(datetime("1979-05-27")).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "failed to convert datetime: expected a local time"
+    );
+    let err = PrimitiveDateTime::try_from(datetime("1979-05-27T07:32:00Z")).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "failed to convert datetime: expected a local datetime"
+    );
+    let err = OffsetDateTime::try_from(datetime("1979-05-27T07:32:00")).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "failed to convert datetime: expected an offset datetime"
+    );
+}
+
+#[test]
+fn out_of_range() {
+    let mut dt = datetime("1979-05-27");
+    dt.date.as_mut().unwrap().month = 13;
+    let err = Date::try_from(dt).unwrap_err();
+    assert_eq!(err.to_string(), "failed to convert datetime: invalid date");
+
+    let mut dt = datetime("1979-05-27T07:32:00Z");
+    dt.offset = Some(toml_datetime::Offset::Custom { minutes: 26 * 60 });
+    let err = OffsetDateTime::try_from(dt).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "failed to convert datetime: invalid offset"
+    );
+}
+
+#[test]
+fn partial_minute_offset() {
+    let offset = UtcOffset::from_hms(0, 0, 30).unwrap();
+    let odd = PrimitiveDateTime::try_from(datetime("1979-05-27T07:32:00"))
+        .unwrap()
+        .assume_offset(offset);
+    let err = Datetime::try_from(odd).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "failed to convert datetime: offset is not a whole number of minutes"
+    );
+}
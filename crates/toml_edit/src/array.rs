@@ -1,6 +1,7 @@
 use std::iter::FromIterator;
 use std::mem;
 
+use crate::raw_string::contains_newline;
 use crate::repr::Decor;
 use crate::value::{DEFAULT_LEADING_VALUE_DECOR, DEFAULT_VALUE_DECOR};
 use crate::{Item, RawString, Value};
@@ -26,6 +27,13 @@ pub type ArrayIntoIter = Box<dyn Iterator<Item = Value>>;
 pub type ArrayIter<'a> = Box<dyn Iterator<Item = &'a Value> + 'a>;
 /// An iterator type over `Array`'s values.
 pub type ArrayIterMut<'a> = Box<dyn Iterator<Item = &'a mut Value> + 'a>;
+/// An iterator type over `Array`'s values along with their prefix and suffix.
+pub type ArrayIterWithDecor<'a> =
+    Box<dyn Iterator<Item = (&'a RawString, &'a Value, &'a RawString)> + 'a>;
+
+static LEADING_VALUE_PREFIX: RawString = RawString::from_static(DEFAULT_LEADING_VALUE_DECOR.0);
+static VALUE_PREFIX: RawString = RawString::from_static(DEFAULT_VALUE_DECOR.0);
+static VALUE_SUFFIX: RawString = RawString::from_static(DEFAULT_VALUE_DECOR.1);
 
 /// Constructors
 ///
@@ -42,6 +50,19 @@ impl Array {
         Default::default()
     }
 
+    /// Creates an empty array with room for at least `capacity` values
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// let mut arr = toml_edit::Array::with_capacity(3);
+    /// arr.extend([1, 2, 3]);
+    /// assert_eq!(arr.len(), 3);
+    /// ```
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self::with_vec(Vec::with_capacity(capacity))
+    }
+
     pub(crate) fn with_vec(values: Vec<Item>) -> Self {
         Self {
             values,
@@ -57,6 +78,115 @@ impl Array {
         decorate_array(self);
     }
 
+    /// Whether the array is written across multiple lines
+    ///
+    /// That is, whether a value or the closing bracket starts a new line.
+    pub fn is_multiline(&self) -> bool {
+        self.iter()
+            .any(|value| contains_newline(value.decor().prefix()))
+            || contains_newline(Some(&self.trailing))
+    }
+
+    /// Write the array with one value per line, or on a single line
+    ///
+    /// Wrapped values are indented one level deeper than the line the array starts on and
+    /// followed by a trailing comma; an array that is already multi-line is left as-is.  That
+    /// line's indentation is taken from the array's decor, as for an array nested in a
+    /// multi-line array, and used as the width of a level, or 4 spaces when it has none; see
+    /// [`Array::set_multiline_with`] to choose the width.  Unwrapping is the same as
+    /// [`Array::fmt`], dropping any comments between the values.  To indent by nesting depth,
+    /// see [`ToStringOptions::indent`][crate::ToStringOptions::indent].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "display")] {
+    /// let mut arr = toml_edit::Array::from_iter(["serde", "derive"]);
+    /// arr.set_multiline(true);
+    /// assert!(arr.is_multiline());
+    /// assert_eq!(arr.to_string(), "[\n    \"serde\",\n    \"derive\",\n]");
+    ///
+    /// arr.set_multiline(false);
+    /// assert_eq!(arr.to_string(), "[\"serde\", \"derive\"]");
+    /// # }
+    /// ```
+    pub fn set_multiline(&mut self, yes: bool) {
+        if !yes {
+            self.fmt();
+            return;
+        }
+        let level = match self.line_indent() {
+            "" => "    ".to_owned(),
+            indent => indent.to_owned(),
+        };
+        self.wrap_with(&level);
+    }
+
+    /// Write the array with one value per line, indented `width` spaces deeper than the line
+    /// the array starts on
+    ///
+    /// See [`Array::set_multiline`], which picks the width from the array's decor.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "display")] {
+    /// let mut arr = toml_edit::Array::from_iter([1, 2]);
+    /// arr.set_multiline_with(2);
+    /// assert_eq!(arr.to_string(), "[\n  1,\n  2,\n]");
+    /// # }
+    /// ```
+    pub fn set_multiline_with(&mut self, width: usize) {
+        self.wrap_with(&" ".repeat(width));
+    }
+
+    fn wrap_with(&mut self, level: &str) {
+        if self.is_multiline() || self.is_empty() {
+            return;
+        }
+        let indent = self.line_indent().to_owned();
+        let value_indent = format!("\n{indent}{level}");
+        for value in self.iter_mut() {
+            value.decorate(value_indent.as_str(), DEFAULT_MULTILINE_VALUE_DECOR.1);
+        }
+        self.set_trailing_comma(true);
+        self.set_trailing(format!("\n{indent}"));
+    }
+
+    /// The indentation of the line the array starts on, as far as its decor tells
+    fn line_indent(&self) -> &str {
+        self.decor()
+            .prefix()
+            .and_then(RawString::as_str)
+            .and_then(|prefix| prefix.rfind('\n').map(|i| &prefix[i + 1..]))
+            .filter(|indent| indent.chars().all(|c| c == ' ' || c == '\t'))
+            .unwrap_or("")
+    }
+
+    /// Wrap the array, one value per line, if it is longer than `max` characters on a single line
+    ///
+    /// Returns whether the array was wrapped; see [`Array::set_multiline`].  Only the array
+    /// itself is measured, so to fit a line of `key = [..]`, leave the width of the key and
+    /// `" = "` out of `max`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut arr = toml_edit::Array::from_iter([1, 2, 3]);
+    /// assert!(!arr.wrap_to_width(9));
+    /// assert!(arr.wrap_to_width(8));
+    /// assert!(arr.is_multiline());
+    /// ```
+    #[cfg(feature = "display")]
+    pub fn wrap_to_width(&mut self, max: usize) -> bool {
+        if self.is_multiline() || self.is_empty() || self.to_string().trim().chars().count() <= max
+        {
+            return false;
+        }
+        self.set_multiline(true);
+        true
+    }
+
     /// Set whether the array will use a trailing comma
     pub fn set_trailing_comma(&mut self, yes: bool) {
         self.trailing_comma = yes;
@@ -77,6 +207,97 @@ impl Array {
         &self.trailing
     }
 
+    /// Returns the comment following the value at `index` on its line, stripped of its `#` and
+    /// surrounding whitespace
+    ///
+    /// This generally requires a [`DocumentMut`][crate::DocumentMut].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "parse")] {
+    /// let doc = "a = [\n  1, # first\n  2,\n]\n".parse::<toml_edit::DocumentMut>().unwrap();
+    /// let a = doc["a"].as_array().unwrap();
+    /// assert_eq!(a.trailing_comment(0), Some("first"));
+    /// assert_eq!(a.trailing_comment(1), None);
+    /// # }
+    /// ```
+    pub fn trailing_comment(&self, index: usize) -> Option<&str> {
+        let value = self.values.get(index)?.as_value()?;
+        let suffix = value.decor().suffix();
+        let raw = if contains_newline(suffix) {
+            suffix
+        } else if let Some(next) = self.values.get(index + 1) {
+            next.as_value()?.decor().prefix()
+        } else if self.trailing_comma {
+            Some(&self.trailing)
+        } else {
+            suffix
+        };
+        raw.and_then(crate::repr::line_comment)
+    }
+
+    /// Replaces or, with `None`, removes the comment following the value at `index` on its line
+    ///
+    /// The comment is placed after the comma separating the value from the next one, starting a
+    /// new line if needed.  A trailing comma is added when the last value is not already followed
+    /// by a line break.
+    ///
+    /// # Panics
+    ///
+    /// If `index` is out of bounds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "display")] {
+    /// let mut a = toml_edit::Array::from_iter([1, 2]);
+    /// a.set_trailing_comment(0, Some("first"));
+    /// a.set_trailing_comment(1, Some("second"));
+    /// assert_eq!(a.to_string(), "[1, # first\n2, # second\n]");
+    /// # }
+    /// ```
+    pub fn set_trailing_comment(&mut self, index: usize, comment: Option<&str>) {
+        let len = self.len();
+        assert!(
+            index < len,
+            "index out of bounds: the len is {len} but the index is {index}"
+        );
+        let indent = self.values[index]
+            .as_value()
+            .and_then(|value| value.decor().prefix())
+            .and_then(RawString::as_str)
+            .and_then(|prefix| prefix.rfind('\n').map(|i| prefix[i..].to_owned()))
+            .unwrap_or_else(|| "\n".to_owned());
+        let suffix = self.values[index]
+            .as_value()
+            .and_then(|value| value.decor().suffix())
+            .cloned();
+        let slot = if contains_newline(suffix.as_ref()) {
+            self.values[index].as_value_mut().map(|value| {
+                let decor = value.decor_mut();
+                let raw = decor.suffix().cloned().unwrap_or_default();
+                decor.set_suffix(crate::repr::set_line_comment(&raw, comment, None));
+            })
+        } else if index + 1 < len {
+            self.values[index + 1].as_value_mut().map(|value| {
+                let decor = value.decor_mut();
+                let raw = decor.prefix().cloned().unwrap_or_else(|| " ".into());
+                decor.set_prefix(crate::repr::set_line_comment(&raw, comment, Some(&indent)));
+            })
+        } else {
+            None
+        };
+        if slot.is_none() && index + 1 == len {
+            if comment.is_some() {
+                self.trailing_comma = true;
+            }
+            if self.trailing_comma {
+                self.trailing = crate::repr::set_line_comment(&self.trailing, comment, Some("\n"));
+            }
+        }
+    }
+
     /// Returns the surrounding whitespace
     pub fn decor_mut(&mut self) -> &mut Decor {
         &mut self.decor
@@ -115,6 +336,42 @@ impl Array {
         Box::new(self.values.iter_mut().filter_map(Item::as_value_mut))
     }
 
+    /// Returns an iterator over all values, each with the whitespace and comments around it.
+    ///
+    /// Yields the prefix between the preceding `[` or `,` and the value, the value itself, and the
+    /// suffix up to the following `,` or `]`.  Decor that hasn't been set is reported as what gets
+    /// rendered in its place.  To rewrite the decor in a single pass, use [`Array::iter_mut`] with
+    /// [`Value::decor_mut`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # #[cfg(feature = "parse")] {
+    /// let doc = "a = [\n  1, # one\n  2,\n]".parse::<toml_edit::DocumentMut>().unwrap();
+    /// let array = doc["a"].as_array().unwrap();
+    /// let decor = array
+    ///     .iter_with_decor()
+    ///     .map(|(prefix, _, suffix)| (prefix.as_str().unwrap(), suffix.as_str().unwrap()))
+    ///     .collect::<Vec<_>>();
+    /// assert_eq!(decor, [("\n  ", ""), (" # one\n  ", "")]);
+    /// # }
+    /// ```
+    pub fn iter_with_decor(&self) -> ArrayIterWithDecor<'_> {
+        Box::new(self.iter().enumerate().map(|(i, value)| {
+            let default_prefix = if i == 0 {
+                &LEADING_VALUE_PREFIX
+            } else {
+                &VALUE_PREFIX
+            };
+            let decor = value.decor();
+            (
+                decor.prefix().unwrap_or(default_prefix),
+                value,
+                decor.suffix().unwrap_or(&VALUE_SUFFIX),
+            )
+        }))
+    }
+
     /// Returns the length of the underlying Vec.
     ///
     /// In some rare cases, placeholder elements will exist.  For a more accurate count, call
@@ -153,6 +410,11 @@ impl Array {
         self.values.clear();
     }
 
+    /// Reserves room for at least `additional` more values
+    pub fn reserve(&mut self, additional: usize) {
+        self.values.reserve(additional);
+    }
+
     /// Returns a reference to the value at the given index, or `None` if the index is out of
     /// bounds.
     pub fn get(&self, index: usize) -> Option<&Value> {
@@ -180,23 +442,37 @@ impl Array {
         });
     }
 
-    /// Appends a new, already formatted value to the end of the array.
+    /// Appends a new, already formatted value to the end of the array, returning a mutable
+    /// reference to it for further formatting.
     ///
     /// # Examples
     ///
     /// ```rust
     /// # #[cfg(feature = "parse")] {
+    /// # #[cfg(feature = "display")] {
     /// let formatted_value = "'literal'".parse::<toml_edit::Value>().unwrap();
     /// let mut arr = toml_edit::Array::new();
-    /// arr.push_formatted(formatted_value);
+    /// arr.push_formatted(formatted_value)
+    ///     .decor_mut()
+    ///     .set_prefix("\n    ");
+    /// arr.set_trailing("\n");
+    /// assert_eq!(arr.to_string(), "[\n    'literal'\n]");
+    /// # }
     /// # }
     /// ```
-    pub fn push_formatted(&mut self, v: Value) {
+    pub fn push_formatted(&mut self, v: Value) -> &mut Value {
         self.values.push(Item::Value(v));
+        self.values
+            .last_mut()
+            .and_then(Item::as_value_mut)
+            .expect("just pushed a value")
     }
 
-    /// Inserts an element at the given position within the array, applying default formatting to
-    /// it and shifting all values after it to the right.
+    /// Inserts an element at the given position within the array, laying it out like its
+    /// neighbours and shifting all values after it to the right.
+    ///
+    /// In a multi-line array, the value goes on its own line with the indentation of the values
+    /// around it.
     ///
     /// # Panics
     ///
@@ -205,21 +481,29 @@ impl Array {
     /// # Examples
     ///
     /// ```rust
-    /// let mut arr = toml_edit::Array::new();
-    /// arr.push(1);
-    /// arr.push("foo");
-    ///
+    /// # #[cfg(feature = "parse")] {
+    /// # #[cfg(feature = "display")] {
+    /// let mut doc = "a = [\n  1,\n  2\n]\n".parse::<toml_edit::DocumentMut>().unwrap();
+    /// let arr = doc["a"].as_array_mut().unwrap();
     /// arr.insert(0, "start");
+    /// arr.insert(3, "end");
+    /// assert_eq!(doc.to_string(), "a = [\n  \"start\",\n  1,\n  2,\n  \"end\"\n]\n");
+    /// # }
+    /// # }
     /// ```
     pub fn insert<V: Into<Value>>(&mut self, index: usize, v: V) {
-        self.value_op(v.into(), true, |items, value| {
-            items.insert(index, Item::Value(value));
-        });
+        let mut value = v.into();
+        value.decor_mut().clear();
+        self.insert_formatted(index, value);
     }
 
     /// Inserts an already formatted value at the given position within the array, shifting all
     /// values after it to the right.
     ///
+    /// Only a prefix or suffix left unset on the value is filled in from its neighbours.  The
+    /// neighbours are adjusted so no whitespace meant for the start or end of the array ends up
+    /// next to a comma.
+    ///
     /// # Panics
     ///
     /// Panics if `index > len`.
@@ -228,16 +512,77 @@ impl Array {
     ///
     /// ```rust
     /// # #[cfg(feature = "parse")] {
+    /// # #[cfg(feature = "display")] {
     /// let mut arr = toml_edit::Array::new();
     /// arr.push(1);
     /// arr.push("foo");
     ///
     /// let formatted_value = "'start'".parse::<toml_edit::Value>().unwrap();
     /// arr.insert_formatted(0, formatted_value);
+    /// assert_eq!(arr.to_string(), "['start', 1, \"foo\"]");
+    /// # }
     /// # }
     /// ```
     pub fn insert_formatted(&mut self, index: usize, v: Value) {
         self.values.insert(index, Item::Value(v));
+        self.repair_inserted(index);
+    }
+
+    /// Lay out the value at `index` like its neighbours, after inserting it
+    fn repair_inserted(&mut self, index: usize) {
+        let len = self.values.len();
+        if len == 1 {
+            return;
+        }
+        let neighbour = if index == 0 { 1 } else { index - 1 };
+        let neighbour_prefix = self.values[neighbour]
+            .as_value()
+            .and_then(|value| value.decor().prefix())
+            .cloned();
+        let multiline = contains_newline(neighbour_prefix.as_ref());
+
+        let prefix = if multiline {
+            neighbour_prefix
+                .as_ref()
+                .and_then(RawString::as_str)
+                .and_then(|prefix| prefix.rfind('\n').map(|i| RawString::from(&prefix[i..])))
+        } else if index == 0 {
+            // The old first value now follows a comma
+            if neighbour_prefix.is_some() {
+                if let Some(first) = self.values[1].as_value_mut() {
+                    first.decor_mut().set_prefix(DEFAULT_VALUE_DECOR.0);
+                }
+            }
+            neighbour_prefix
+        } else {
+            Some(DEFAULT_VALUE_DECOR.0.into())
+        };
+        let suffix = if index + 1 == len && !self.trailing_comma {
+            // Whitespace before the `]` moves along with the end of the array
+            self.values[index - 1].as_value_mut().and_then(|last| {
+                let suffix = last.decor().suffix()?.as_str()?;
+                if suffix.is_empty() || !suffix.trim().is_empty() {
+                    return None;
+                }
+                let suffix = suffix.to_owned();
+                last.decor_mut().clear_suffix();
+                Some(suffix)
+            })
+        } else {
+            None
+        };
+
+        let decor = self.values[index]
+            .as_value_mut()
+            .expect("just inserted a value")
+            .decor_mut();
+        if let (None, Some(prefix)) = (decor.prefix(), prefix) {
+            decor.set_prefix(prefix);
+        }
+        if let Some(closing) = suffix {
+            let suffix = decor.suffix().and_then(RawString::as_str).unwrap_or("");
+            decor.set_suffix(format!("{suffix}{closing}"));
+        }
     }
 
     /// Replaces the element at the given position within the array, preserving existing formatting.
@@ -293,6 +638,13 @@ impl Array {
 
     /// Removes the value at the given index.
     ///
+    /// As with [`Array::retain`], the whitespace around the first and last elements is carried
+    /// over when those are removed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index >= len`.
+    ///
     /// # Examples
     ///
     /// ```rust
@@ -304,7 +656,10 @@ impl Array {
     /// assert_eq!(arr.len(), 1);
     /// ```
     pub fn remove(&mut self, index: usize) -> Value {
+        let len = self.values.len();
+        let edges = self.edge_decor();
         let removed = self.values.remove(index);
+        self.repair_removed(edges, index == 0, index + 1 == len);
         match removed {
             Item::Value(v) => v,
             x => panic!("non-value item {x:?} in an array"),
@@ -317,12 +672,104 @@ impl Array {
     ///
     /// This method operates in place, visiting each element exactly once in the
     /// original order, and preserves the order of the retained elements.
+    ///
+    /// The formatting of retained values is preserved, with the whitespace around the first and
+    /// last elements carried over when those are removed.
     pub fn retain<F>(&mut self, mut keep: F)
     where
         F: FnMut(&Value) -> bool,
     {
-        self.values
-            .retain(|item| item.as_value().map(&mut keep).unwrap_or(false));
+        self.retain_mut(|value| keep(value));
+    }
+
+    /// Retains only the values specified by the `keep` predicate, passing a mutable reference to
+    /// it.
+    ///
+    /// In other words, remove all values for which `keep(&mut value)` returns `false`.
+    ///
+    /// This method operates in place, visiting each element exactly once in the
+    /// original order, and preserves the order of the retained elements.
+    pub fn retain_mut<F>(&mut self, mut keep: F)
+    where
+        F: FnMut(&mut Value) -> bool,
+    {
+        let len = self.values.len();
+        let edges = self.edge_decor();
+
+        let mut index = 0;
+        let mut first_kept = None;
+        let mut last_kept = None;
+        self.values.retain_mut(|item| {
+            let retain = item.as_value_mut().map(&mut keep).unwrap_or(false);
+            if retain {
+                first_kept.get_or_insert(index);
+                last_kept = Some(index);
+            }
+            index += 1;
+            retain
+        });
+
+        self.repair_removed(edges, first_kept != Some(0), last_kept != Some(len - 1));
+    }
+
+    /// The prefix of the first value and suffix of the last, which sit just inside the brackets
+    fn edge_decor(&self) -> (Option<RawString>, Option<RawString>) {
+        let leading_prefix = self
+            .values
+            .first()
+            .and_then(Item::as_value)
+            .and_then(|v| v.decor().prefix().cloned());
+        let trailing_suffix = self
+            .values
+            .last()
+            .and_then(Item::as_value)
+            .and_then(|v| v.decor().suffix().cloned());
+        (leading_prefix, trailing_suffix)
+    }
+
+    /// Carry the whitespace just inside the brackets over to the new first and last values, after
+    /// removing the old ones
+    fn repair_removed(
+        &mut self,
+        (leading_prefix, trailing_suffix): (Option<RawString>, Option<RawString>),
+        first_removed: bool,
+        last_removed: bool,
+    ) {
+        if self.values.is_empty() {
+            self.set_trailing_comma(false);
+            self.set_trailing("");
+            return;
+        }
+
+        if first_removed {
+            if let Some(first) = self.values.first_mut().and_then(Item::as_value_mut) {
+                // A prefix with comments or newlines belongs to the value, only replace spacing
+                let is_spacing = first
+                    .decor()
+                    .prefix()
+                    .map(|p| {
+                        p.as_str()
+                            .map(|p| p.trim().is_empty() && !p.contains('\n'))
+                            .unwrap_or(false)
+                    })
+                    .unwrap_or(true);
+                if is_spacing {
+                    let prefix = leading_prefix
+                        .filter(|p| p.as_str().map(|p| p.trim().is_empty()).unwrap_or(false))
+                        .unwrap_or_else(|| DEFAULT_LEADING_VALUE_DECOR.0.into());
+                    first.decor_mut().set_prefix(prefix);
+                }
+            }
+        }
+
+        if last_removed && !self.trailing_comma {
+            if let (Some(last), Some(suffix)) = (
+                self.values.last_mut().and_then(Item::as_value_mut),
+                trailing_suffix,
+            ) {
+                last.decor_mut().set_suffix(suffix);
+            }
+        }
     }
 
     /// Sorts the slice with a comparator function.
@@ -391,17 +838,35 @@ impl Array {
     }
 }
 
+#[cfg(feature = "parse")]
+impl std::str::FromStr for Array {
+    type Err = crate::TomlError;
+
+    /// Parses an array, like `[1, 2]`, from a &str
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.parse::<Value>()? {
+            Value::Array(array) => Ok(array),
+            value => Err(crate::value::unexpected_kind(
+                crate::ItemKind::Array,
+                &value,
+            )),
+        }
+    }
+}
+
 #[cfg(feature = "display")]
 impl std::fmt::Display for Array {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        crate::encode::encode_array(self, f, None, ("", ""))
+        crate::encode::encode_lf(f, |buf| {
+            crate::encode::encode_array(self, buf, None, ("", ""))
+        })
     }
 }
 
 impl<V: Into<Value>> Extend<V> for Array {
     fn extend<T: IntoIterator<Item = V>>(&mut self, iter: T) {
         for value in iter {
-            self.push_formatted(value.into());
+            self.push(value);
         }
     }
 }
@@ -442,6 +907,9 @@ impl<'s> IntoIterator for &'s Array {
     }
 }
 
+// `[\n    value,\n]`
+const DEFAULT_MULTILINE_VALUE_DECOR: (&str, &str) = ("\n    ", "");
+
 fn decorate_array(array: &mut Array) {
     for (i, value) in array
         .values
@@ -85,6 +85,9 @@ impl ArrayOfTables {
     }
 
     /// Appends a table to the array.
+    ///
+    /// Unless given a [position][Table::set_position], the table is rendered after the
+    /// preceding tables of the array.
     pub fn push(&mut self, table: Table) {
         self.values.push(Item::Table(table));
     }
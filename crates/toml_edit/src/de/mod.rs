@@ -106,6 +106,26 @@ where
 }
 
 /// Convert a [`DocumentMut`][crate::DocumentMut] into `T`.
+///
+/// The document's items are deserialized directly, without rendering it back to TOML.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "parse")] {
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// struct Config {
+///     port: u16,
+/// }
+///
+/// let mut doc = "port = 80".parse::<toml_edit::DocumentMut>().unwrap();
+/// doc["port"] = toml_edit::value(0x1F90);
+/// let config: Config = toml_edit::de::from_document(&doc).unwrap();
+/// assert_eq!(config.port, 8080);
+/// # }
+/// ```
 pub fn from_document<T>(d: impl Into<Deserializer>) -> Result<T, Error>
 where
     T: DeserializeOwned,
@@ -145,6 +165,15 @@ impl From<crate::DocumentMut> for Deserializer {
     }
 }
 
+impl From<&crate::DocumentMut> for Deserializer {
+    fn from(doc: &crate::DocumentMut) -> Self {
+        Self {
+            root: doc.root.clone(),
+            raw: None,
+        }
+    }
+}
+
 impl<S> From<crate::ImDocument<S>> for Deserializer<S> {
     fn from(doc: crate::ImDocument<S>) -> Self {
         let crate::ImDocument { root, raw, .. } = doc;
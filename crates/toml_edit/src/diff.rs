@@ -0,0 +1,386 @@
+use crate::table::TableLike;
+use crate::{DocumentMut, Item, Key, Value};
+
+/// A semantic difference between two documents, see [`diff`]
+#[derive(Debug, Clone)]
+pub enum Change {
+    /// An item only present in the new document
+    Added {
+        /// Location of the item within the new document
+        path: Vec<PathSegment>,
+        /// The added item
+        value: Box<Item>,
+    },
+    /// An item only present in the old document
+    Removed {
+        /// Location of the item within the old document
+        path: Vec<PathSegment>,
+        /// The removed item
+        old: Box<Item>,
+    },
+    /// An item present in both documents with a different value
+    Modified {
+        /// Location of the item within either document
+        path: Vec<PathSegment>,
+        /// The item in the old document
+        old: Box<Item>,
+        /// The item in the new document
+        new: Box<Item>,
+    },
+}
+
+impl Change {
+    /// Location of the change
+    pub fn path(&self) -> &[PathSegment] {
+        match self {
+            Change::Added { path, .. } => path,
+            Change::Removed { path, .. } => path,
+            Change::Modified { path, .. } => path,
+        }
+    }
+}
+
+/// A step into a table or an array, see [`Change`]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum PathSegment {
+    /// A key within a table, inline table, or dotted key
+    Key(Key),
+    /// A position within an array or array of tables
+    Index(usize),
+}
+
+/// Compare two documents, ignoring formatting
+///
+/// - Whitespace, comments, and the representation of values (e.g. `0x10` vs `16`) are ignored
+/// - Reordering keys is not reported as a change
+/// - Standard tables and inline tables, or arrays of tables and arrays of inline tables, with
+///   the same content are considered equal
+/// - Arrays are compared position by position
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "parse")] {
+/// use toml_edit::{diff, Change, DocumentMut};
+///
+/// let old = "a = 1\nb = [1, 2]\n".parse::<DocumentMut>().unwrap();
+/// let new = "b = [ 1, 3 ]  # reordered\na = 0x1\n".parse::<DocumentMut>().unwrap();
+/// let changes = diff(&old, &new);
+/// assert_eq!(changes.len(), 1);
+/// assert!(matches!(changes[0], Change::Modified { .. }));
+/// # }
+/// ```
+pub fn diff(old: &DocumentMut, new: &DocumentMut) -> Vec<Change> {
+    let mut changes = Vec::new();
+    let mut path = Vec::new();
+    diff_table_like(&mut path, old.as_table(), new.as_table(), &mut changes);
+    changes
+}
+
+fn diff_item(path: &mut Vec<PathSegment>, old: &Item, new: &Item, changes: &mut Vec<Change>) {
+    if let (Some(old), Some(new)) = (old.as_table_like(), new.as_table_like()) {
+        diff_table_like(path, old, new, changes);
+        return;
+    }
+
+    match (as_items(old), as_items(new)) {
+        (Some(old_items), Some(new_items)) => {
+            diff_items(path, &old_items, &new_items, changes);
+        }
+        _ => match (old.as_value(), new.as_value()) {
+            (Some(old_value), Some(new_value)) if value_eq(old_value, new_value) => {}
+            _ => changes.push(Change::Modified {
+                path: path.clone(),
+                old: Box::new(old.clone()),
+                new: Box::new(new.clone()),
+            }),
+        },
+    }
+}
+
+fn diff_table_like(
+    path: &mut Vec<PathSegment>,
+    old: &dyn TableLike,
+    new: &dyn TableLike,
+    changes: &mut Vec<Change>,
+) {
+    for (key, old_item) in old.iter() {
+        if old_item.is_none() {
+            continue;
+        }
+        let key = old.key(key).expect("iterated key exists");
+        path.push(PathSegment::Key(key.clone()));
+        match new.get(key).filter(|item| !item.is_none()) {
+            Some(new_item) => diff_item(path, old_item, new_item, changes),
+            None => changes.push(Change::Removed {
+                path: path.clone(),
+                old: Box::new(old_item.clone()),
+            }),
+        }
+        path.pop();
+    }
+    for (key, new_item) in new.iter() {
+        if new_item.is_none() || old.get(key).map(|i| !i.is_none()).unwrap_or(false) {
+            continue;
+        }
+        let key = new.key(key).expect("iterated key exists");
+        path.push(PathSegment::Key(key.clone()));
+        changes.push(Change::Added {
+            path: path.clone(),
+            value: Box::new(new_item.clone()),
+        });
+        path.pop();
+    }
+}
+
+fn diff_items(
+    path: &mut Vec<PathSegment>,
+    old: &[&Item],
+    new: &[&Item],
+    changes: &mut Vec<Change>,
+) {
+    for index in 0..old.len().max(new.len()) {
+        path.push(PathSegment::Index(index));
+        match (old.get(index), new.get(index)) {
+            (Some(old_item), Some(new_item)) => diff_item(path, old_item, new_item, changes),
+            (Some(old_item), None) => changes.push(Change::Removed {
+                path: path.clone(),
+                old: Box::new((*old_item).clone()),
+            }),
+            (None, Some(new_item)) => changes.push(Change::Added {
+                path: path.clone(),
+                value: Box::new((*new_item).clone()),
+            }),
+            (None, None) => unreachable!("index is within one of the arrays"),
+        }
+        path.pop();
+    }
+}
+
+impl DocumentMut {
+    /// Whether the documents hold the same values, ignoring formatting
+    ///
+    /// This is the same as [`diff`] finding no changes, without collecting them.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "parse")] {
+    /// use toml_edit::DocumentMut;
+    ///
+    /// let lhs = "# config\na = 1\n[b]\nc = [1, 2]\n".parse::<DocumentMut>().unwrap();
+    /// let rhs = "b = { c = [ 1, 2 ] }\na = 0x1\n".parse::<DocumentMut>().unwrap();
+    /// assert!(lhs.semantic_eq(&rhs));
+    ///
+    /// let other = "a = 1\nb.c = [2, 1]\n".parse::<DocumentMut>().unwrap();
+    /// assert!(!lhs.semantic_eq(&other));
+    /// # }
+    /// ```
+    pub fn semantic_eq(&self, other: &DocumentMut) -> bool {
+        table_like_eq(self.as_table(), other.as_table())
+    }
+}
+
+fn item_eq(lhs: &Item, rhs: &Item) -> bool {
+    if let (Some(lhs), Some(rhs)) = (lhs.as_table_like(), rhs.as_table_like()) {
+        return table_like_eq(lhs, rhs);
+    }
+
+    match (as_items(lhs), as_items(rhs)) {
+        (Some(lhs), Some(rhs)) => {
+            lhs.len() == rhs.len() && lhs.iter().zip(rhs.iter()).all(|(l, r)| item_eq(l, r))
+        }
+        _ => match (lhs.as_value(), rhs.as_value()) {
+            (Some(lhs), Some(rhs)) => value_eq(lhs, rhs),
+            _ => false,
+        },
+    }
+}
+
+fn table_like_eq(lhs: &dyn TableLike, rhs: &dyn TableLike) -> bool {
+    let present = |table: &dyn TableLike| table.iter().filter(|(_, i)| !i.is_none()).count();
+    present(lhs) == present(rhs)
+        && lhs
+            .iter()
+            .filter(|(_, item)| !item.is_none())
+            .all(|(key, l)| rhs.get(key).map(|r| item_eq(l, r)).unwrap_or(false))
+}
+
+fn as_items(item: &Item) -> Option<Vec<&Item>> {
+    match item {
+        Item::ArrayOfTables(a) => Some(a.values.iter().filter(|i| !i.is_none()).collect()),
+        Item::Value(Value::Array(a)) => Some(a.values.iter().filter(|i| !i.is_none()).collect()),
+        _ => None,
+    }
+}
+
+/// Compare values, ignoring formatting
+pub(crate) fn value_eq(lhs: &Value, rhs: &Value) -> bool {
+    match (lhs, rhs) {
+        (Value::String(l), Value::String(r)) => l.value() == r.value(),
+        (Value::Integer(l), Value::Integer(r)) => l.value() == r.value(),
+        (Value::Float(l), Value::Float(r)) => {
+            let (l, r) = (*l.value(), *r.value());
+            l == r || (l.is_nan() && r.is_nan())
+        }
+        (Value::Boolean(l), Value::Boolean(r)) => l.value() == r.value(),
+        (Value::Datetime(l), Value::Datetime(r)) => l.value() == r.value(),
+        (Value::Array(l), Value::Array(r)) => {
+            l.len() == r.len() && l.iter().zip(r.iter()).all(|(l, r)| value_eq(l, r))
+        }
+        (Value::InlineTable(l), Value::InlineTable(r)) => {
+            l.len() == r.len()
+                && l.iter()
+                    .all(|(key, l)| r.get(key).map(|r| value_eq(l, r)).unwrap_or(false))
+        }
+        _ => false,
+    }
+}
+
+impl DocumentMut {
+    /// A fingerprint of the values in the document, ignoring formatting and the order of keys
+    ///
+    /// Documents that are [`semantic_eq`][DocumentMut::semantic_eq] have the same fingerprint,
+    /// making it suitable for caching results derived from a document's content.  Use
+    /// [`DocumentMut::content_hash_ordered`] for the order of keys to matter.
+    ///
+    /// The fingerprint is stable across platforms and versions of this crate.  It is the 64-bit
+    /// FNV-1a hash of the following encoding, with lengths and integers as little-endian `u64`s and
+    /// `i64`s:
+    /// - Strings are `s`, the length in bytes, then the UTF-8 bytes
+    /// - Integers are `i` then the value
+    /// - Floats are `f` then the IEEE 754 bits, with `-0.0` written as `0.0` and every NaN as
+    ///   `nan`
+    /// - Booleans are `b` then `1` or `0`
+    /// - Datetimes are `d` then the datetime rendered as TOML, encoded as a string
+    /// - Arrays and arrays of tables are `a`, the number of elements, then each element
+    /// - Tables and inline tables are `t`, the number of entries, then each key, encoded as a
+    ///   string, followed by its item, in order of the keys' UTF-8 bytes
+    ///
+    /// [`Item::None`] is skipped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "parse")] {
+    /// use toml_edit::DocumentMut;
+    ///
+    /// let lhs = "# config\na = 1\n[b]\nc = [1, 2]\n".parse::<DocumentMut>().unwrap();
+    /// let rhs = "b = { c = [ 1, 2 ] }\na = 0x1\n".parse::<DocumentMut>().unwrap();
+    /// assert_eq!(lhs.content_hash(), rhs.content_hash());
+    /// assert_ne!(lhs.content_hash_ordered(), rhs.content_hash_ordered());
+    /// # }
+    /// ```
+    pub fn content_hash(&self) -> u64 {
+        let mut fingerprint = Fingerprint::new(false);
+        fingerprint.table_like(self.as_table());
+        fingerprint.state
+    }
+
+    /// A fingerprint of the values in the document, ignoring formatting
+    ///
+    /// This is the same as [`DocumentMut::content_hash`] except that the entries of tables are
+    /// encoded in the order they are iterated, so reordering keys changes the fingerprint.
+    pub fn content_hash_ordered(&self) -> u64 {
+        let mut fingerprint = Fingerprint::new(true);
+        fingerprint.table_like(self.as_table());
+        fingerprint.state
+    }
+}
+
+/// 64-bit FNV-1a over the encoding documented on [`DocumentMut::content_hash`]
+struct Fingerprint {
+    state: u64,
+    key_order: bool,
+}
+
+impl Fingerprint {
+    const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0100_0000_01b3;
+
+    fn new(key_order: bool) -> Self {
+        Self {
+            state: Self::OFFSET_BASIS,
+            key_order,
+        }
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.state ^= u64::from(*byte);
+            self.state = self.state.wrapping_mul(Self::PRIME);
+        }
+    }
+
+    fn len(&mut self, len: usize) {
+        self.write(&(len as u64).to_le_bytes());
+    }
+
+    fn str(&mut self, s: &str) {
+        self.write(b"s");
+        self.len(s.len());
+        self.write(s.as_bytes());
+    }
+
+    fn item(&mut self, item: &Item) {
+        if let Some(table) = item.as_table_like() {
+            self.table_like(table);
+        } else if let Some(items) = as_items(item) {
+            self.write(b"a");
+            self.len(items.len());
+            for item in items {
+                self.item(item);
+            }
+        } else if let Some(value) = item.as_value() {
+            self.scalar(value);
+        }
+    }
+
+    fn table_like(&mut self, table: &dyn TableLike) {
+        let mut entries = table
+            .iter()
+            .filter(|(_, item)| !item.is_none())
+            .collect::<Vec<_>>();
+        if !self.key_order {
+            entries.sort_by_key(|(key, _)| *key);
+        }
+        self.write(b"t");
+        self.len(entries.len());
+        for (key, item) in entries {
+            self.str(key);
+            self.item(item);
+        }
+    }
+
+    fn scalar(&mut self, value: &Value) {
+        match value {
+            Value::String(s) => self.str(s.value()),
+            Value::Integer(i) => {
+                self.write(b"i");
+                self.write(&i.value().to_le_bytes());
+            }
+            Value::Float(f) => {
+                self.write(b"f");
+                let f = *f.value();
+                if f.is_nan() {
+                    self.write(b"nan");
+                } else {
+                    // `-0.0 == 0.0`
+                    let f = if f == 0.0 { 0.0f64 } else { f };
+                    self.write(&f.to_bits().to_le_bytes());
+                }
+            }
+            Value::Boolean(b) => {
+                self.write(b"b");
+                self.write(&[u8::from(*b.value())]);
+            }
+            Value::Datetime(d) => {
+                self.write(b"d");
+                self.str(&d.value().to_string());
+            }
+            Value::Array(_) | Value::InlineTable(_) => {
+                unreachable!("containers are handled by `Fingerprint::item`")
+            }
+        }
+    }
+}
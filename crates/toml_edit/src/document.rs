@@ -1,7 +1,9 @@
+use std::collections::BTreeMap;
+#[cfg(feature = "parse")]
 use std::str::FromStr;
 
 use crate::table::Iter;
-use crate::{Item, RawString, Table};
+use crate::{InlineTable, Item, Key, RawString, Table, Value};
 
 /// Type representing a parsed TOML document
 #[derive(Debug, Clone)]
@@ -10,6 +12,8 @@ pub struct ImDocument<S> {
     // Trailing comments and whitespaces
     pub(crate) trailing: RawString,
     pub(crate) raw: S,
+    /// The values and table headers parsed, as counted against `Limits::max_items`
+    pub(crate) items: usize,
 }
 
 impl ImDocument<&'static str> {
@@ -19,12 +23,83 @@ impl ImDocument<&'static str> {
     }
 }
 
+#[cfg(feature = "parse")]
+impl<'s> ImDocument<&'s str> {
+    /// Parse a TOML document from bytes, like the contents of a file
+    ///
+    /// Unlike checking the bytes with [`std::str::from_utf8`] first, invalid UTF-8 is reported as
+    /// a [`TomlError`][crate::TomlError], with a [span][crate::TomlError::span] covering the bytes
+    /// of the invalid sequence and [`ErrorKind::InvalidUtf8`][crate::ErrorKind::InvalidUtf8].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let bytes = b"name = \"caf\xE9\"\n";
+    /// let err = toml_edit::ImDocument::parse_bytes(bytes).unwrap_err();
+    /// assert_eq!(err.span(), Some(11..12));
+    /// assert_eq!(err.line_col(), Some((1, 12)));
+    /// assert_eq!(err.message(), "invalid UTF-8 sequence `\\xE9`");
+    ///
+    /// let doc = toml_edit::ImDocument::parse_bytes("name = \"café\"\n".as_bytes()).unwrap();
+    /// assert_eq!(doc["name"].as_str(), Some("café"));
+    /// ```
+    pub fn parse_bytes(bytes: &'s [u8]) -> Result<Self, crate::TomlError> {
+        let raw = std::str::from_utf8(bytes)
+            .map_err(|error| crate::TomlError::invalid_utf8(bytes, error))?;
+        Self::parse(raw)
+    }
+}
+
 #[cfg(feature = "parse")]
 impl<S: AsRef<str>> ImDocument<S> {
     /// Parse a TOML document
     pub fn parse(raw: S) -> Result<Self, crate::TomlError> {
         crate::parser::parse_document(raw)
     }
+
+    /// Parse a TOML document, collecting every error instead of stopping at the first
+    ///
+    /// After an error, parsing resumes on the next line.  The returned document holds what
+    /// could be parsed: lines with errors are left out, along with the comments preceding them,
+    /// as are the key/value pairs of a table whose header has an error.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let raw = "a = 1\nb = \nc = 3\n[t\nd = 4\n";
+    /// let (doc, errors) = toml_edit::ImDocument::parse_recover(raw);
+    /// assert_eq!(errors.len(), 2);
+    /// assert_eq!(&raw[errors[0].span().unwrap()], "\n");
+    /// assert_eq!(doc.get("c").and_then(|c| c.as_integer()), Some(3));
+    /// assert!(!doc.contains_key("t"));
+    /// ```
+    pub fn parse_recover(raw: S) -> (Self, Vec<crate::TomlError>) {
+        crate::parser::parse_document_recover(raw)
+    }
+
+    /// Parse a TOML document, rejecting input beyond `limits`
+    ///
+    /// This is for untrusted input, see [`Limits`][crate::Limits].
+    pub fn parse_with_limits(raw: S, limits: crate::Limits) -> Result<Self, crate::TomlError> {
+        crate::parser::parse_document_with_limits(raw, limits)
+    }
+
+    /// Parse a TOML document according to `options`
+    ///
+    /// See [`ParseOptions`][crate::ParseOptions].
+    pub fn parse_with_options(
+        raw: S,
+        options: crate::ParseOptions,
+    ) -> Result<Self, crate::TomlError> {
+        crate::parser::parse_document_with_options(raw, options)
+    }
+
+    /// Parse a TOML document, rejecting features of later versions than `spec`
+    ///
+    /// See [`ParseOptions::spec`][crate::ParseOptions::spec].
+    pub fn parse_with_spec(raw: S, spec: crate::TomlSpec) -> Result<Self, crate::TomlError> {
+        Self::parse_with_options(raw, crate::ParseOptions::new().spec(spec))
+    }
 }
 
 impl<S: AsRef<str>> ImDocument<S> {
@@ -53,10 +128,61 @@ impl<S> ImDocument<S> {
         self.as_table().iter()
     }
 
+    /// The number of values and table headers parsed, as counted against
+    /// [`Limits::max_items`][crate::Limits::max_items]
+    ///
+    /// Along with the length of [`ImDocument::raw`], this is how much the document cost to parse,
+    /// for picking limits.  Documents not parsed have a count of 0.
+    ///
+    /// ```
+    /// # #[cfg(feature = "parse")] {
+    /// let doc = toml_edit::ImDocument::parse("a = [1, 2]\n[b]\n").unwrap();
+    /// assert_eq!(doc.item_count(), 4);
+    /// # }
+    /// ```
+    pub fn item_count(&self) -> usize {
+        self.items
+    }
+
     /// Whitespace after last element
     pub fn trailing(&self) -> &RawString {
         &self.trailing
     }
+
+    /// The location of a key within [`ImDocument::raw`], given a path of keys
+    ///
+    /// See [`Table::get_path`] for how the path is resolved.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "parse")] {
+    /// let raw = "[server]\nport = 80\n";
+    /// let doc = toml_edit::ImDocument::parse(raw).unwrap();
+    /// let span = doc.key_span(&["server", "port"]).unwrap();
+    /// assert_eq!(&raw[span], "port");
+    /// let span = doc.value_span(&["server", "port"]).unwrap();
+    /// assert_eq!(&raw[span], "80");
+    /// # }
+    /// ```
+    pub fn key_span(&self, path: &[&str]) -> Option<std::ops::Range<usize>> {
+        let (last, parents) = path.split_last()?;
+        let parent = if parents.is_empty() {
+            self.as_item()
+        } else {
+            self.as_table().get_path(parents)?
+        };
+        parent.as_table_like()?.key(last)?.span()
+    }
+
+    /// The location of an item within [`ImDocument::raw`], given a path of keys
+    ///
+    /// For standard tables, this covers the header and its key/value pairs.
+    ///
+    /// See [`Table::get_path`] for how the path is resolved.
+    pub fn value_span(&self, path: &[&str]) -> Option<std::ops::Range<usize>> {
+        self.as_table().get_path(path)?.span()
+    }
 }
 
 impl<S: AsRef<str>> ImDocument<S> {
@@ -66,15 +192,145 @@ impl<S: AsRef<str>> ImDocument<S> {
     }
 }
 
+impl<S: AsRef<str>> ImDocument<S> {
+    /// Get a string value given a path of keys, see [`Table::get_path`]
+    ///
+    /// When the string is written without escapes, the result is a slice of
+    /// [`ImDocument::raw`] found through the value's span rather than the decoded copy.
+    /// Whether it can be borrowed is decided from the literal itself.
+    ///
+    /// This saves no allocations: parsing still decodes every string and key into an owned
+    /// copy, whether or not it is read.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "parse")] {
+    /// let raw = "[package]\nname = 'demo'\n";
+    /// let doc = toml_edit::ImDocument::parse(raw).unwrap();
+    /// let name = doc.get_str(&["package", "name"]).unwrap();
+    /// assert_eq!(name, "demo");
+    /// assert!(raw.as_bytes().as_ptr_range().contains(&name.as_ptr()));
+    /// # }
+    /// ```
+    pub fn get_str(&self, path: &[&str]) -> Option<&str> {
+        let value = self.as_table().get_path(path)?.as_value()?;
+        let decoded = value.as_str()?;
+        let raw = value
+            .span()
+            .and_then(|span| self.raw().get(span))
+            .and_then(unescaped_body);
+        Some(raw.unwrap_or(decoded))
+    }
+}
+
+/// The body of a string literal, if decoding it wouldn't change it
+fn unescaped_body(raw: &str) -> Option<&str> {
+    let (body, escapes) = if let Some(body) = strip_quotes(raw, "'''") {
+        (trim_newline(body), false)
+    } else if let Some(body) = strip_quotes(raw, "\"\"\"") {
+        (trim_newline(body), true)
+    } else if let Some(body) = strip_quotes(raw, "'") {
+        (body, false)
+    } else {
+        (strip_quotes(raw, "\"")?, true)
+    };
+    // Newlines in multi-line strings decode to LF
+    if body.contains('\r') || (escapes && body.contains('\\')) {
+        None
+    } else {
+        Some(body)
+    }
+}
+
+fn trim_newline(body: &str) -> &str {
+    body.strip_prefix('\n')
+        .or_else(|| body.strip_prefix("\r\n"))
+        .unwrap_or(body)
+}
+
+fn strip_quotes<'r>(raw: &'r str, quote: &str) -> Option<&'r str> {
+    raw.strip_prefix(quote)?.strip_suffix(quote)
+}
+
 impl<S: AsRef<str>> ImDocument<S> {
     /// Allow editing of the [`DocumentMut`]
+    ///
+    /// All formatting is copied out of the source, so the returned document doesn't depend on it
+    /// and the source can be dropped.  The document keeps the [`LineEnding`] of the source's first
+    /// line and whether the source [started with a BOM][DocumentMut::has_bom].
     pub fn into_mut(mut self) -> DocumentMut {
+        let line_ending = LineEnding::detect(self.raw.as_ref());
+        let line_endings = LineEnding::is_mixed(self.raw.as_ref()).then(|| {
+            let mut line_endings = LineEndings::default();
+            line_endings.record(self.as_table(), self.raw.as_ref(), false);
+            line_endings
+        });
+        let bom = self.raw.as_ref().starts_with(BOM);
         self.despan();
         DocumentMut {
             root: self.root,
             trailing: self.trailing,
+            line_ending,
+            line_endings,
+            bom,
+            #[cfg(feature = "display")]
+            profile: None,
+        }
+    }
+}
+
+/// The line break ending each parsed line of a document with mixed line endings
+#[derive(Debug, Clone, Default)]
+pub(crate) struct LineEndings {
+    /// After the header of the table at each [`Table::position`]
+    headers: BTreeMap<usize, LineEnding>,
+    /// After the key/value pair whose leaf key is at each [`Key::position`]
+    keyvals: BTreeMap<usize, LineEnding>,
+}
+
+impl LineEndings {
+    /// Remember how each line holding a header or key/value pair of `table` ended in `raw`
+    fn record(&mut self, table: &Table, raw: &str, has_header: bool) {
+        if let (true, Some(position), Some(span)) = (has_header, table.position(), table.span()) {
+            if let Some(line_ending) = LineEnding::after(raw, span.start) {
+                self.headers.insert(position, line_ending);
+            }
+        }
+        for (key, item) in table.items.iter() {
+            match item {
+                Item::Value(value) => {
+                    let end = value.span().map(|span| span.end);
+                    let line_ending = end.and_then(|end| LineEnding::after(raw, end));
+                    if let (Some(position), Some(line_ending)) = (key.position(), line_ending) {
+                        self.keyvals.insert(position, line_ending);
+                    }
+                }
+                Item::Table(table) => {
+                    let has_header = !table.is_dotted() && !table.is_implicit();
+                    self.record(table, raw, has_header);
+                }
+                Item::ArrayOfTables(array) => {
+                    for table in array.iter() {
+                        self.record(table, raw, true);
+                    }
+                }
+                Item::None => {}
+            }
         }
     }
+
+    /// How the line with the header of `table` ended, if it was parsed
+    #[cfg(feature = "display")]
+    pub(crate) fn header(&self, table: &Table) -> Option<LineEnding> {
+        self.headers.get(&table.position()?).copied()
+    }
+
+    /// How the line with the key/value pair ending at `key` ended, if it was parsed
+    #[cfg(feature = "display")]
+    pub(crate) fn keyval(&self, key: &Key) -> Option<LineEnding> {
+        self.keyvals.get(&key.position()?).copied()
+    }
 }
 
 impl Default for ImDocument<&'static str> {
@@ -83,6 +339,7 @@ impl Default for ImDocument<&'static str> {
             root: Item::Table(Table::with_pos(Some(0))),
             trailing: Default::default(),
             raw: "",
+            items: 0,
         }
     }
 }
@@ -106,11 +363,20 @@ impl<S> std::ops::Deref for ImDocument<S> {
 }
 
 /// Type representing a TOML document
+///
+/// Unlike [`ImDocument`], this owns all of its formatting, without referring to the source it
+/// was parsed from; see [`Decor::is_despanned`][crate::Decor::is_despanned].
 #[derive(Debug, Clone)]
 pub struct DocumentMut {
     pub(crate) root: Item,
     // Trailing comments and whitespaces
     pub(crate) trailing: RawString,
+    pub(crate) line_ending: LineEnding,
+    // The line break of each parsed line when they differ, see `DocumentMut::line_ending`
+    pub(crate) line_endings: Option<LineEndings>,
+    pub(crate) bom: bool,
+    #[cfg(feature = "display")]
+    pub(crate) profile: Option<crate::Profile>,
 }
 
 impl DocumentMut {
@@ -146,6 +412,34 @@ impl DocumentMut {
         self.as_table().iter()
     }
 
+    /// Replace the root table, returning the previous one
+    ///
+    /// The document's [trailing][DocumentMut::trailing] whitespace and comments are kept.  The
+    /// new table is placed at the start of the document and, as the root can't be written with
+    /// dotted keys, is no longer [dotted][Table::set_dotted].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "parse")] {
+    /// # #[cfg(feature = "display")] {
+    /// let mut doc = "a = 1\n# end\n".parse::<toml_edit::DocumentMut>().unwrap();
+    /// let mut root = toml_edit::Table::new();
+    /// root["b"] = toml_edit::value(2);
+    /// let old = doc.set_root_table(root);
+    /// assert_eq!(old["a"].as_integer(), Some(1));
+    /// assert_eq!(doc.to_string(), "b = 2\n# end\n");
+    /// # }
+    /// # }
+    /// ```
+    pub fn set_root_table(&mut self, mut table: Table) -> Table {
+        table.set_position(0);
+        table.set_dotted(false);
+        std::mem::replace(&mut self.root, Item::Table(table))
+            .into_table()
+            .expect("root should always be a table")
+    }
+
     /// Set whitespace after last element
     pub fn set_trailing(&mut self, trailing: impl Into<RawString>) {
         self.trailing = trailing.into();
@@ -155,6 +449,451 @@ impl DocumentMut {
     pub fn trailing(&self) -> &RawString {
         &self.trailing
     }
+
+    /// Recursively merge `other` into this document.
+    ///
+    /// Conflicts are resolved as follows:
+    /// - Keys only in `other` are inserted, with their formatting, after the existing keys
+    /// - Tables present in both, as long as both are standard tables or both are inline tables,
+    ///   are merged recursively
+    /// - Arrays of tables present in both have `other`'s tables appended
+    /// - For anything else, `other`'s item replaces the existing one while the existing key
+    ///   formatting is kept
+    ///
+    /// Standard tables brought over from `other` are placed after all of this document's tables,
+    /// keeping their relative order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "parse")] {
+    /// # #[cfg(feature = "display")] {
+    /// let mut doc = "[server]\nport = 80 # default\n".parse::<toml_edit::DocumentMut>().unwrap();
+    /// let user = "[server]\nhost = 'localhost'\nport = 8080\n".parse().unwrap();
+    /// doc.merge(&user);
+    /// assert_eq!(doc.to_string(), "[server]\nport = 8080\nhost = 'localhost'\n");
+    /// # }
+    /// # }
+    /// ```
+    pub fn merge(&mut self, other: &DocumentMut) {
+        let offset = max_position(self.as_table()) + 1;
+        merge_table(self.as_table_mut(), other.as_table(), offset);
+    }
+}
+
+fn max_position(table: &Table) -> usize {
+    let mut max = table.position().unwrap_or(0);
+    for (_, item) in table.iter() {
+        match item {
+            Item::Table(t) => max = max.max(max_position(t)),
+            Item::ArrayOfTables(a) => {
+                for t in a.iter() {
+                    max = max.max(max_position(t));
+                }
+            }
+            _ => {}
+        }
+    }
+    max
+}
+
+fn shift_positions(item: &mut Item, offset: usize) {
+    match item {
+        Item::Table(t) => shift_table_positions(t, offset),
+        Item::ArrayOfTables(a) => {
+            for t in a.iter_mut() {
+                shift_table_positions(t, offset);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn shift_table_positions(table: &mut Table, offset: usize) {
+    if let Some(position) = table.position() {
+        table.set_position(position + offset);
+    }
+    for (_, item) in table.iter_mut() {
+        shift_positions(item, offset);
+    }
+}
+
+fn merge_table(this: &mut Table, other: &Table, offset: usize) {
+    if this.is_implicit() && !other.is_implicit() {
+        this.set_implicit(false);
+        *this.decor_mut() = other.decor().clone();
+    }
+    for (key, item) in other.items.iter() {
+        if item.is_none() {
+            continue;
+        }
+        match this.items.get_mut(key.get()) {
+            Some(existing) if !existing.is_none() => merge_item(existing, item, offset),
+            _ => {
+                let mut item = item.clone();
+                shift_positions(&mut item, offset);
+                this.items.insert(foreign_key(key, &mut item), item);
+            }
+        }
+    }
+}
+
+fn merge_inline_table(this: &mut InlineTable, other: &InlineTable) {
+    for (key, item) in other.items.iter() {
+        if item.is_none() {
+            continue;
+        }
+        match this.items.get_mut(key.get()) {
+            Some(existing) if !existing.is_none() => merge_item(existing, item, 0),
+            _ => {
+                let mut item = item.clone();
+                this.items.insert(foreign_key(key, &mut item), item);
+            }
+        }
+    }
+}
+
+/// Positions of key/value pairs from another document don't relate to ours, so the pair and any
+/// dotted keys within it are placed after the existing pairs
+fn foreign_key(key: &Key, item: &mut Item) -> Key {
+    match item {
+        Item::Table(table) if table.is_dotted() => table.clear_value_positions(),
+        Item::Value(Value::InlineTable(table)) if table.is_dotted() => {
+            table.clear_value_positions();
+        }
+        _ => {}
+    }
+    let mut key = key.clone();
+    key.position = None;
+    key
+}
+
+fn merge_item(this: &mut Item, other: &Item, offset: usize) {
+    match (&mut *this, other) {
+        (Item::Table(this), Item::Table(other)) => merge_table(this, other, offset),
+        (Item::Value(Value::InlineTable(this)), Item::Value(Value::InlineTable(other))) => {
+            merge_inline_table(this, other);
+        }
+        (Item::ArrayOfTables(this), Item::ArrayOfTables(other)) => {
+            for table in other.iter() {
+                let mut table = table.clone();
+                shift_table_positions(&mut table, offset);
+                this.push(table);
+            }
+        }
+        (this, other) => {
+            *this = other.clone();
+            shift_positions(this, offset);
+        }
+    }
+}
+
+impl DocumentMut {
+    /// The line break style the document is rendered with
+    ///
+    /// Parsed documents use that of their first line, so files using CRLF round-trip without
+    /// spurious diffs.  When the lines of a parsed document end differently, each keeps its own
+    /// line ending and only new lines and decor use this one, until
+    /// [`DocumentMut::set_line_ending`] is called.  Those lines are found by the
+    /// [`Table::position`] of their header or the [`Key::position`] of their key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "parse")] #[cfg(feature = "display")] {
+    /// use toml_edit::{DocumentMut, LineEnding};
+    ///
+    /// let mut doc = "a = 1\r\nb = 2\n".parse::<DocumentMut>().unwrap();
+    /// assert_eq!(doc.line_ending(), LineEnding::Crlf);
+    /// doc["c"] = toml_edit::value(3);
+    /// assert_eq!(doc.to_string(), "a = 1\r\nb = 2\nc = 3\r\n");
+    /// # }
+    /// ```
+    pub fn line_ending(&self) -> LineEnding {
+        self.line_ending
+    }
+
+    /// Render every line break with `line_ending`
+    ///
+    /// This covers the whitespace and comments between items, the line breaks ending key/value
+    /// pairs and headers, and those within multi-line strings, whose values are unaffected as
+    /// parsers normalize them.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "parse")] #[cfg(feature = "display")] {
+    /// use toml_edit::{DocumentMut, LineEnding};
+    ///
+    /// let mut doc = "# config\r\na = 1\r\n".parse::<DocumentMut>().unwrap();
+    /// assert_eq!(doc.line_ending(), LineEnding::Crlf);
+    /// doc["b"] = toml_edit::value(2);
+    /// assert_eq!(doc.to_string(), "# config\r\na = 1\r\nb = 2\r\n");
+    ///
+    /// doc.set_line_ending(LineEnding::Lf);
+    /// assert_eq!(doc.to_string(), "# config\na = 1\nb = 2\n");
+    /// # }
+    /// ```
+    pub fn set_line_ending(&mut self, line_ending: LineEnding) {
+        self.line_ending = line_ending;
+        self.line_endings = None;
+    }
+
+    /// Whether the document is rendered starting with a byte order mark
+    ///
+    /// Parsed documents keep the BOM of their source, as some editors on Windows write one.
+    pub fn has_bom(&self) -> bool {
+        self.bom
+    }
+
+    /// Render the document starting with a byte order mark, or not
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "parse")] #[cfg(feature = "display")] {
+    /// let mut doc = "\u{feff}a = 1\n".parse::<toml_edit::DocumentMut>().unwrap();
+    /// assert!(doc.has_bom());
+    /// assert_eq!(doc.to_string(), "\u{feff}a = 1\n");
+    ///
+    /// doc.set_bom(false);
+    /// assert_eq!(doc.to_string(), "a = 1\n");
+    /// # }
+    /// ```
+    pub fn set_bom(&mut self, yes: bool) {
+        self.bom = yes;
+    }
+
+    /// Separate every table header from what precedes it by exactly `blank_lines` blank lines
+    ///
+    /// See [`Table::set_prefix_blank_lines`].  When no key/value pairs precede the first header,
+    /// it is left at the start of the document.  Headers that aren't rendered, like those of
+    /// dotted and implicit tables, are left untouched.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "parse")] #[cfg(feature = "display")] {
+    /// let mut doc = "[a]\nx = 1\n[[b]]\n\n\n\n[[b]]\n".parse::<toml_edit::DocumentMut>().unwrap();
+    /// doc.separate_tables(1);
+    /// assert_eq!(doc.to_string(), "[a]\nx = 1\n\n[[b]]\n\n[[b]]\n");
+    /// # }
+    /// ```
+    pub fn separate_tables(&mut self, blank_lines: usize) {
+        let mut headers = Vec::new();
+        let mut last_position = 0;
+        collect_headers(self.as_table(), &mut last_position, &mut headers);
+        // The index, in the order the headers are visited, of the one rendered first
+        let first = headers
+            .iter()
+            .enumerate()
+            .min_by_key(|(_, position)| **position)
+            .map(|(index, _)| index)
+            .filter(|_| self.as_table().get_values().is_empty());
+        separate_tables(self.as_table_mut(), blank_lines, first, &mut 0);
+    }
+
+    /// Remove the tables holding no key/value pairs, once their own empty tables are removed
+    ///
+    /// This cleans up the headers left behind by edits, like `[section]` after its last key was
+    /// removed.  The root table is kept, as are inline tables, which are values.  Tables holding
+    /// comments after their header, see [`Table::trailing_comments`], are kept so the comments
+    /// aren't lost.  Comments before the header of a removed table go with it, while the decor
+    /// of everything kept is left untouched.  Running it again changes nothing.
+    ///
+    /// See [`DocumentMut::remove_empty_tables_with`] to keep tables with a header or to remove
+    /// empty entries of arrays of tables.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "parse")] #[cfg(feature = "display")] {
+    /// let mut doc = "[a]\n[b.c]\n[d] # kept\nx = 1\n[[e]]\n".parse::<toml_edit::DocumentMut>().unwrap();
+    /// doc.remove_empty_tables();
+    /// assert_eq!(doc.to_string(), "[d] # kept\nx = 1\n[[e]]\n");
+    /// # }
+    /// ```
+    pub fn remove_empty_tables(&mut self) {
+        self.remove_empty_tables_with(&EmptyTableOptions::new());
+    }
+
+    /// Remove the tables holding no key/value pairs, see [`DocumentMut::remove_empty_tables`]
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "parse")] #[cfg(feature = "display")] {
+    /// use toml_edit::EmptyTableOptions;
+    ///
+    /// let mut doc = "[a]\n[b.c]\n[[d]]\n[[d]]\nx = 1\n".parse::<toml_edit::DocumentMut>().unwrap();
+    /// let options = EmptyTableOptions::new()
+    ///     .keep_explicit(true)
+    ///     .prune_arrays_of_tables(true);
+    /// doc.remove_empty_tables_with(&options);
+    /// assert_eq!(doc.to_string(), "[a]\n[b.c]\n[[d]]\nx = 1\n");
+    /// # }
+    /// ```
+    pub fn remove_empty_tables_with(&mut self, options: &EmptyTableOptions) {
+        remove_empty_tables(self.as_table_mut(), options);
+    }
+}
+
+/// The positions the encoder sorts the tables rendered with a header below `table` by
+fn collect_headers(table: &Table, last_position: &mut usize, headers: &mut Vec<usize>) {
+    let mut collect = |table: &Table, is_array_of_tables| {
+        if let Some(position) = table.position() {
+            *last_position = position;
+        }
+        if has_header(table, is_array_of_tables) {
+            headers.push(*last_position);
+        }
+        collect_headers(table, last_position, headers);
+    };
+    for (_, item) in table.iter() {
+        match item {
+            Item::Table(table) => collect(table, false),
+            Item::ArrayOfTables(array) => {
+                for table in array.iter() {
+                    collect(table, true);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Visits the headers in the same order as [`collect_headers`], counting them in `index`
+fn separate_tables(table: &mut Table, blank_lines: usize, first: Option<usize>, index: &mut usize) {
+    let mut separate = |table: &mut Table, is_array_of_tables| {
+        if has_header(table, is_array_of_tables) {
+            let is_first = first == Some(*index);
+            table.set_prefix_blank_lines(if is_first { 0 } else { blank_lines });
+            *index += 1;
+        }
+        separate_tables(table, blank_lines, first, index);
+    };
+    for (_, item) in table.iter_mut() {
+        match item {
+            Item::Table(table) => separate(table, false),
+            Item::ArrayOfTables(array) => {
+                for table in array.iter_mut() {
+                    separate(table, true);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn has_header(table: &Table, is_array_of_tables: bool) -> bool {
+    is_array_of_tables
+        || !(table.is_dotted() || table.is_implicit() && table.get_values().is_empty())
+}
+
+/// Removes tables that are empty once their own empty tables are removed from `table`, returning
+/// whether `table` is then empty
+fn remove_empty_tables(table: &mut Table, options: &EmptyTableOptions) -> bool {
+    table.retain(|_, item| match item {
+        Item::Table(table) => {
+            let is_empty = remove_empty_tables(table, options);
+            let keep = options.keep_explicit && !table.is_implicit() && !table.is_dotted();
+            !is_empty || keep
+        }
+        Item::ArrayOfTables(array) => {
+            for table in array.iter_mut() {
+                remove_empty_tables(table, options);
+            }
+            if options.prune_arrays_of_tables {
+                array.retain(|table| !is_empty_table(table));
+                !array.is_empty()
+            } else {
+                true
+            }
+        }
+        _ => true,
+    });
+    is_empty_table(table)
+}
+
+fn is_empty_table(table: &Table) -> bool {
+    table.is_empty() && table.trailing_comments().next().is_none()
+}
+
+/// Which empty tables [`DocumentMut::remove_empty_tables_with`] removes
+///
+/// The defaults match [`DocumentMut::remove_empty_tables`].
+#[derive(Clone, Debug, Default)]
+pub struct EmptyTableOptions {
+    keep_explicit: bool,
+    prune_arrays_of_tables: bool,
+}
+
+impl EmptyTableOptions {
+    /// Options matching [`DocumentMut::remove_empty_tables`]
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Keep empty tables that are written with a header, see [`Table::set_implicit`]
+    ///
+    /// Implicit tables, like `a` for `[a.b]`, and dotted tables are still removed.
+    pub fn keep_explicit(mut self, yes: bool) -> Self {
+        self.keep_explicit = yes;
+        self
+    }
+
+    /// Remove the empty tables of arrays of tables, and then arrays holding no tables
+    ///
+    /// By default, arrays of tables are kept as-is, as the number of tables can be meaningful.
+    pub fn prune_arrays_of_tables(mut self, yes: bool) -> Self {
+        self.prune_arrays_of_tables = yes;
+        self
+    }
+}
+
+/// The UTF-8 byte order mark, skipped by the parser
+pub(crate) const BOM: char = '\u{feff}';
+
+/// The line break style of a [`DocumentMut`], see [`DocumentMut::set_line_ending`]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum LineEnding {
+    /// `\n`
+    #[default]
+    Lf,
+    /// `\r\n`, as is common on Windows
+    Crlf,
+}
+
+impl LineEnding {
+    /// The line ending of the first line of `raw`, defaulting to [`LineEnding::Lf`]
+    pub(crate) fn detect(raw: &str) -> Self {
+        match raw.find('\n') {
+            Some(i) if raw[..i].ends_with('\r') => LineEnding::Crlf,
+            _ => LineEnding::Lf,
+        }
+    }
+
+    /// Whether `raw` has lines ending with both `\n` and `\r\n`
+    pub(crate) fn is_mixed(raw: &str) -> bool {
+        let crlf = raw.matches("\r\n").count();
+        crlf != 0 && crlf != raw.matches('\n').count()
+    }
+
+    /// The line ending of the line holding `raw[pos..]`, if it isn't the last
+    pub(crate) fn after(raw: &str, pos: usize) -> Option<Self> {
+        let rest = raw.get(pos..)?;
+        let i = rest.find('\n')?;
+        Some(Self::detect(&rest[..=i]))
+    }
+
+    /// The characters making up a line break
+    pub fn as_str(self) -> &'static str {
+        match self {
+            LineEnding::Lf => "\n",
+            LineEnding::Crlf => "\r\n",
+        }
+    }
 }
 
 impl Default for DocumentMut {
@@ -162,6 +901,11 @@ impl Default for DocumentMut {
         Self {
             root: Item::Table(Table::with_pos(Some(0))),
             trailing: Default::default(),
+            line_ending: Default::default(),
+            line_endings: None,
+            bom: false,
+            #[cfg(feature = "display")]
+            profile: None,
         }
     }
 }
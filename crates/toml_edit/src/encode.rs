@@ -3,6 +3,7 @@ use std::fmt::{Display, Formatter, Result, Write};
 
 use toml_datetime::Datetime;
 
+use crate::document::LineEndings;
 use crate::inline_table::DEFAULT_INLINE_KEY_DECOR;
 use crate::key::Key;
 use crate::repr::{Formatted, Repr, ValueRepr};
@@ -12,8 +13,8 @@ use crate::table::{
 use crate::value::{
     DEFAULT_LEADING_VALUE_DECOR, DEFAULT_TRAILING_VALUE_DECOR, DEFAULT_VALUE_DECOR,
 };
-use crate::DocumentMut;
 use crate::{Array, InlineTable, Item, Table, Value};
+use crate::{DocumentMut, LineEnding};
 
 pub(crate) fn encode_key(this: &Key, buf: &mut dyn Write, input: Option<&str>) -> Result {
     if let Some(input) = input {
@@ -164,7 +165,7 @@ pub(crate) fn encode_table(
         if i != 0 {
             write!(buf, ",")?;
         }
-        let inner_decor = if i == len - 1 {
+        let inner_decor = if i == len - 1 && !this.trailing_comma() {
             DEFAULT_TRAILING_VALUE_DECOR
         } else {
             DEFAULT_VALUE_DECOR
@@ -173,6 +174,10 @@ pub(crate) fn encode_table(
         write!(buf, "=")?;
         encode_value(value, buf, input, inner_decor)?;
     }
+    if this.trailing_comma() && len != 0 {
+        write!(buf, ",")?;
+        this.trailing().encode_with_default(buf, input, "")?;
+    }
 
     write!(buf, "}}")?;
     decor.suffix_encode(buf, input, default_decor.1)?;
@@ -199,28 +204,165 @@ pub(crate) fn encode_value(
 
 impl Display for DocumentMut {
     fn fmt(&self, f: &mut Formatter<'_>) -> Result {
-        let decor = self.decor();
-        decor.prefix_encode(f, None, DEFAULT_ROOT_DECOR.0)?;
-
-        let mut path = Vec::new();
-        let mut last_position = 0;
-        let mut tables = Vec::new();
-        visit_nested_tables(self.as_table(), &mut path, false, &mut |t, p, is_array| {
-            if let Some(pos) = t.position() {
-                last_position = pos;
-            }
-            tables.push((last_position, t, p.clone(), is_array));
-            Ok(())
+        if self.has_bom() {
+            f.write_char(crate::document::BOM)?;
+        }
+        if self.line_endings.is_some() {
+            // Keep the line breaks of the source, only rendering new ones with the line ending
+            return encode_document(self, f);
+        }
+        let mut writer = LineEndingWriter::new(f, self.line_ending());
+        encode_document(self, &mut writer)?;
+        writer.finish()
+    }
+}
+
+impl DocumentMut {
+    /// Render the document into `writer` without building a [`String`] first
+    ///
+    /// The output is the same as [`ToString::to_string`].  The document is written in many small
+    /// pieces, so wrap unbuffered writers, like a [`File`][std::fs::File], in a
+    /// [`BufWriter`][std::io::BufWriter].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "parse")] {
+    /// let doc = "a = 1 # one\n[b]\nc = 'd'\n".parse::<toml_edit::DocumentMut>().unwrap();
+    /// let mut output = Vec::new();
+    /// doc.to_writer(&mut output).unwrap();
+    /// assert_eq!(output, doc.to_string().as_bytes());
+    /// # }
+    /// ```
+    pub fn to_writer<W: std::io::Write + ?Sized>(&self, writer: &mut W) -> std::io::Result<()> {
+        let mut adapter = IoWriter {
+            inner: writer,
+            error: None,
+        };
+        write!(adapter, "{self}").map_err(|_| {
+            adapter.error.take().unwrap_or_else(|| {
+                std::io::Error::new(std::io::ErrorKind::Other, "formatter error")
+            })
         })
-        .unwrap();
+    }
+}
+
+/// Forwards to an [`std::io::Write`], keeping the error that [`std::fmt::Error`] can't carry
+struct IoWriter<'w, W: ?Sized> {
+    inner: &'w mut W,
+    error: Option<std::io::Error>,
+}
 
-        tables.sort_by_key(|&(id, _, _, _)| id);
-        let mut first_table = true;
-        for (_, table, path, is_array) in tables {
-            visit_table(f, None, table, &path, is_array, &mut first_table)?;
+impl<W: std::io::Write + ?Sized> Write for IoWriter<'_, W> {
+    fn write_str(&mut self, s: &str) -> Result {
+        self.inner.write_all(s.as_bytes()).map_err(|e| {
+            self.error = Some(e);
+            std::fmt::Error
+        })
+    }
+}
+
+fn encode_document(this: &DocumentMut, f: &mut dyn Write) -> Result {
+    let decor = this.decor();
+    decor.prefix_encode(f, None, DEFAULT_ROOT_DECOR.0)?;
+
+    let mut path = Vec::new();
+    let mut last_position = 0;
+    let mut tables = Vec::new();
+    visit_nested_tables(this.as_table(), &mut path, false, &mut |t, p, is_array| {
+        if let Some(pos) = t.position() {
+            last_position = pos;
         }
-        decor.suffix_encode(f, None, DEFAULT_ROOT_DECOR.1)?;
-        self.trailing().encode_with_default(f, None, "")
+        tables.push((last_position, t, p.clone(), is_array));
+        Ok(())
+    })
+    .unwrap();
+
+    tables.sort_by_key(|&(id, _, _, _)| id);
+    let mut first_table = true;
+    let line_breaks = LineBreaks {
+        line_ending: this.line_ending(),
+        parsed: this.line_endings.as_ref(),
+    };
+    for (_, table, path, is_array) in tables {
+        visit_table(
+            f,
+            None,
+            table,
+            &path,
+            is_array,
+            &mut first_table,
+            line_breaks,
+        )?;
+    }
+    decor.suffix_encode(f, None, DEFAULT_ROOT_DECOR.1)?;
+    this.trailing().encode_with_default(f, None, "")
+}
+
+/// Encode a fragment with every line break as `\n`, as it's rendered outside of a document
+pub(crate) fn encode_lf(
+    f: &mut dyn Write,
+    encode: impl FnOnce(&mut dyn Write) -> Result,
+) -> Result {
+    let mut writer = LineEndingWriter::new(f, LineEnding::Lf);
+    encode(&mut writer)?;
+    writer.finish()
+}
+
+/// Writes every line break as the document's [`LineEnding`]
+struct LineEndingWriter<'w> {
+    inner: &'w mut dyn Write,
+    line_ending: &'static str,
+    // A `\r` at the end of the last write, possibly starting a `\r\n`
+    pending_cr: bool,
+}
+
+impl<'w> LineEndingWriter<'w> {
+    fn new(inner: &'w mut dyn Write, line_ending: LineEnding) -> Self {
+        Self {
+            inner,
+            line_ending: line_ending.as_str(),
+            pending_cr: false,
+        }
+    }
+
+    fn finish(self) -> Result {
+        if self.pending_cr {
+            self.inner.write_str("\r")?;
+        }
+        Ok(())
+    }
+}
+
+impl Write for LineEndingWriter<'_> {
+    fn write_str(&mut self, mut s: &str) -> Result {
+        if self.pending_cr {
+            self.pending_cr = false;
+            if let Some(rest) = s.strip_prefix('\n') {
+                self.inner.write_str(self.line_ending)?;
+                s = rest;
+            } else {
+                self.inner.write_str("\r")?;
+            }
+        }
+        while let Some(i) = s.find(['\r', '\n']) {
+            self.inner.write_str(&s[..i])?;
+            let rest = &s[i + 1..];
+            if s.as_bytes()[i] == b'\n' {
+                self.inner.write_str(self.line_ending)?;
+                s = rest;
+            } else if let Some(rest) = rest.strip_prefix('\n') {
+                self.inner.write_str(self.line_ending)?;
+                s = rest;
+            } else if rest.is_empty() {
+                self.pending_cr = true;
+                return Ok(());
+            } else {
+                self.inner.write_str("\r")?;
+                s = rest;
+            }
+        }
+        self.inner.write_str(s)
     }
 }
 
@@ -259,6 +401,26 @@ where
     Ok(())
 }
 
+/// The line breaks to end a document's lines with
+#[derive(Copy, Clone)]
+struct LineBreaks<'d> {
+    line_ending: LineEnding,
+    /// The line break of each parsed line, when they differ
+    parsed: Option<&'d LineEndings>,
+}
+
+impl LineBreaks<'_> {
+    fn header(&self, table: &Table) -> &'static str {
+        let parsed = self.parsed.and_then(|parsed| parsed.header(table));
+        parsed.unwrap_or(self.line_ending).as_str()
+    }
+
+    fn keyval(&self, key: &Key) -> &'static str {
+        let parsed = self.parsed.and_then(|parsed| parsed.keyval(key));
+        parsed.unwrap_or(self.line_ending).as_str()
+    }
+}
+
 fn visit_table(
     buf: &mut dyn Write,
     input: Option<&str>,
@@ -266,7 +428,9 @@ fn visit_table(
     path: &[Key],
     is_array_of_tables: bool,
     first_table: &mut bool,
+    line_breaks: LineBreaks<'_>,
 ) -> Result {
+    let line_ending = line_breaks.line_ending.as_str();
     let children = table.get_values();
     // We are intentionally hiding implicit tables without any tables nested under them (ie
     // `table.is_empty()` which is in contrast to `table.get_values().is_empty()`).  We are
@@ -289,34 +453,38 @@ fn visit_table(
             *first_table = false;
             ("", DEFAULT_TABLE_DECOR.1)
         } else {
-            DEFAULT_TABLE_DECOR
+            (line_ending, DEFAULT_TABLE_DECOR.1)
         };
         table.decor.prefix_encode(buf, input, default_decor.0)?;
         write!(buf, "[[")?;
         encode_key_path(path, buf, input, DEFAULT_KEY_PATH_DECOR)?;
         write!(buf, "]]")?;
         table.decor.suffix_encode(buf, input, default_decor.1)?;
-        writeln!(buf)?;
+        buf.write_str(line_breaks.header(table))?;
     } else if is_visible_std_table {
         let default_decor = if *first_table {
             *first_table = false;
             ("", DEFAULT_TABLE_DECOR.1)
         } else {
-            DEFAULT_TABLE_DECOR
+            (line_ending, DEFAULT_TABLE_DECOR.1)
         };
         table.decor.prefix_encode(buf, input, default_decor.0)?;
         write!(buf, "[")?;
         encode_key_path(path, buf, input, DEFAULT_KEY_PATH_DECOR)?;
         write!(buf, "]")?;
         table.decor.suffix_encode(buf, input, default_decor.1)?;
-        writeln!(buf)?;
+        buf.write_str(line_breaks.header(table))?;
     }
     // print table body
     for (key_path, value) in children {
         encode_key_path_ref(&key_path, buf, input, DEFAULT_KEY_DECOR)?;
         write!(buf, "=")?;
         encode_value(value, buf, input, DEFAULT_VALUE_DECOR)?;
-        writeln!(buf)?;
+        let key = key_path.last().expect("key/value pairs have a key");
+        buf.write_str(line_breaks.keyval(key))?;
+    }
+    if path.is_empty() || is_array_of_tables || is_visible_std_table {
+        table.trailing().encode_with_default(buf, input, "")?;
     }
     Ok(())
 }
@@ -427,16 +595,21 @@ fn infer_style(
 }
 
 fn infer_literal(value: &str) -> bool {
+    (value.contains('"') | value.contains('\\')) && can_be_literal(value)
+}
+
+/// Whether `value` can be written as a one-line literal string
+pub(crate) fn can_be_literal(value: &str) -> bool {
     #[cfg(feature = "parse")]
     {
         use winnow::stream::ContainsToken as _;
-        (value.contains('"') | value.contains('\\'))
-            && value
-                .chars()
-                .all(|c| crate::parser::strings::LITERAL_CHAR.contains_token(c))
+        value
+            .chars()
+            .all(|c| crate::parser::strings::LITERAL_CHAR.contains_token(c))
     }
     #[cfg(not(feature = "parse"))]
     {
+        let _ = value;
         false
     }
 }
@@ -555,13 +728,13 @@ impl ValueRepr for Datetime {
 }
 
 #[cfg(test)]
+#[cfg(feature = "parse")]
 mod test {
     use super::*;
     use proptest::prelude::*;
 
     proptest! {
         #[test]
-        #[cfg(feature = "parse")]
         fn parseable_string(string in "\\PC*") {
             let string = Value::from(string);
             let encoded = string.to_string();
@@ -579,7 +752,6 @@ string:
 
     proptest! {
         #[test]
-        #[cfg(feature = "parse")]
         fn parseable_key(string in "\\PC*") {
             let string = Key::new(string);
             let encoded = string.to_string();
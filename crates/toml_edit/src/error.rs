@@ -1,13 +1,83 @@
 use std::error::Error as StdError;
 use std::fmt::{Display, Formatter, Result};
+use std::sync::Arc;
+
+/// What a [`TomlError`] reports, for handling errors without parsing their message
+///
+/// See [`TomlError::kind`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ErrorKind {
+    /// A key defined more than once, or a table header repeated
+    DuplicateKey {
+        /// The key, as written in the document
+        key: String,
+        /// The keys of the table it is in, empty for the document root, when known
+        table: Option<Vec<String>>,
+    },
+    /// A dotted key adding to a value that is not a table
+    DottedKeyExtendWrongType {
+        /// The keys up to the value
+        key: Vec<String>,
+        /// The type of the value
+        actual: &'static str,
+    },
+    /// An escape sequence in a basic string that TOML does not define
+    InvalidEscape {
+        /// The character after the `\`, `None` at the end of the document
+        found: Option<char>,
+    },
+    /// A string without its closing quotes
+    UnterminatedString,
+    /// A date, time or offset that is malformed or out of range
+    InvalidDatetime,
+    /// A malformed integer or float
+    InvalidNumber,
+    /// An integer too large for an `i64`
+    IntegerOutOfRange {
+        /// The integer, as written in the document
+        literal: String,
+    },
+    /// A key not followed by `=`
+    ExpectedEquals,
+    /// A document longer than [`Limits::max_len`][crate::Limits::max_len], rejected before parsing
+    LengthLimitExceeded {
+        /// The length of the document, in bytes
+        len: usize,
+        /// The limit
+        max: usize,
+    },
+    /// Arrays and inline tables nested more deeply than [`Limits::max_depth`][crate::Limits::max_depth]
+    RecursionLimitExceeded,
+    /// More values and table headers than [`Limits::max_items`][crate::Limits::max_items]
+    ItemLimitExceeded {
+        /// The limit
+        max: usize,
+    },
+    /// Syntax from a later version of TOML than the document was parsed with
+    #[cfg(feature = "parse")]
+    UnsupportedFeature {
+        /// What the document used
+        feature: &'static str,
+        /// The version introducing it
+        spec: crate::TomlSpec,
+    },
+    /// Bytes that aren't UTF-8, see [`ImDocument::parse_bytes`][crate::ImDocument::parse_bytes]
+    InvalidUtf8,
+    /// Any other error, described by [`TomlError::message`]
+    Other,
+}
 
 /// Type representing a TOML parse error
 #[derive(Debug, Clone, Eq, PartialEq, Hash)]
 pub struct TomlError {
     message: String,
-    raw: Option<String>,
+    kind: ErrorKind,
+    // Shared by the errors of one document, see `parse_document_recover`
+    raw: Option<Arc<str>>,
     keys: Vec<String>,
     span: Option<std::ops::Range<usize>>,
+    suggestions: Vec<String>,
 }
 
 impl TomlError {
@@ -21,11 +91,21 @@ impl TomlError {
     ) -> Self {
         use winnow::stream::Stream;
 
-        let message = error.inner().to_string();
         let raw = raw.finish();
-        let raw = String::from_utf8(raw.to_owned()).expect("original document was utf8");
+        let raw = std::str::from_utf8(raw)
+            .expect("original document was utf8")
+            .into();
+        Self::from_context(error.inner(), raw, error.offset())
+    }
+
+    #[cfg(feature = "parse")]
+    pub(crate) fn from_context(
+        error: &winnow::error::ContextError,
+        raw: Arc<str>,
+        offset: usize,
+    ) -> Self {
+        let message = error.to_string();
 
-        let offset = error.offset();
         let offset = (0..=offset)
             .rev()
             .find(|index| raw.is_char_boundary(*index))
@@ -38,13 +118,63 @@ impl TomlError {
         } else {
             raw.len() - offset
         };
+        // Point at the whole literal rather than its first character
+        let len = match error
+            .cause()
+            .and_then(|cause| cause.downcast_ref::<crate::parser::error::CustomError>())
+        {
+            Some(crate::parser::error::CustomError::IntegerOutOfRange { literal })
+                if raw[offset..].starts_with(literal.as_str()) =>
+            {
+                literal.len()
+            }
+            _ => len,
+        };
         let span = offset..(offset + len);
+        let kind = classify(error, &raw, offset);
+        // Errors like duplicate keys are not syntax mistakes
+        let suggestions = if error.cause().is_none() {
+            suggest(&raw, offset)
+        } else {
+            Vec::new()
+        };
 
         Self {
             message,
+            kind,
             raw: Some(raw),
             keys: Vec::new(),
             span: Some(span),
+            suggestions,
+        }
+    }
+
+    /// Point at the invalid sequence `error` is about, or at the incomplete one ending `bytes`
+    #[cfg(feature = "parse")]
+    pub(crate) fn invalid_utf8(bytes: &[u8], error: std::str::Utf8Error) -> Self {
+        let start = error.valid_up_to();
+        let end = error
+            .error_len()
+            .map(|len| start + len)
+            .unwrap_or(bytes.len());
+        let sequence = bytes[start..end]
+            .iter()
+            .map(|byte| format!("\\x{byte:02X}"))
+            .collect::<String>();
+        let message = if error.error_len().is_some() {
+            format!("invalid UTF-8 sequence `{sequence}`")
+        } else {
+            format!("incomplete UTF-8 sequence `{sequence}` at the end of the document")
+        };
+        Self {
+            message,
+            kind: ErrorKind::InvalidUtf8,
+            // Only the line up to the invalid sequence is rendered, where the lossy conversion
+            // has the same offsets
+            raw: Some(String::from_utf8_lossy(bytes).into()),
+            keys: Vec::new(),
+            span: Some(start..end),
+            suggestions: Vec::new(),
         }
     }
 
@@ -52,12 +182,24 @@ impl TomlError {
     pub(crate) fn custom(message: String, span: Option<std::ops::Range<usize>>) -> Self {
         Self {
             message,
+            kind: ErrorKind::Other,
             raw: None,
             keys: Vec::new(),
             span,
+            suggestions: Vec::new(),
         }
     }
 
+    #[cfg(feature = "parse")]
+    pub(crate) fn length_limit_exceeded(len: usize, max: usize) -> Self {
+        let mut error = Self::custom(
+            format!("document is {len} bytes long, exceeding the limit of {max}"),
+            None,
+        );
+        error.kind = ErrorKind::LengthLimitExceeded { len, max };
+        error
+    }
+
     #[cfg(feature = "serde")]
     pub(crate) fn add_key(&mut self, key: String) {
         self.keys.insert(0, key);
@@ -68,11 +210,92 @@ impl TomlError {
         &self.message
     }
 
+    /// What went wrong, for matching on
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "parse")] {
+    /// use toml_edit::ErrorKind;
+    ///
+    /// let err = "a = 1\na = 2\n".parse::<toml_edit::DocumentMut>().unwrap_err();
+    /// assert_eq!(
+    ///     err.kind(),
+    ///     &ErrorKind::DuplicateKey { key: "a".to_owned(), table: Some(Vec::new()) }
+    /// );
+    ///
+    /// let err = r#"a = "\q""#.parse::<toml_edit::DocumentMut>().unwrap_err();
+    /// assert_eq!(err.kind(), &ErrorKind::InvalidEscape { found: Some('q') });
+    /// # }
+    /// ```
+    pub fn kind(&self) -> &ErrorKind {
+        &self.kind
+    }
+
     /// The start/end index into the original document where the error occurred
     pub fn span(&self) -> Option<std::ops::Range<usize>> {
         self.span.clone()
     }
 
+    /// Hints for fixing common mistakes that lead to this error
+    ///
+    /// These are not included when displaying the error.
+    pub fn suggestions(&self) -> &[String] {
+        &self.suggestions
+    }
+
+    /// The 1-based line and column where the error occurred, as displayed
+    ///
+    /// This is only available when the error carries the original document, like parse errors.
+    /// Columns count characters, not bytes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "parse")] {
+    /// let err = "a = 1\nb = ?\n".parse::<toml_edit::DocumentMut>().unwrap_err();
+    /// assert_eq!(err.line_col(), Some((2, 5)));
+    /// # }
+    /// ```
+    pub fn line_col(&self) -> Option<(usize, usize)> {
+        let raw = self.raw.as_deref()?;
+        let span = self.span.as_ref()?;
+        let (line, column) = translate_position(raw.as_bytes(), span.start);
+        Some((line + 1, column + 1))
+    }
+
+    /// Render the error with a snippet of `source` underlining where it occurred
+    ///
+    /// [`Display`] only includes the snippet when the error carries the original document, like
+    /// parse errors; this renders it for any error with a [span][TomlError::span].  `source`
+    /// should be the document the span refers to.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "parse")] {
+    /// let source = "name = 'demo'\nport = eighty\n";
+    /// let err = source.parse::<toml_edit::DocumentMut>().unwrap_err();
+    /// assert_eq!(
+    ///     err.to_string_pretty(source),
+    ///     "\
+    /// TOML parse error at line 2, column 8
+    ///   |
+    /// 2 | port = eighty
+    ///   |        ^
+    /// invalid string
+    /// expected `\"`, `'`
+    /// "
+    /// );
+    /// # }
+    /// ```
+    pub fn to_string_pretty(&self, source: &str) -> String {
+        let mut output = String::new();
+        self.render(Some(source), &mut output)
+            .expect("writing to a string can't fail");
+        output
+    }
+
     #[cfg(feature = "serde")]
     pub(crate) fn set_span(&mut self, span: Option<std::ops::Range<usize>>) {
         self.span = span;
@@ -80,7 +303,7 @@ impl TomlError {
 
     #[cfg(feature = "serde")]
     pub(crate) fn set_raw(&mut self, raw: Option<String>) {
-        self.raw = raw;
+        self.raw = raw.map(Arc::from);
     }
 }
 
@@ -98,8 +321,18 @@ impl TomlError {
 /// While parsing a Date-Time
 impl Display for TomlError {
     fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        self.render(self.raw.as_deref(), f)
+    }
+}
+
+impl TomlError {
+    fn render(&self, raw: Option<&str>, f: &mut dyn std::fmt::Write) -> Result {
         let mut context = false;
-        if let (Some(raw), Some(span)) = (&self.raw, self.span()) {
+        if let (Some(raw), Some(span)) = (raw, self.span()) {
+            if raw.len() < span.end {
+                // The span is from a different document
+                return render_message(self, false, f);
+            }
             context = true;
 
             let (line, column) = translate_position(raw.as_bytes(), span.start);
@@ -138,13 +371,16 @@ impl Display for TomlError {
             }
             writeln!(f)?;
         }
-        writeln!(f, "{}", self.message)?;
-        if !context && !self.keys.is_empty() {
-            writeln!(f, "in `{}`", self.keys.join("."))?;
-        }
+        render_message(self, context, f)
+    }
+}
 
-        Ok(())
+fn render_message(error: &TomlError, context: bool, f: &mut dyn std::fmt::Write) -> Result {
+    writeln!(f, "{}", error.message)?;
+    if !context && !error.keys.is_empty() {
+        writeln!(f, "in `{}`", error.keys.join("."))?;
     }
+    Ok(())
 }
 
 impl StdError for TomlError {
@@ -153,6 +389,134 @@ impl StdError for TomlError {
     }
 }
 
+/// The [`ErrorKind`] of a parse error at `offset`, from its cause or what was being parsed
+#[cfg(feature = "parse")]
+fn classify(error: &winnow::error::ContextError, raw: &str, offset: usize) -> ErrorKind {
+    use crate::parser::error::CustomError;
+    use winnow::error::{StrContext, StrContextValue};
+
+    let keys = |keys: &[crate::Key]| keys.iter().map(|key| key.get().to_owned()).collect();
+    let label = error.context().find_map(|context| match context {
+        StrContext::Label(label) => Some(*label),
+        _ => None,
+    });
+    let is_datetime = matches!(label, Some("date-time" | "time" | "time offset"));
+    let is_escape = matches!(
+        label,
+        Some("escape sequence" | "unicode 4-digit hex code" | "unicode 8-digit hex code")
+    );
+    // The error is usually reported after the character following the `\`, except for those the
+    // escape grammar doesn't look at, like non-ASCII ones
+    let invalid_escape = || ErrorKind::InvalidEscape {
+        found: match raw[..offset].chars().next_back() {
+            Some('\\') => raw[offset..].chars().next(),
+            found => found,
+        },
+    };
+
+    let cause = error
+        .cause()
+        .and_then(|cause| cause.downcast_ref::<CustomError>());
+    if let Some(cause) = cause {
+        return match cause {
+            CustomError::DuplicateKey { key, table } => ErrorKind::DuplicateKey {
+                key: key.clone(),
+                table: table.as_deref().map(keys),
+            },
+            CustomError::DottedKeyExtendWrongType { key, actual } => {
+                ErrorKind::DottedKeyExtendWrongType {
+                    key: keys(key),
+                    actual,
+                }
+            }
+            CustomError::OutOfRange if is_datetime => ErrorKind::InvalidDatetime,
+            CustomError::OutOfRange if is_escape => invalid_escape(),
+            CustomError::OutOfRange => ErrorKind::Other,
+            CustomError::IntegerOutOfRange { literal } => ErrorKind::IntegerOutOfRange {
+                literal: literal.clone(),
+            },
+            CustomError::RecursionLimitExceeded => ErrorKind::RecursionLimitExceeded,
+            CustomError::ItemLimitExceeded { max } => ErrorKind::ItemLimitExceeded { max: *max },
+            CustomError::UnsupportedFeature { feature, spec } => ErrorKind::UnsupportedFeature {
+                feature,
+                spec: *spec,
+            },
+        };
+    }
+
+    let rest = &raw[offset..];
+    match label {
+        _ if is_escape => invalid_escape(),
+        _ if is_datetime => ErrorKind::InvalidDatetime,
+        Some("basic string" | "literal string")
+            if rest.is_empty() || rest.starts_with('\n') || rest.starts_with("\r\n") =>
+        {
+            ErrorKind::UnterminatedString
+        }
+        Some("multiline basic string" | "multiline literal string") if rest.is_empty() => {
+            ErrorKind::UnterminatedString
+        }
+        Some(
+            "integer"
+            | "floating-point number"
+            | "binary integer"
+            | "octal integer"
+            | "hexadecimal integer",
+        ) => ErrorKind::InvalidNumber,
+        None if error
+            .context()
+            .any(|context| context == &StrContext::Expected(StrContextValue::CharLiteral('='))) =>
+        {
+            ErrorKind::ExpectedEquals
+        }
+        _ => ErrorKind::Other,
+    }
+}
+
+/// Guess at the mistake behind an error at `offset` from the line it is on
+#[cfg(feature = "parse")]
+fn suggest(raw: &str, offset: usize) -> Vec<String> {
+    let line_start = raw[..offset].rfind('\n').map(|i| i + 1).unwrap_or(0);
+    let line_end = raw[offset..]
+        .find('\n')
+        .map(|i| offset + i)
+        .unwrap_or(raw.len());
+    let line = raw[line_start..line_end].trim_end_matches('\r');
+    let column = (offset - line_start).min(line.len());
+    let trimmed = line.trim_start();
+    if trimmed.is_empty() || trimmed.starts_with('#') {
+        return Vec::new();
+    }
+
+    let suggestion = if trimmed.starts_with('[') {
+        let header = trimmed.trim_start_matches('[').trim_end_matches(']');
+        (header.trim().contains(char::is_whitespace) && !header.contains(['"', '\'']))
+            .then_some("keys with spaces must be quoted, e.g. `[\"my table\"]`")
+    } else if let Some(eq) = line.find('=') {
+        let before = line.get(eq + 1..column).unwrap_or_default();
+        let current = line[column..].chars().next();
+        let depth = |open, close| {
+            before.matches(open).count() as isize - before.matches(close).count() as isize
+        };
+        if column < eq {
+            Some("did you mean to quote this key? e.g. `\"my key\" = 1`")
+        } else if current == Some('=') && depth('[', ']') > 0 && depth('{', '}') <= 0 {
+            Some("arrays use commas to separate values, not `=`")
+        } else if before.trim().is_empty() && current.map(char::is_alphabetic).unwrap_or(false) {
+            Some("did you mean to quote this string? e.g. `key = \"value\"`")
+        } else if column == line.len()
+            && (before.matches('"').count() % 2 == 1 || before.matches('\'').count() % 2 == 1)
+        {
+            Some("did you forget a closing quote?")
+        } else {
+            None
+        }
+    } else {
+        Some("key/value pairs need an `=` between the key and the value")
+    };
+    suggestion.into_iter().map(String::from).collect()
+}
+
 fn translate_position(input: &[u8], index: usize) -> (usize, usize) {
     if input.is_empty() {
         return (0, index);
@@ -0,0 +1,125 @@
+use crate::{DocumentMut, Item, PathSegment, Value};
+
+/// An iterator over every leaf value of a document, see [`DocumentMut::flatten_iter`]
+pub type FlattenIter<'a> = Box<dyn Iterator<Item = (Vec<PathSegment>, &'a Value)> + 'a>;
+
+type Children<'a> = Box<dyn Iterator<Item = (PathSegment, &'a Item)> + 'a>;
+
+impl DocumentMut {
+    /// Iterate over every value that isn't an array or table, along with its full path
+    ///
+    /// Standard tables, inline tables, arrays of tables, and arrays are descended into, with
+    /// positions within arrays given as [`PathSegment::Index`].  Empty arrays and tables have no
+    /// leaves, so don't show up.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "parse")] {
+    /// # #[cfg(feature = "display")] {
+    /// use toml_edit::{DocumentMut, PathSegment};
+    ///
+    /// let doc = "
+    /// name = 'demo'
+    /// [server]
+    /// ports = [80, 443]
+    /// tls = { enabled = true }
+    /// "
+    /// .parse::<DocumentMut>()
+    /// .unwrap();
+    /// let env = doc
+    ///     .flatten_iter()
+    ///     .map(|(path, value)| {
+    ///         let name = path
+    ///             .iter()
+    ///             .map(|segment| match segment {
+    ///                 PathSegment::Key(key) => key.get().to_uppercase(),
+    ///                 PathSegment::Index(index) => index.to_string(),
+    ///             })
+    ///             .collect::<Vec<_>>()
+    ///             .join("_");
+    ///         format!("{name}={}", value.to_string().trim())
+    ///     })
+    ///     .collect::<Vec<_>>();
+    /// assert_eq!(
+    ///     env,
+    ///     [
+    ///         "NAME='demo'",
+    ///         "SERVER_PORTS_0=80",
+    ///         "SERVER_PORTS_1=443",
+    ///         "SERVER_TLS_ENABLED=true",
+    ///     ]
+    /// );
+    /// # }
+    /// # }
+    /// ```
+    pub fn flatten_iter(&self) -> FlattenIter<'_> {
+        Box::new(Flatten {
+            path: Vec::new(),
+            stack: vec![children(self.as_item())],
+        })
+    }
+}
+
+struct Flatten<'a> {
+    path: Vec<PathSegment>,
+    stack: Vec<Children<'a>>,
+}
+
+impl<'a> Iterator for Flatten<'a> {
+    type Item = (Vec<PathSegment>, &'a Value);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let Some((segment, item)) = self.stack.last_mut()?.next() else {
+                self.stack.pop();
+                self.path.pop();
+                continue;
+            };
+            match item {
+                Item::Value(value) if !value.is_array() && !value.is_inline_table() => {
+                    let mut path = self.path.clone();
+                    path.push(segment);
+                    return Some((path, value));
+                }
+                _ => {
+                    self.path.push(segment);
+                    self.stack.push(children(item));
+                }
+            }
+        }
+    }
+}
+
+fn children(item: &Item) -> Children<'_> {
+    match item {
+        Item::None => Box::new(std::iter::empty()),
+        Item::Value(Value::Array(array)) => Box::new(
+            array
+                .values
+                .iter()
+                .enumerate()
+                .map(|(index, item)| (PathSegment::Index(index), item)),
+        ),
+        Item::Value(Value::InlineTable(table)) => Box::new(
+            table
+                .items
+                .iter()
+                .map(|(key, item)| (PathSegment::Key(key.clone()), item)),
+        ),
+        Item::Value(_) => Box::new(std::iter::empty()),
+        Item::Table(table) => Box::new(
+            table
+                .items
+                .iter()
+                .map(|(key, item)| (PathSegment::Key(key.clone()), item)),
+        ),
+        Item::ArrayOfTables(array) => Box::new(
+            array
+                .values
+                .iter()
+                .enumerate()
+                .map(|(index, item)| (PathSegment::Index(index), item)),
+        ),
+    }
+}
@@ -0,0 +1,1096 @@
+use std::sync::Arc;
+
+use crate::encode::{can_be_literal, to_string_repr, StringStyle};
+use crate::key::to_key_repr;
+use crate::raw_string::contains_newline;
+use crate::table::KeyValuePairs;
+use crate::visit_mut::{self, VisitMut};
+use crate::{
+    Array, Decor, DocumentMut, InlineTable, Item, KeyMut, PathSegment, RawString, Repr, Table,
+    Value,
+};
+
+/// Formatting options for [`DocumentMut::to_string_with`] and [`DocumentMut::reformat`]
+///
+/// The defaults render a document the same as [`ToString::to_string`].
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "parse")] {
+/// use toml_edit::{DocumentMut, KeyStyle, ToStringOptions};
+///
+/// let doc = "a = { 'b' = [1, 2, 3] }".parse::<DocumentMut>().unwrap();
+/// let options = ToStringOptions::new()
+///     .standard_tables(true)
+///     .max_array_width(8)
+///     .indent(2)
+///     .key_style(KeyStyle::Bare);
+/// assert_eq!(doc.to_string_with(&options), "[a]\nb = [\n  1,\n  2,\n  3,\n]\n");
+/// # }
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct ToStringOptions {
+    indent: Option<usize>,
+    standard_tables: bool,
+    max_array_width: Option<usize>,
+    max_inline_table_width: Option<usize>,
+    inline_table_wrap: InlineTableWrap,
+    key_style: KeyStyle,
+    normalize_whitespace: bool,
+    max_blank_lines: Option<usize>,
+    compact: bool,
+}
+
+impl ToStringOptions {
+    /// Options matching [`ToString::to_string`]
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Number of spaces to indent each level of a multi-line array by
+    ///
+    /// By default, the existing indentation is kept and arrays wrapped by
+    /// [`ToStringOptions::max_array_width`] are indented by 4 spaces.
+    pub fn indent(mut self, width: usize) -> Self {
+        self.indent = Some(width);
+        self
+    }
+
+    /// Render inline tables, and arrays of inline tables, as standard tables
+    ///
+    /// Inline tables within arrays of other values are left as-is.
+    pub fn standard_tables(mut self, yes: bool) -> Self {
+        self.standard_tables = yes;
+        self
+    }
+
+    /// Wrap single-line arrays longer than `width` characters, one value per line
+    pub fn max_array_width(mut self, width: usize) -> Self {
+        self.max_array_width = Some(width);
+        self
+    }
+
+    /// Rewrite single-line inline tables longer than `width` characters
+    ///
+    /// How they are rewritten is set with [`ToStringOptions::inline_table_wrap`].
+    pub fn max_inline_table_width(mut self, width: usize) -> Self {
+        self.max_inline_table_width = Some(width);
+        self
+    }
+
+    /// How inline tables beyond [`ToStringOptions::max_inline_table_width`] are rewritten
+    ///
+    /// By default, they become standard tables so the output stays valid TOML 1.0.
+    pub fn inline_table_wrap(mut self, wrap: InlineTableWrap) -> Self {
+        self.inline_table_wrap = wrap;
+        self
+    }
+
+    /// How to quote keys
+    pub fn key_style(mut self, style: KeyStyle) -> Self {
+        self.key_style = style;
+        self
+    }
+
+    /// Use canonical whitespace, keeping comments where they are
+    ///
+    /// This spaces out `=` as ` = `, removes the indentation of key/value pairs and headers and
+    /// the whitespace within headers and inline tables, and indents multi-line arrays by
+    /// [`ToStringOptions::indent`], defaulting to 4 spaces.
+    pub fn normalize_whitespace(mut self, yes: bool) -> Self {
+        self.normalize_whitespace = yes;
+        self
+    }
+
+    /// Collapse runs of more than `max` blank lines
+    ///
+    /// By default, blank lines are kept.
+    pub fn max_blank_lines(mut self, max: usize) -> Self {
+        self.max_blank_lines = Some(max);
+        self
+    }
+
+    /// Render the smallest TOML holding the same values, see [`DocumentMut::to_string_compact`]
+    ///
+    /// This takes precedence over the other options.
+    pub fn compact(mut self, yes: bool) -> Self {
+        self.compact = yes;
+        self
+    }
+}
+
+/// How long inline tables are rewritten, see [`ToStringOptions::inline_table_wrap`]
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "parse")] {
+/// use toml_edit::{DocumentMut, InlineTableWrap, ToStringOptions};
+///
+/// let doc = "point = { x = 1, y = 2 }\n".parse::<DocumentMut>().unwrap();
+/// let options = ToStringOptions::new().max_inline_table_width(10);
+/// assert_eq!(doc.to_string_with(&options), "[point]\nx = 1\ny = 2\n");
+///
+/// let options = options.inline_table_wrap(InlineTableWrap::Multiline);
+/// assert_eq!(doc.to_string_with(&options), "point = {\n    x = 1,\n    y = 2,\n}\n");
+/// # }
+/// ```
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum InlineTableWrap {
+    /// Turn the key/value pair into a `[table]`, which every version of TOML can read
+    ///
+    /// As with [`ToStringOptions::standard_tables`], inline tables within arrays are left as-is.
+    #[default]
+    StandardTable,
+    /// Put each key/value pair on its own line, see [`InlineTable::set_multiline`]
+    ///
+    /// Newlines within inline tables are new in TOML 1.1, so the output can only be parsed again
+    /// with [`ParseOptions::allow_inline_newlines`][crate::ParseOptions::allow_inline_newlines].
+    Multiline,
+}
+
+/// How keys are quoted, see [`ToStringOptions::key_style`]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum KeyStyle {
+    /// Keep each key's existing representation
+    #[default]
+    Preserve,
+    /// Use bare keys, only quoting keys that require it
+    Bare,
+    /// Quote every key, choosing between basic and literal strings
+    Quoted,
+    /// Quote every key with basic strings, e.g. `"key"`
+    BasicQuoted,
+    /// Quote every key with literal strings, e.g. `'key'`, where possible
+    LiteralQuoted,
+}
+
+impl KeyStyle {
+    /// The representation of `key` in this style, `None` to keep the existing one
+    pub(crate) fn to_repr(self, key: &str) -> Option<Repr> {
+        let repr = match self {
+            KeyStyle::Preserve => return None,
+            KeyStyle::Bare => to_key_repr(key),
+            KeyStyle::Quoted => to_string_repr(key, Some(StringStyle::OnelineSingle), None),
+            KeyStyle::BasicQuoted => {
+                to_string_repr(key, Some(StringStyle::OnelineSingle), Some(false))
+            }
+            KeyStyle::LiteralQuoted => to_string_repr(
+                key,
+                Some(StringStyle::OnelineSingle),
+                Some(can_be_literal(key)),
+            ),
+        };
+        Some(repr)
+    }
+}
+
+/// A house style for documents built from scratch, see [`DocumentBuilder::profile`]
+///
+/// Key/value pairs inserted into the tables of a document with a profile, at any depth, are
+/// laid out by it as [`DocumentMut::reformat`] would, along with the tables and arrays within
+/// them.  This covers [`Table::insert`] and the [`Entry`][crate::Entry] API, and so the keys
+/// that indexing adds, but not what is then assigned through the `&mut Item`, as in
+/// `doc["key"] = value`.  Insert such values instead.
+///
+/// Inline tables, including those created by indexing into [`Item::None`], and tables pushed
+/// into an [`ArrayOfTables`][crate::ArrayOfTables] don't lay out what is inserted into them.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "parse")] {
+/// use toml_edit::{value, Array, DocumentBuilder, InlineTable, Item, Profile, Table};
+///
+/// let mut doc = DocumentBuilder::new().profile(Profile::Cargo).build();
+/// let mut package = Table::new();
+/// package.insert("name", value("demo"));
+/// package.insert(
+///     "authors",
+///     value(Array::from_iter([
+///         "Alice Archer <alice@example.com>",
+///         "Bob Baker <bob@example.com>",
+///         "Carol Chen <carol@example.com>",
+///     ])),
+/// );
+/// doc.insert("package", Item::Table(package));
+///
+/// let dependencies = doc.entry("dependencies").or_insert(Item::Table(Table::new()));
+/// let mut serde = InlineTable::new();
+/// serde.insert("version", "1.0".into());
+/// let dependencies = dependencies.as_table_mut().unwrap();
+/// dependencies.insert("serde", value(serde));
+/// assert_eq!(
+///     doc.to_string(),
+///     r#"[package]
+/// name = "demo"
+/// authors = [
+///     "Alice Archer <alice@example.com>",
+///     "Bob Baker <bob@example.com>",
+///     "Carol Chen <carol@example.com>",
+/// ]
+///
+/// [dependencies]
+/// serde = { version = "1.0" }
+/// "#
+/// );
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub enum Profile {
+    /// The layout of `Cargo.toml` files written by `cargo`
+    ///
+    /// Keys are bare where possible and separated from values by ` = `, arrays are wrapped one
+    /// value per line, indented by 4 spaces, when longer than 80 columns, and inline tables,
+    /// such as dependency specifications, are kept inline.
+    Cargo,
+    /// Standard tables wherever possible, with arrays wrapped when longer than 40 columns
+    ///
+    /// Inline tables become `[tables]` and arrays of inline tables become `[[arrays]]`, except
+    /// within arrays of other values.
+    Expanded,
+    /// A profile of your own
+    Custom(ToStringOptions),
+}
+
+impl Profile {
+    /// The options laying out what is inserted into the document
+    pub fn options(&self) -> ToStringOptions {
+        match self {
+            Profile::Cargo => ToStringOptions::new()
+                .normalize_whitespace(true)
+                .key_style(KeyStyle::Bare)
+                .max_array_width(80)
+                .indent(4)
+                .max_blank_lines(1),
+            Profile::Expanded => ToStringOptions::new()
+                .normalize_whitespace(true)
+                .standard_tables(true)
+                .max_array_width(40)
+                .indent(4)
+                .max_blank_lines(1),
+            Profile::Custom(options) => options.clone(),
+        }
+    }
+}
+
+/// Create a [`DocumentMut`] with a formatting [`Profile`]
+///
+/// See [`Profile`] for an example.
+#[derive(Clone, Debug, Default)]
+pub struct DocumentBuilder {
+    profile: Option<Profile>,
+}
+
+impl DocumentBuilder {
+    /// Build an empty document, rendered like [`DocumentMut::new`]
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Lay out the document with `profile`
+    pub fn profile(mut self, profile: Profile) -> Self {
+        self.profile = Some(profile);
+        self
+    }
+
+    /// Create the empty document
+    pub fn build(self) -> DocumentMut {
+        let mut doc = DocumentMut::new();
+        doc.set_profile(self.profile);
+        doc
+    }
+}
+
+impl DocumentMut {
+    /// Render the document, normalizing its formatting according to `options`
+    ///
+    /// See [`ToStringOptions`].  The document's [`Profile`], if any, is not used.
+    pub fn to_string_with(&self, options: &ToStringOptions) -> String {
+        let mut doc = self.clone();
+        doc.reformat(options);
+        doc.to_string()
+    }
+
+    /// Normalize the document's formatting according to `options`
+    ///
+    /// The document is edited in place, unlike [`DocumentMut::to_string_with`].  Only formatting
+    /// is changed, so the document holds the same values after being rendered and parsed again.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "parse")] {
+    /// use toml_edit::{DocumentMut, ToStringOptions};
+    ///
+    /// let mut doc = "a=1\n\n\n\n   # about b\n  b   =   [ 2,3 ]   # two\n".parse::<DocumentMut>().unwrap();
+    /// let options = ToStringOptions::new()
+    ///     .normalize_whitespace(true)
+    ///     .max_blank_lines(1);
+    /// doc.reformat(&options);
+    /// assert_eq!(doc.to_string(), "a = 1\n\n# about b\nb = [2, 3] # two\n");
+    /// # }
+    /// ```
+    pub fn reformat(&mut self, options: &ToStringOptions) {
+        if options.compact {
+            compact(self);
+            return;
+        }
+        Formatter {
+            options,
+            in_table: false,
+            in_multiline_inline_table: false,
+            array_depth: 0,
+            inline_table_depth: 0,
+        }
+        .visit_document_mut(self);
+    }
+
+    /// The profile laying out what is inserted into the document, see [`DocumentBuilder::profile`]
+    pub fn profile(&self) -> Option<&Profile> {
+        self.profile.as_ref()
+    }
+
+    /// Set or clear the profile laying out what is inserted into the document
+    ///
+    /// The formatting the document already holds is left as-is; call [`DocumentMut::reformat`]
+    /// with [`Profile::options`] to lay it out the same way.
+    pub fn set_profile(&mut self, profile: Option<Profile>) {
+        let options = profile.as_ref().map(|profile| Arc::new(profile.options()));
+        set_tables_profile(&mut self.root, options.as_ref());
+        self.profile = profile;
+    }
+
+    /// Render the document with as little whitespace as possible
+    ///
+    /// Every table is written inline and keys are bare where possible, with no whitespace besides
+    /// the line break ending each top-level key/value pair.  This is lossy by design:
+    /// **all comments are dropped**, along with blank lines and the layout of the document.  The
+    /// output parses back to the same values.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "parse")] {
+    /// let doc = r#"
+    /// ## comments are dropped
+    /// name = "demo"
+    ///
+    /// [dependencies]
+    /// "serde" = { version = "1.0", features = [ "derive" ] }
+    ///
+    /// [[bin]]
+    /// path = "main.rs"
+    /// "#.parse::<toml_edit::DocumentMut>().unwrap();
+    /// assert_eq!(
+    ///     doc.to_string_compact(),
+    ///     r#"name="demo"
+    /// dependencies={serde={version="1.0",features=["derive"]}}
+    /// bin=[{path="main.rs"}]
+    /// "#
+    /// );
+    /// # }
+    /// ```
+    pub fn to_string_compact(&self) -> String {
+        self.to_string_with(&ToStringOptions::new().compact(true))
+    }
+
+    /// Write small arrays of tables inline, as arrays of inline tables
+    ///
+    /// An array of tables is converted when it holds at most `max_entries` tables and the inline
+    /// array fits within `max_width` columns, leaving out its key.  Nested arrays of tables are
+    /// considered first, so they may be inlined even when their parent is not.
+    ///
+    /// See [`DocumentMut::explode_inline_arrays_of_tables`] for the reverse.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "parse")] {
+    /// let mut doc = r#"
+    /// [[bin]]
+    /// name = "a"
+    ///
+    /// [[bin]]
+    /// name = "b"
+    /// "#.parse::<toml_edit::DocumentMut>().unwrap();
+    /// doc.inline_small_arrays_of_tables(2, 40);
+    /// assert_eq!(doc.to_string(), "bin = [{ name = \"a\" }, { name = \"b\" }]\n");
+    /// # }
+    /// ```
+    pub fn inline_small_arrays_of_tables(&mut self, max_entries: usize, max_width: usize) {
+        inline_arrays_of_tables(self.as_table_mut(), max_entries, max_width);
+    }
+
+    /// Write arrays of inline tables as arrays of tables, with a `[[header]]` per table
+    ///
+    /// Only arrays directly within standard tables are converted, as arrays of tables can't be
+    /// written inline.  Inline tables within the converted tables are left as-is.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "parse")] {
+    /// let mut doc = "bin = [{ name = 'a' }, { name = 'b' }]\n"
+    ///     .parse::<toml_edit::DocumentMut>()
+    ///     .unwrap();
+    /// doc.explode_inline_arrays_of_tables();
+    /// assert_eq!(doc.to_string(), "[[bin]]\nname = 'a'\n\n[[bin]]\nname = 'b'\n");
+    /// # }
+    /// ```
+    pub fn explode_inline_arrays_of_tables(&mut self) {
+        explode_arrays_of_tables(self.as_table_mut());
+    }
+
+    /// Rewrite every key in the document in `style`, keeping its decor
+    ///
+    /// This covers keys of tables, headers, dotted keys, and inline tables, at any depth.  Keys
+    /// that can't be written in `style`, like a bare key with a space or a literal key with a `'`,
+    /// get the closest valid representation instead and their locations are returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "parse")] {
+    /// use toml_edit::{DocumentMut, KeyStyle, PathSegment};
+    ///
+    /// let mut doc = "\"a\" = 1\n['b'.\"c d\"]\ne = { 'f' = 2 }\n".parse::<DocumentMut>().unwrap();
+    /// let invalid = doc.normalize_keys(KeyStyle::Bare);
+    /// assert_eq!(doc.to_string(), "a = 1\n[b.\"c d\"]\ne = { f = 2 }\n");
+    /// assert_eq!(invalid.len(), 1);
+    /// assert!(matches!(&invalid[0][..], [PathSegment::Key(b), PathSegment::Key(cd)] if b == "b" && cd == "c d"));
+    /// # }
+    /// ```
+    pub fn normalize_keys(&mut self, style: KeyStyle) -> Vec<Vec<PathSegment>> {
+        let mut invalid = Vec::new();
+        if style != KeyStyle::Preserve {
+            let mut path = Vec::new();
+            normalize_table_keys(
+                &mut self.as_table_mut().items,
+                style,
+                &mut path,
+                &mut invalid,
+            );
+        }
+        invalid
+    }
+}
+
+/// Lay out a key/value pair being inserted into a table of a document with a [`Profile`]
+///
+/// The tables within `item` then lay out what is inserted into them the same way.
+pub(crate) fn format_inserted(
+    options: Option<&Arc<ToStringOptions>>,
+    key: KeyMut<'_>,
+    item: &mut Item,
+) {
+    let Some(options) = options else {
+        adopt_profile(item, None);
+        return;
+    };
+    if options.compact {
+        // Everything is inline, leaving no tables to insert into
+        item.make_value();
+        Compactor.visit_table_like_kv_mut(key, item);
+        return;
+    }
+    Formatter {
+        options,
+        in_table: true,
+        in_multiline_inline_table: false,
+        array_depth: 0,
+        inline_table_depth: 0,
+    }
+    .visit_table_like_kv_mut(key, item);
+    set_tables_profile(item, Some(options));
+}
+
+/// Have the tables of `item`, being inserted into a table laid out with `options`, lay out what
+/// is inserted into them the same way
+pub(crate) fn adopt_profile(item: &mut Item, options: Option<&Arc<ToStringOptions>>) {
+    // Tables moved out of a document with a profile take on that of their new one
+    let has_profile = match item {
+        Item::Table(table) => table.profile.is_some(),
+        Item::ArrayOfTables(array) => array.iter().any(|table| table.profile.is_some()),
+        Item::None | Item::Value(_) => false,
+    };
+    if options.is_some() || has_profile {
+        set_tables_profile(item, options);
+    }
+}
+
+/// Have the tables of `item` lay out what is inserted into them with `options`
+fn set_tables_profile(item: &mut Item, options: Option<&Arc<ToStringOptions>>) {
+    match item {
+        Item::Table(table) => set_table_profile(table, options),
+        Item::ArrayOfTables(array) => {
+            for table in array.iter_mut() {
+                set_table_profile(table, options);
+            }
+        }
+        Item::None | Item::Value(_) => {}
+    }
+}
+
+fn set_table_profile(table: &mut Table, options: Option<&Arc<ToStringOptions>>) {
+    table.profile = options.cloned();
+    for item in table.items.values_mut() {
+        set_tables_profile(item, options);
+    }
+}
+
+fn inline_arrays_of_tables(table: &mut Table, max_entries: usize, max_width: usize) {
+    use indexmap::map::MutableKeys;
+    for (key, item) in table.items.iter_mut2() {
+        match item {
+            Item::Table(table) => inline_arrays_of_tables(table, max_entries, max_width),
+            Item::ArrayOfTables(array) => {
+                for table in array.iter_mut() {
+                    inline_arrays_of_tables(table, max_entries, max_width);
+                }
+                if array.len() <= max_entries {
+                    let inline = array.clone().into_array();
+                    if inline.to_string().trim().len() <= max_width {
+                        key.leaf_decor.clear();
+                        *item = Item::Value(Value::Array(inline));
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn explode_arrays_of_tables(table: &mut Table) {
+    use indexmap::map::MutableKeys;
+    for (key, item) in table.items.iter_mut2() {
+        let is_tables = item
+            .as_array()
+            .map(|array| !array.is_empty() && array.iter().all(Value::is_inline_table))
+            .unwrap_or(false);
+        if is_tables {
+            key.leaf_decor.clear();
+            item.make_item();
+        }
+        match item {
+            Item::Table(table) => explode_arrays_of_tables(table),
+            Item::ArrayOfTables(array) => {
+                for table in array.iter_mut() {
+                    explode_arrays_of_tables(table);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn normalize_table_keys(
+    items: &mut KeyValuePairs,
+    style: KeyStyle,
+    path: &mut Vec<PathSegment>,
+    invalid: &mut Vec<Vec<PathSegment>>,
+) {
+    use indexmap::map::MutableKeys;
+    for (key, item) in items.iter_mut2() {
+        if let Some(repr) = style.to_repr(key.get()) {
+            let quote = repr.as_raw().as_str().and_then(|raw| raw.chars().next());
+            let valid = match style {
+                KeyStyle::Bare => !matches!(quote, Some('"' | '\'')),
+                KeyStyle::LiteralQuoted => quote == Some('\''),
+                KeyStyle::Preserve | KeyStyle::Quoted | KeyStyle::BasicQuoted => true,
+            };
+            key.repr = Some(repr);
+            if !valid {
+                let mut path = path.clone();
+                path.push(PathSegment::Key(key.clone()));
+                invalid.push(path);
+            }
+        }
+        path.push(PathSegment::Key(key.clone()));
+        normalize_item_keys(item, style, path, invalid);
+        path.pop();
+    }
+}
+
+fn normalize_item_keys(
+    item: &mut Item,
+    style: KeyStyle,
+    path: &mut Vec<PathSegment>,
+    invalid: &mut Vec<Vec<PathSegment>>,
+) {
+    match item {
+        Item::None => {}
+        Item::Table(table) => normalize_table_keys(&mut table.items, style, path, invalid),
+        Item::Value(Value::InlineTable(table)) => {
+            normalize_table_keys(&mut table.items, style, path, invalid);
+        }
+        Item::Value(Value::Array(array)) => {
+            for (index, item) in array.values.iter_mut().enumerate() {
+                path.push(PathSegment::Index(index));
+                normalize_item_keys(item, style, path, invalid);
+                path.pop();
+            }
+        }
+        Item::Value(_) => {}
+        Item::ArrayOfTables(array) => {
+            for (index, item) in array.values.iter_mut().enumerate() {
+                path.push(PathSegment::Index(index));
+                normalize_item_keys(item, style, path, invalid);
+                path.pop();
+            }
+        }
+    }
+}
+
+fn compact(doc: &mut DocumentMut) {
+    for (_, item) in doc.as_table_mut().iter_mut() {
+        if !item.is_value() {
+            item.make_value();
+        }
+    }
+    doc.decor_mut().set_prefix("");
+    doc.decor_mut().set_suffix("");
+    doc.as_table_mut().set_trailing("");
+    doc.set_trailing("");
+    Compactor.visit_document_mut(doc);
+}
+
+/// Strips all optional whitespace and comments, see [`DocumentMut::to_string_compact`]
+struct Compactor;
+
+impl VisitMut for Compactor {
+    fn visit_table_like_kv_mut(&mut self, mut key: KeyMut<'_>, node: &mut Item) {
+        key.set_repr(to_key_repr(key.get()));
+        *key.leaf_decor_mut() = Decor::new("", "");
+        *key.dotted_decor_mut() = Decor::new("", "");
+        if let Some(value) = node.as_value_mut() {
+            *value.decor_mut() = Decor::new("", "");
+        }
+        visit_mut::visit_table_like_kv_mut(self, key, node);
+    }
+
+    fn visit_inline_table_mut(&mut self, node: &mut InlineTable) {
+        node.set_preamble("");
+        node.set_trailing("");
+        node.set_trailing_comma(false);
+        visit_mut::visit_inline_table_mut(self, node);
+    }
+
+    fn visit_array_mut(&mut self, node: &mut Array) {
+        for value in node.iter_mut() {
+            *value.decor_mut() = Decor::new("", "");
+        }
+        node.set_trailing("");
+        node.set_trailing_comma(false);
+        visit_mut::visit_array_mut(self, node);
+    }
+}
+
+struct Formatter<'o> {
+    options: &'o ToStringOptions,
+    // Whether the parent of the current key/value pair is a standard table
+    in_table: bool,
+    // Whether the parent of the current key/value pair is a multi-line inline table
+    in_multiline_inline_table: bool,
+    array_depth: usize,
+    inline_table_depth: usize,
+}
+
+impl Formatter<'_> {
+    fn format_array(&self, node: &mut Array) {
+        let is_multiline = node.is_multiline();
+        if !is_multiline && self.options.normalize_whitespace {
+            for value in node.iter_mut() {
+                value.decor_mut().clear();
+            }
+            node.set_trailing("");
+        }
+        if !is_multiline {
+            let wrap = self
+                .options
+                .max_array_width
+                .map(|max| !node.is_empty() && node.to_string().trim().len() > max)
+                .unwrap_or(false);
+            if !wrap {
+                return;
+            }
+            for value in node.iter_mut() {
+                value.decor_mut().set_prefix("\n");
+                value.decor_mut().set_suffix("");
+            }
+            node.set_trailing("\n");
+            node.set_trailing_comma(true);
+        } else if self.options.indent.is_none()
+            && !self.options.normalize_whitespace
+            && self.options.max_blank_lines.is_none()
+        {
+            return;
+        }
+
+        let width = self.options.indent.unwrap_or(4);
+        let indent = " ".repeat(width * self.array_depth);
+        let closing = " ".repeat(width * (self.array_depth - 1));
+        let trailing_newline = contains_newline(Some(node.trailing()));
+        let len = node.len();
+        for (i, value) in node.iter_mut().enumerate() {
+            let decor = value.decor_mut();
+            if let Some(prefix) = decor.prefix().and_then(RawString::as_str) {
+                if prefix.contains('\n') {
+                    let prefix = self.reindent(prefix, &indent, &indent);
+                    decor.set_prefix(prefix);
+                } else if self.options.normalize_whitespace {
+                    decor.set_prefix(if i == 0 { "" } else { " " });
+                }
+            }
+            if let Some(suffix) = decor.suffix().and_then(RawString::as_str) {
+                if suffix.contains('\n') {
+                    let last = if i + 1 == len && !trailing_newline {
+                        &closing
+                    } else {
+                        &indent
+                    };
+                    let suffix = self.reindent(suffix, &indent, last);
+                    decor.set_suffix(suffix);
+                } else if self.options.normalize_whitespace {
+                    decor.set_suffix("");
+                }
+            }
+        }
+        if let Some(trailing) = node.trailing().as_str() {
+            if trailing.contains('\n') {
+                let trailing = self.reindent(trailing, &indent, &closing);
+                node.set_trailing(trailing);
+            } else if self.options.normalize_whitespace {
+                node.set_trailing("");
+            }
+        }
+    }
+
+    fn format_inline_table(&self, node: &mut InlineTable) {
+        let is_multiline = node.is_multiline();
+        if !is_multiline {
+            if self.options.normalize_whitespace {
+                node.set_preamble("");
+                if node.trailing_comma() {
+                    node.set_trailing(" ");
+                }
+            }
+            if self.options.inline_table_wrap == InlineTableWrap::Multiline
+                && self.is_too_wide(node)
+            {
+                let (indent, closing) = self.inline_table_indent();
+                node.wrap(&indent, &closing);
+            }
+            return;
+        } else if self.options.indent.is_none()
+            && !self.options.normalize_whitespace
+            && self.options.max_blank_lines.is_none()
+        {
+            return;
+        }
+
+        let (indent, closing) = self.inline_table_indent();
+        for (key, value) in node.leaf_values_mut() {
+            let decor = key.leaf_decor_mut();
+            if let Some(prefix) = decor.prefix().and_then(RawString::as_str) {
+                if prefix.contains('\n') {
+                    let prefix = self.reindent(prefix, &indent, &indent);
+                    decor.set_prefix(prefix);
+                } else if self.options.normalize_whitespace {
+                    decor.set_prefix(" ");
+                }
+            }
+            let decor = value.decor_mut();
+            if let Some(suffix) = decor.suffix().and_then(RawString::as_str) {
+                if suffix.contains('\n') {
+                    let suffix = self.reindent(suffix, &indent, &closing);
+                    decor.set_suffix(suffix);
+                } else if self.options.normalize_whitespace {
+                    decor.set_suffix("");
+                }
+            }
+        }
+        if let Some(preamble) = node.preamble().as_str() {
+            if preamble.contains('\n') {
+                let preamble = self.reindent(preamble, &indent, &closing);
+                node.set_preamble(preamble);
+            } else if self.options.normalize_whitespace {
+                node.set_preamble("");
+            }
+        }
+        if let Some(trailing) = node.trailing().as_str() {
+            if trailing.contains('\n') {
+                let trailing = self.reindent(trailing, &indent, &closing);
+                node.set_trailing(trailing);
+            } else if self.options.normalize_whitespace {
+                node.set_trailing(" ");
+            }
+        }
+    }
+
+    /// The indentation of the key/value pairs of the current inline table, and of its `}`
+    fn inline_table_indent(&self) -> (String, String) {
+        let width = self.options.indent.unwrap_or(4);
+        let depth = self.array_depth + self.inline_table_depth;
+        (" ".repeat(width * depth), " ".repeat(width * (depth - 1)))
+    }
+
+    fn is_too_wide(&self, node: &InlineTable) -> bool {
+        self.options
+            .max_inline_table_width
+            .map(|max| !node.is_empty() && node.to_string().trim().len() > max)
+            .unwrap_or(false)
+    }
+
+    /// Lay out the whitespace and comments between two tokens of a multi-line array
+    fn reindent(&self, raw: &str, indent: &str, last: &str) -> String {
+        let raw = if self.options.normalize_whitespace {
+            normalize_lines(raw, true, indent, last)
+        } else if self.options.indent.is_some() {
+            reindent(raw, indent, last)
+        } else {
+            raw.to_owned()
+        };
+        self.collapse_blank_lines(&raw, true)
+    }
+
+    /// Lay out the whitespace and comments preceding a line
+    fn format_lines(&self, raw: Option<&RawString>) -> Option<String> {
+        let raw = raw.and_then(RawString::as_str)?;
+        let raw = if self.options.normalize_whitespace {
+            normalize_lines(raw, false, "", "")
+        } else {
+            raw.to_owned()
+        };
+        Some(self.collapse_blank_lines(&raw, false))
+    }
+
+    fn collapse_blank_lines(&self, raw: &str, mid_line: bool) -> String {
+        match self.options.max_blank_lines {
+            Some(max) => collapse_blank_lines(raw, mid_line, max),
+            None => raw.to_owned(),
+        }
+    }
+
+    /// Canonical whitespace for a key/value pair, keeping any comments
+    fn format_key_value(&self, key: &mut KeyMut<'_>, node: &mut Item) {
+        key.dotted_decor_mut().clear();
+        match node {
+            Item::Value(value) if self.in_table => {
+                if let Some(prefix) = self.format_lines(key.leaf_decor().prefix()) {
+                    key.leaf_decor_mut().set_prefix(prefix);
+                }
+                key.leaf_decor_mut().set_suffix(" ");
+                let decor = value.decor_mut();
+                decor.set_prefix(" ");
+                let suffix = decor
+                    .suffix()
+                    .and_then(RawString::as_str)
+                    .map(trailing_comment);
+                if let Some(suffix) = suffix {
+                    decor.set_suffix(suffix);
+                }
+            }
+            // The lines of multi-line inline tables are laid out by `format_inline_table`
+            Item::Value(value) if self.in_multiline_inline_table => {
+                key.leaf_decor_mut().set_suffix(" ");
+                value.decor_mut().set_prefix(" ");
+            }
+            Item::Value(value) => {
+                key.leaf_decor_mut().clear();
+                value.decor_mut().clear();
+            }
+            // The decor of header keys is within the brackets
+            _ => key.leaf_decor_mut().clear(),
+        }
+    }
+
+    /// Canonical whitespace for a table header, keeping any comments
+    fn format_header(&self, node: &mut Table) {
+        if let Some(prefix) = self.format_lines(node.decor().prefix()) {
+            node.decor_mut().set_prefix(prefix);
+        }
+        if self.options.normalize_whitespace {
+            let suffix = node
+                .decor()
+                .suffix()
+                .and_then(RawString::as_str)
+                .map(trailing_comment);
+            if let Some(suffix) = suffix {
+                node.decor_mut().set_suffix(suffix);
+            }
+        }
+    }
+}
+
+impl VisitMut for Formatter<'_> {
+    fn visit_document_mut(&mut self, node: &mut DocumentMut) {
+        visit_mut::visit_document_mut(self, node);
+        if let Some(trailing) = self.format_lines(Some(node.trailing())) {
+            node.set_trailing(trailing);
+        }
+    }
+
+    fn visit_table_mut(&mut self, node: &mut Table) {
+        if !node.is_dotted() {
+            self.format_header(node);
+        }
+        if let Some(trailing) = self.format_lines(Some(node.trailing())) {
+            node.set_trailing(trailing);
+        }
+        let in_table = std::mem::replace(&mut self.in_table, true);
+        visit_mut::visit_table_mut(self, node);
+        self.in_table = in_table;
+    }
+
+    fn visit_inline_table_mut(&mut self, node: &mut InlineTable) {
+        if node.is_dotted() {
+            // Its key/value pairs are laid out as part of the inline table holding it
+            visit_mut::visit_inline_table_mut(self, node);
+            return;
+        }
+        self.inline_table_depth += 1;
+        self.format_inline_table(node);
+        let in_table = std::mem::replace(&mut self.in_table, false);
+        let in_multiline_inline_table =
+            std::mem::replace(&mut self.in_multiline_inline_table, node.is_multiline());
+        visit_mut::visit_inline_table_mut(self, node);
+        self.in_multiline_inline_table = in_multiline_inline_table;
+        self.in_table = in_table;
+        self.inline_table_depth -= 1;
+    }
+
+    fn visit_table_like_kv_mut(&mut self, mut key: KeyMut<'_>, node: &mut Item) {
+        if let Some(repr) = self.options.key_style.to_repr(key.get()) {
+            key.set_repr(repr);
+        }
+
+        let wrap_as_table = self.options.inline_table_wrap == InlineTableWrap::StandardTable
+            && node
+                .as_inline_table()
+                .map(|table| self.is_too_wide(table))
+                .unwrap_or(false);
+        if (self.options.standard_tables || wrap_as_table) && self.in_table && node.is_value() {
+            node.make_item();
+            if !node.is_value() {
+                // Header keys are laid out differently than keys of key/value pairs
+                key.leaf_decor_mut().clear();
+                key.dotted_decor_mut().clear();
+            }
+        }
+
+        if self.options.normalize_whitespace {
+            self.format_key_value(&mut key, node);
+        } else if self.in_table && node.is_value() {
+            if let Some(prefix) = self.format_lines(key.leaf_decor().prefix()) {
+                key.leaf_decor_mut().set_prefix(prefix);
+            }
+        }
+
+        visit_mut::visit_table_like_kv_mut(self, key, node);
+    }
+
+    fn visit_array_mut(&mut self, node: &mut Array) {
+        let in_table = std::mem::replace(&mut self.in_table, false);
+        self.array_depth += 1;
+        self.format_array(node);
+        visit_mut::visit_array_mut(self, node);
+        self.array_depth -= 1;
+        self.in_table = in_table;
+    }
+}
+
+/// Indent each line after a newline by `indent`, with the final line (what precedes the next
+/// token) indented by `last`
+fn reindent(raw: &str, indent: &str, last: &str) -> String {
+    let mut lines = raw.split('\n');
+    let mut output = lines.next().unwrap_or_default().to_owned();
+    let rest = lines.collect::<Vec<_>>();
+    for (i, line) in rest.iter().enumerate() {
+        output.push('\n');
+        if i + 1 == rest.len() {
+            output.push_str(last);
+        } else {
+            let line = line.trim();
+            if !line.is_empty() {
+                output.push_str(indent);
+                output.push_str(line);
+            }
+        }
+    }
+    output
+}
+
+/// Like [`reindent`] but also trims the whitespace around comments
+///
+/// `mid_line` is whether `raw` follows a token on the same line, which a comment on the first
+/// line is then separated from by a space.
+fn normalize_lines(raw: &str, mid_line: bool, indent: &str, last: &str) -> String {
+    let mut lines = raw.split('\n');
+    let first = lines.next().unwrap_or_default().trim();
+    let rest = lines.collect::<Vec<_>>();
+    let mut output = String::new();
+    if mid_line && !first.is_empty() {
+        output.push(' ');
+        output.push_str(first);
+    } else if !mid_line {
+        output.push_str(first);
+    }
+    if rest.is_empty() {
+        return output;
+    }
+    for (i, line) in rest.iter().enumerate() {
+        output.push('\n');
+        if i + 1 == rest.len() {
+            output.push_str(last);
+        } else {
+            let line = line.trim();
+            if !line.is_empty() {
+                output.push_str(indent);
+                output.push_str(line);
+            }
+        }
+    }
+    output
+}
+
+/// Remove blank lines beyond the first `max` of each run
+///
+/// `mid_line` is whether `raw` follows a token on the same line, in which case its first line
+/// is not a line of its own.
+fn collapse_blank_lines(raw: &str, mid_line: bool, max: usize) -> String {
+    let lines = raw.split('\n').collect::<Vec<_>>();
+    let last = lines.len() - 1;
+    let mut output = String::new();
+    let mut blank = 0;
+    for (i, line) in lines.into_iter().enumerate() {
+        let is_blank = i != last && !(mid_line && i == 0) && line.trim().is_empty();
+        if is_blank {
+            blank += 1;
+            if max < blank {
+                continue;
+            }
+        } else {
+            blank = 0;
+        }
+        output.push_str(line);
+        if i != last {
+            output.push('\n');
+        }
+    }
+    output
+}
+
+/// The whitespace and comment following a value or header on its line
+fn trailing_comment(raw: &str) -> String {
+    let comment = raw.trim();
+    if comment.is_empty() {
+        String::new()
+    } else {
+        format!(" {comment}")
+    }
+}
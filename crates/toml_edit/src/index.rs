@@ -2,7 +2,7 @@ use std::ops;
 
 use crate::key::Key;
 use crate::DocumentMut;
-use crate::{value, InlineTable, Item, Table, Value};
+use crate::{value, Array, InlineTable, Item, Table, Value};
 
 // copied from
 // https://github.com/serde-rs/json/blob/master/src/value/index.rs
@@ -127,6 +127,26 @@ impl<'s> ops::IndexMut<&'s str> for InlineTable {
     }
 }
 
+impl ops::Index<usize> for Array {
+    type Output = Value;
+
+    fn index(&self, index: usize) -> &Value {
+        let len = self.len();
+        self.get(index).unwrap_or_else(|| {
+            panic!("index out of bounds: the len is {len} but the index is {index}")
+        })
+    }
+}
+
+impl ops::IndexMut<usize> for Array {
+    fn index_mut(&mut self, index: usize) -> &mut Value {
+        let len = self.len();
+        self.get_mut(index).unwrap_or_else(|| {
+            panic!("index out of bounds: the len is {len} but the index is {index}")
+        })
+    }
+}
+
 impl<'s> ops::Index<&'s str> for DocumentMut {
     type Output = Item;
 
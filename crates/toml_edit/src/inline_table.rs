@@ -1,9 +1,10 @@
 use std::iter::FromIterator;
 
 use crate::key::Key;
+use crate::raw_string::contains_newline;
 use crate::repr::Decor;
-use crate::table::{Iter, IterMut, KeyValuePairs, TableLike};
-use crate::{InternalString, Item, KeyMut, RawString, Table, Value};
+use crate::table::{Iter, IterMut, KeyValuePairs, RenameError, TableLike};
+use crate::{Array, InternalString, Item, KeyMut, RawString, Table, Value};
 
 /// Type representing a TOML inline table,
 /// payload of the `Value::InlineTable` variant
@@ -11,6 +12,9 @@ use crate::{InternalString, Item, KeyMut, RawString, Table, Value};
 pub struct InlineTable {
     // `preamble` represents whitespaces in an empty table
     preamble: RawString,
+    // `trailing` represents whitespaces after the trailing comma
+    trailing: RawString,
+    trailing_comma: bool,
     // Whether to hide an empty table
     pub(crate) implicit: bool,
     // prefix before `{` and suffix after `}`
@@ -30,6 +34,11 @@ impl InlineTable {
         Default::default()
     }
 
+    /// Creates an empty table with room for at least `capacity` key/value pairs
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self::with_pairs(KeyValuePairs::with_capacity(capacity))
+    }
+
     pub(crate) fn with_pairs(items: KeyValuePairs) -> Self {
         Self {
             items,
@@ -38,9 +47,20 @@ impl InlineTable {
     }
 
     /// Convert to a table
-    pub fn into_table(self) -> Table {
+    ///
+    /// The spacing around `=` and the dotted state are preserved, while the spacing used to lay
+    /// out the entries within the braces is reset to the defaults.
+    pub fn into_table(mut self) -> Table {
+        use indexmap::map::MutableKeys;
+        for (key, value) in self.items.iter_mut2() {
+            key.leaf_decor.clear_prefix();
+            if let Some(value) = value.as_value_mut() {
+                value.decor_mut().clear_suffix();
+            }
+        }
         let mut t = Table::with_pairs(self.items);
-        t.fmt();
+        t.set_implicit(self.implicit);
+        t.set_dotted(self.dotted);
         t
     }
 }
@@ -49,12 +69,12 @@ impl InlineTable {
 impl InlineTable {
     /// Get key/values for values that are visually children of this table
     ///
-    /// For example, this will return dotted keys
+    /// For example, this will return dotted keys, ordered by [`Key::position`]
     pub fn get_values(&self) -> Vec<(Vec<&Key>, &Value)> {
         let mut values = Vec::new();
         let root = Vec::new();
         self.append_values(&root, &mut values);
-        values
+        crate::table::sort_by_position(values)
     }
 
     pub(crate) fn append_values<'s>(
@@ -83,13 +103,23 @@ impl InlineTable {
     }
 
     /// Sorts the key/value pairs by key.
+    ///
+    /// The whitespace after `{` and before `}` stays in place, so entries that move to or from
+    /// the start or end of the table don't carry it with them.
     pub fn sort_values(&mut self) {
+        let edges = self.edge_paths();
         // Assuming standard tables have their position set and this won't negatively impact them
+        self.clear_value_positions();
+        self.sort_values_internal();
+        self.restore_edge_decor(edges);
+    }
+
+    fn sort_values_internal(&mut self) {
         self.items.sort_keys();
         for value in self.items.values_mut() {
             match value {
                 Item::Value(Value::InlineTable(table)) if table.is_dotted() => {
-                    table.sort_values();
+                    table.sort_values_internal();
                 }
                 _ => {}
             }
@@ -100,28 +130,24 @@ impl InlineTable {
     ///
     /// The comparison function receives two key and value pairs to compare (you can sort by keys or
     /// values or their combination as needed).
+    ///
+    /// As with [`InlineTable::sort_values`], the whitespace just inside the braces stays in place.
     pub fn sort_values_by<F>(&mut self, mut compare: F)
     where
         F: FnMut(&Key, &Value, &Key, &Value) -> std::cmp::Ordering,
     {
+        let edges = self.edge_paths();
+        self.clear_value_positions();
         self.sort_values_by_internal(&mut compare);
+        self.restore_edge_decor(edges);
     }
 
     fn sort_values_by_internal<F>(&mut self, compare: &mut F)
     where
         F: FnMut(&Key, &Value, &Key, &Value) -> std::cmp::Ordering,
     {
-        let modified_cmp =
-            |key1: &Key, val1: &Item, key2: &Key, val2: &Item| -> std::cmp::Ordering {
-                match (val1.as_value(), val2.as_value()) {
-                    (Some(v1), Some(v2)) => compare(key1, v1, key2, v2),
-                    (Some(_), None) => std::cmp::Ordering::Greater,
-                    (None, Some(_)) => std::cmp::Ordering::Less,
-                    (None, None) => std::cmp::Ordering::Equal,
-                }
-            };
-
-        self.items.sort_by(modified_cmp);
+        self.items
+            .sort_by(|key1, val1, key2, val2| compare_items(compare, key1, val1, key2, val2));
         for value in self.items.values_mut() {
             match value {
                 Item::Value(Value::InlineTable(table)) if table.is_dotted() => {
@@ -132,6 +158,110 @@ impl InlineTable {
         }
     }
 
+    /// Key paths of the first and last values, in the order they're rendered
+    fn edge_paths(&self) -> Option<(Vec<InternalString>, Vec<InternalString>)> {
+        let values = self.get_values();
+        let path = |keys: &[&Key]| keys.iter().map(|k| k.get().into()).collect::<Vec<_>>();
+        let first = path(&values.first()?.0);
+        let last = path(&values.last()?.0);
+        Some((first, last))
+    }
+
+    /// Move the decor just inside the braces back to the values now at the edges
+    ///
+    /// The first key's prefix follows the `{` and the last value's suffix precedes the `}`,
+    /// while the others follow and precede a `,`.
+    fn restore_edge_decor(&mut self, edges: Option<(Vec<InternalString>, Vec<InternalString>)>) {
+        if self.is_dotted() {
+            return;
+        }
+        let (Some((old_first, old_last)), Some((new_first, new_last))) = (edges, self.edge_paths())
+        else {
+            return;
+        };
+        if old_first != new_first {
+            let open = self.entry_mut(&old_first).0.leaf_decor.replace_prefix(None);
+            let separator = self.entry_mut(&new_first).0.leaf_decor.replace_prefix(open);
+            self.entry_mut(&old_first)
+                .0
+                .leaf_decor
+                .replace_prefix(separator);
+        }
+        if old_last != new_last {
+            let close = self.entry_mut(&old_last).1.decor_mut().replace_suffix(None);
+            let separator = self
+                .entry_mut(&new_last)
+                .1
+                .decor_mut()
+                .replace_suffix(close);
+            self.entry_mut(&old_last)
+                .1
+                .decor_mut()
+                .replace_suffix(separator);
+        }
+    }
+
+    fn entry_mut(&mut self, path: &[InternalString]) -> (&mut Key, &mut Value) {
+        use indexmap::map::MutableKeys;
+        let (first, rest) = path.split_first().expect("paths are never empty");
+        let (_, key, item) = self
+            .items
+            .get_full_mut2(first.as_str())
+            .expect("path was collected from this table");
+        let value = item.as_value_mut().expect("paths only lead to values");
+        if rest.is_empty() {
+            (key, value)
+        } else {
+            value
+                .as_inline_table_mut()
+                .expect("paths only lead through inline tables")
+                .entry_mut(rest)
+        }
+    }
+
+    /// Sort Key/Value Pairs of the table and all nested inline tables using the comparison
+    /// function `compare`.
+    ///
+    /// The comparison function receives the same pairs as with [`InlineTable::sort_values_by`].
+    /// Unlike it, this also sorts the contents of nested inline tables.
+    pub fn sort_values_recursive_by<F>(&mut self, mut compare: F)
+    where
+        F: FnMut(&Key, &Value, &Key, &Value) -> std::cmp::Ordering,
+    {
+        self.sort_items_recursive_by(&mut |key1: &Key, val1: &Item, key2: &Key, val2: &Item| {
+            compare_items(&mut compare, key1, val1, key2, val2)
+        });
+    }
+
+    pub(crate) fn sort_items_recursive_by<F>(&mut self, compare: &mut F)
+    where
+        F: FnMut(&Key, &Item, &Key, &Item) -> std::cmp::Ordering,
+    {
+        let edges = self.edge_paths();
+        self.clear_value_positions();
+        self.items
+            .sort_by(|key1, val1, key2, val2| compare(key1, val1, key2, val2));
+        for value in self.items.values_mut() {
+            if let Item::Value(Value::InlineTable(table)) = value {
+                table.sort_items_recursive_by(compare);
+            }
+        }
+        self.restore_edge_decor(edges);
+    }
+
+    /// Forget the order key/value pairs were parsed in, so they are rendered in map order
+    pub(crate) fn clear_value_positions(&mut self) {
+        use indexmap::map::MutableKeys;
+        for (key, value) in self.items.iter_mut2() {
+            key.position = None;
+            if let Item::Value(Value::InlineTable(table)) = value {
+                if table.is_dotted() {
+                    table.clear_value_positions();
+                }
+            }
+        }
+    }
+
     /// If a table has no key/value pairs and implicit, it will not be displayed.
     ///
     /// # Examples
@@ -158,6 +288,7 @@ impl InlineTable {
     }
 
     /// If a table has no key/value pairs and implicit, it will not be displayed.
+    #[cfg(feature = "parse")]
     pub(crate) fn is_implicit(&self) -> bool {
         self.implicit
     }
@@ -188,6 +319,9 @@ impl InlineTable {
     }
 
     /// Returns an accessor to a key's formatting
+    ///
+    /// A comment or whitespace after a key-value pair belongs to the value, see
+    /// [`InlineTable::get_key_value_mut`].
     pub fn key_mut(&mut self, key: &str) -> Option<KeyMut<'_>> {
         use indexmap::map::MutableKeys;
         self.items
@@ -222,6 +356,116 @@ impl InlineTable {
         &self.preamble
     }
 
+    /// Whether the table is written across multiple lines
+    ///
+    /// That is, whether a key/value pair or the closing brace starts a new line.
+    pub fn is_multiline(&self) -> bool {
+        contains_newline(Some(&self.preamble))
+            || contains_newline(Some(&self.trailing))
+            || self.get_values().into_iter().any(|(path, value)| {
+                let key = path.last().expect("always at least one key");
+                contains_newline(key.leaf_decor().prefix())
+                    || contains_newline(value.decor().suffix())
+            })
+    }
+
+    /// Write the table with one key/value pair per line, or on a single line
+    ///
+    /// Wrapped pairs are indented by 4 spaces and followed by a trailing comma; a table that is
+    /// already multi-line is left as-is.  Unwrapping drops any comments between the pairs.  To
+    /// indent by nesting depth, see [`ToStringOptions::indent`][crate::ToStringOptions::indent].
+    ///
+    /// Newlines within inline tables are new in TOML 1.1, so a multi-line table can only be
+    /// parsed again with [`ParseOptions::allow_inline_newlines`][crate::ParseOptions::allow_inline_newlines].
+    /// For output TOML 1.0 can read, write long tables as standard tables instead, see
+    /// [`ToStringOptions::max_inline_table_width`][crate::ToStringOptions::max_inline_table_width].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "parse")] #[cfg(feature = "display")] {
+    /// let mut table = toml_edit::InlineTable::from_iter([("x", 1), ("y", 2)]);
+    /// table.set_multiline(true);
+    /// assert!(table.is_multiline());
+    /// assert_eq!(table.to_string(), "{\n    x = 1,\n    y = 2,\n}");
+    ///
+    /// table.set_multiline(false);
+    /// assert_eq!(table.to_string(), "{ x = 1, y = 2 }");
+    /// # }
+    /// ```
+    pub fn set_multiline(&mut self, yes: bool) {
+        if !yes {
+            for (key, value) in self.leaf_values_mut() {
+                key.leaf_decor_mut().clear();
+                value.decor_mut().clear();
+            }
+            self.preamble = RawString::default();
+            self.trailing = RawString::default();
+            self.trailing_comma = false;
+            return;
+        }
+        if self.is_multiline() || self.is_empty() {
+            return;
+        }
+        self.wrap("    ", "");
+    }
+
+    /// Put each key/value pair on its own line, indented by `indent`, with `closing` before `}`
+    pub(crate) fn wrap(&mut self, indent: &str, closing: &str) {
+        for (key, value) in self.leaf_values_mut() {
+            key.leaf_decor_mut().set_prefix(format!("\n{indent}"));
+            value.decor_mut().set_suffix("");
+        }
+        self.preamble = RawString::default();
+        self.trailing = RawString::from(format!("\n{closing}"));
+        self.trailing_comma = true;
+    }
+
+    /// The keys and values of [`InlineTable::get_values`], in map order
+    pub(crate) fn leaf_values_mut(&mut self) -> Vec<(&mut Key, &mut Value)> {
+        use indexmap::map::MutableKeys;
+        let mut values = Vec::new();
+        for (key, item) in self.items.iter_mut2() {
+            let Item::Value(value) = item else {
+                continue;
+            };
+            let is_dotted = value
+                .as_inline_table()
+                .map(InlineTable::is_dotted)
+                .unwrap_or(false);
+            if !is_dotted {
+                values.push((key, value));
+            } else if let Value::InlineTable(table) = value {
+                values.extend(table.leaf_values_mut());
+            }
+        }
+        values
+    }
+
+    /// Set whether the table will use a trailing comma
+    ///
+    /// The whitespace after it is set with [`InlineTable::set_trailing`].  This is not valid
+    /// TOML 1.0, so the table can only be parsed again with
+    /// [`ParseOptions::allow_inline_trailing_comma`][crate::ParseOptions::allow_inline_trailing_comma].
+    pub fn set_trailing_comma(&mut self, yes: bool) {
+        self.trailing_comma = yes;
+    }
+
+    /// Whether the table will use a trailing comma
+    pub fn trailing_comma(&self) -> bool {
+        self.trailing_comma
+    }
+
+    /// Set whitespace after the trailing comma
+    pub fn set_trailing(&mut self, trailing: impl Into<RawString>) {
+        self.trailing = trailing.into();
+    }
+
+    /// Whitespace after the trailing comma
+    pub fn trailing(&self) -> &RawString {
+        &self.trailing
+    }
+
     /// The location within the original document
     ///
     /// This generally requires an [`ImDocument`][crate::ImDocument].
@@ -234,6 +478,7 @@ impl InlineTable {
         self.span = None;
         self.decor.despan(input);
         self.preamble.despan(input);
+        self.trailing.despan(input);
         for (key, value) in self.items.iter_mut2() {
             key.despan(input);
             value.despan(input);
@@ -278,6 +523,11 @@ impl InlineTable {
         self.items.clear();
     }
 
+    /// Reserves room for at least `additional` more key/value pairs
+    pub fn reserve(&mut self, additional: usize) {
+        self.items.reserve(additional);
+    }
+
     /// Gets the given key's corresponding entry in the Table for in-place manipulation.
     pub fn entry(&'_ mut self, key: impl Into<InternalString>) -> InlineEntry<'_> {
         match self.items.entry(key.into().into()) {
@@ -379,6 +629,52 @@ impl InlineTable {
             .expect("non-value type in inline table")
     }
 
+    /// Inserts an empty inline table if the table does not contain the key, returning the inline
+    /// table at the key
+    ///
+    /// Returns `None`, leaving the value as-is, for anything other than an inline table.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "parse")] #[cfg(feature = "display")] {
+    /// let mut table = toml_edit::InlineTable::new();
+    /// let b = table.get_or_insert_inline_table("a").unwrap();
+    /// b.insert("c", 1.into());
+    /// b.get_or_insert_array("d").unwrap().push("e");
+    /// assert_eq!(table.to_string(), r#"{ a = { c = 1, d = ["e"] } }"#);
+    /// assert!(table.get_or_insert_array("a").is_none());
+    /// # }
+    /// ```
+    pub fn get_or_insert_inline_table(
+        &mut self,
+        key: impl Into<InternalString>,
+    ) -> Option<&mut InlineTable> {
+        self.get_or_insert_item(key, || Value::InlineTable(InlineTable::new()))
+            .as_inline_table_mut()
+    }
+
+    /// Inserts an empty array if the table does not contain the key, returning the array at the
+    /// key
+    ///
+    /// Returns `None`, leaving the value as-is, for anything other than an array.
+    pub fn get_or_insert_array(&mut self, key: impl Into<InternalString>) -> Option<&mut Array> {
+        self.get_or_insert_item(key, || Value::Array(Array::new()))
+            .as_array_mut()
+    }
+
+    fn get_or_insert_item(
+        &mut self,
+        key: impl Into<InternalString>,
+        value: impl FnOnce() -> Value,
+    ) -> &mut Item {
+        let item = self.items.entry(Key::new(key)).or_insert(Item::None);
+        if item.is_none() {
+            *item = Item::Value(value());
+        }
+        item
+    }
+
     /// Inserts a key-value pair into the map.
     pub fn insert(&mut self, key: impl Into<InternalString>, value: Value) -> Option<Value> {
         use indexmap::map::MutableEntryKey;
@@ -414,6 +710,13 @@ impl InlineTable {
         }
     }
 
+    /// Changes the key of a value, keeping its position and formatting.
+    ///
+    /// See [`Table::rename_key`].
+    pub fn rename_key(&mut self, from: &str, to: impl Into<Key>) -> Result<(), RenameError> {
+        crate::table::rename_key(&mut self.items, from, to.into())
+    }
+
     /// Removes an item given the key.
     pub fn remove(&mut self, key: &str) -> Option<Value> {
         self.items
@@ -446,19 +749,35 @@ impl InlineTable {
     }
 }
 
+#[cfg(feature = "parse")]
+impl std::str::FromStr for InlineTable {
+    type Err = crate::TomlError;
+
+    /// Parses an inline table, like `{ a = 1 }`, from a &str
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.parse::<Value>()? {
+            Value::InlineTable(table) => Ok(table),
+            value => Err(crate::value::unexpected_kind(
+                crate::ItemKind::InlineTable,
+                &value,
+            )),
+        }
+    }
+}
+
 #[cfg(feature = "display")]
 impl std::fmt::Display for InlineTable {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        crate::encode::encode_table(self, f, None, ("", ""))
+        crate::encode::encode_lf(f, |buf| {
+            crate::encode::encode_table(self, buf, None, ("", ""))
+        })
     }
 }
 
 impl<K: Into<Key>, V: Into<Value>> Extend<(K, V)> for InlineTable {
     fn extend<T: IntoIterator<Item = (K, V)>>(&mut self, iter: T) {
         for (key, value) in iter {
-            let key = key.into();
-            let value = Item::Value(value.into());
-            self.items.insert(key, value);
+            self.insert_formatted(&key.into(), value.into());
         }
     }
 }
@@ -497,6 +816,25 @@ impl<'s> IntoIterator for &'s InlineTable {
     }
 }
 
+/// Compare two pairs by their values, as [`InlineTable::sort_values_by`] sees them
+fn compare_items<F>(
+    compare: &mut F,
+    key1: &Key,
+    val1: &Item,
+    key2: &Key,
+    val2: &Item,
+) -> std::cmp::Ordering
+where
+    F: FnMut(&Key, &Value, &Key, &Value) -> std::cmp::Ordering,
+{
+    match (val1.as_value(), val2.as_value()) {
+        (Some(v1), Some(v2)) => compare(key1, v1, key2, v2),
+        (Some(_), None) => std::cmp::Ordering::Greater,
+        (None, Some(_)) => std::cmp::Ordering::Less,
+        (None, None) => std::cmp::Ordering::Equal,
+    }
+}
+
 fn decorate_inline_table(table: &mut InlineTable) {
     use indexmap::map::MutableKeys;
     for (mut key, value) in table
@@ -509,6 +847,9 @@ fn decorate_inline_table(table: &mut InlineTable) {
         key.dotted_decor_mut().clear();
         value.decor_mut().clear();
     }
+    if table.trailing_comma {
+        table.set_trailing(" ");
+    }
 }
 
 /// An owned iterator type over key/value pairs of an inline table.
@@ -535,25 +876,20 @@ impl TableLike for InlineTable {
     }
     fn entry<'a>(&'a mut self, key: &str) -> crate::Entry<'a> {
         // Accept a `&str` rather than an owned type to keep `InternalString`, well, internal
-        match self.items.entry(key.into()) {
-            indexmap::map::Entry::Occupied(entry) => {
-                crate::Entry::Occupied(crate::OccupiedEntry { entry })
-            }
-            indexmap::map::Entry::Vacant(entry) => {
-                crate::Entry::Vacant(crate::VacantEntry { entry })
-            }
-        }
+        // Inline tables don't lay out what is inserted into them with a profile
+        crate::Entry::new(
+            self.items.entry(key.into()),
+            #[cfg(feature = "display")]
+            None,
+        )
     }
     fn entry_format<'a>(&'a mut self, key: &Key) -> crate::Entry<'a> {
         // Accept a `&Key` to be consistent with `entry`
-        match self.items.entry(key.get().into()) {
-            indexmap::map::Entry::Occupied(entry) => {
-                crate::Entry::Occupied(crate::OccupiedEntry { entry })
-            }
-            indexmap::map::Entry::Vacant(entry) => {
-                crate::Entry::Vacant(crate::VacantEntry { entry })
-            }
-        }
+        crate::Entry::new(
+            self.items.entry(key.get().into()),
+            #[cfg(feature = "display")]
+            None,
+        )
     }
     fn get<'s>(&'s self, key: &str) -> Option<&'s Item> {
         self.items.get(key)
@@ -611,6 +947,7 @@ impl TableLike for InlineTable {
 }
 
 // `{ key1 = value1, ... }`
+#[cfg(feature = "display")]
 pub(crate) const DEFAULT_INLINE_KEY_DECOR: (&str, &str) = (" ", " ");
 
 /// A view into a single location in a map, which may be vacant or occupied.
@@ -640,6 +977,18 @@ impl<'a> InlineEntry<'a> {
         }
     }
 
+    /// Provides in-place mutable access to an occupied entry before any potential inserts into
+    /// the map.
+    pub fn and_modify<F: FnOnce(&mut Value)>(self, f: F) -> Self {
+        match self {
+            InlineEntry::Occupied(mut entry) => {
+                f(entry.get_mut());
+                InlineEntry::Occupied(entry)
+            }
+            InlineEntry::Vacant(entry) => InlineEntry::Vacant(entry),
+        }
+    }
+
     /// Ensures a value is in the entry by inserting the default if empty, and returns
     /// a mutable reference to the value in the entry.
     pub fn or_insert(self, default: Value) -> &'a mut Value {
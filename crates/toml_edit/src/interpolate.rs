@@ -0,0 +1,107 @@
+use crate::{ItemKind, Value};
+
+/// Error returned by [`Value::interpolate`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum InterpolateError {
+    /// The value is not a string
+    NotString {
+        /// The type of the value
+        found: ItemKind,
+    },
+    /// A `${` without a closing `}`, or with no name before it
+    Malformed {
+        /// The byte offset of the `$` within the string
+        position: usize,
+    },
+    /// The lookup returned `None` for a variable
+    Undefined {
+        /// The name of the variable
+        name: String,
+    },
+}
+
+impl std::fmt::Display for InterpolateError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InterpolateError::NotString { found } => write!(f, "expected string, found {found}"),
+            InterpolateError::Malformed { position } => {
+                write!(f, "malformed `${{...}}` at position {position}")
+            }
+            InterpolateError::Undefined { name } => write!(f, "undefined variable `{name}`"),
+        }
+    }
+}
+
+impl std::error::Error for InterpolateError {}
+
+impl Value {
+    /// Substitute variables within a string, leaving the value as-is
+    ///
+    /// Variables are written as `${NAME}`, where the name runs to the next `}`, or as `$NAME`,
+    /// where the name is made of ASCII letters, digits, and `_`, not starting with a digit.  `$$`
+    /// is a literal `$`, as is a `$` not followed by a name or `{`.  Each name is resolved with
+    /// `lookup`, like [`std::env::var`], and the result is not scanned for variables again.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "parse")] {
+    /// let doc = r#"path = "${HOME}/.cache/$APP ($$5)""#.parse::<toml_edit::DocumentMut>().unwrap();
+    /// let path = doc["path"].as_value().unwrap();
+    /// let lookup = |name: &str| match name {
+    ///     "HOME" => Some("/home/me".to_owned()),
+    ///     "APP" => Some("demo".to_owned()),
+    ///     _ => None,
+    /// };
+    /// assert_eq!(path.interpolate(lookup).unwrap(), "/home/me/.cache/demo ($5)");
+    /// assert_eq!(path.as_str(), Some("${HOME}/.cache/$APP ($$5)"));
+    /// # }
+    /// ```
+    pub fn interpolate<F>(&self, lookup: F) -> Result<String, InterpolateError>
+    where
+        F: Fn(&str) -> Option<String>,
+    {
+        let raw = self
+            .as_str()
+            .ok_or(InterpolateError::NotString { found: self.kind() })?;
+        let mut output = String::with_capacity(raw.len());
+        let mut rest = raw;
+        while let Some(start) = rest.find('$') {
+            output.push_str(&rest[..start]);
+            let after = &rest[start + 1..];
+            let (name, len) = if let Some(escaped) = after.strip_prefix('$') {
+                output.push('$');
+                rest = escaped;
+                continue;
+            } else if let Some(braced) = after.strip_prefix('{') {
+                let position = raw.len() - rest.len() + start;
+                let end = braced
+                    .find('}')
+                    .filter(|end| *end != 0)
+                    .ok_or(InterpolateError::Malformed { position })?;
+                (&braced[..end], end + 2)
+            } else {
+                let len = after
+                    .char_indices()
+                    .find(|(i, c)| {
+                        !(c.is_ascii_alphabetic() || *c == '_' || (*i > 0 && c.is_ascii_digit()))
+                    })
+                    .map(|(i, _)| i)
+                    .unwrap_or(after.len());
+                (&after[..len], len)
+            };
+            if name.is_empty() {
+                output.push('$');
+            } else {
+                let value = lookup(name).ok_or_else(|| InterpolateError::Undefined {
+                    name: name.to_owned(),
+                })?;
+                output.push_str(&value);
+            }
+            rest = &after[len..];
+        }
+        output.push_str(rest);
+        Ok(output)
+    }
+}
@@ -1,10 +1,11 @@
+#[cfg(feature = "parse")]
 use std::str::FromStr;
 
 use toml_datetime::Datetime;
 
 use crate::array_of_tables::ArrayOfTables;
 use crate::table::TableLike;
-use crate::{Array, InlineTable, Table, Value};
+use crate::{Array, Formatted, InlineTable, Table, Value};
 
 /// Type representing either a value, a table, an array of tables, or none.
 #[derive(Debug, Default)]
@@ -20,6 +21,73 @@ pub enum Item {
     ArrayOfTables(ArrayOfTables),
 }
 
+/// The type of an [`Item`] or [`Value`], see [`Item::kind`]
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "parse")] {
+/// use toml_edit::ItemKind;
+///
+/// let doc = "a = 1\n[b]\n".parse::<toml_edit::DocumentMut>().unwrap();
+/// assert_eq!(doc["a"].kind(), ItemKind::Integer);
+/// assert_eq!(doc["b"].kind(), ItemKind::Table);
+/// assert_eq!(toml_edit::Item::None.kind(), ItemKind::None);
+/// assert_eq!(
+///     format!("expected {}, found {}", ItemKind::String, doc["b"].kind()),
+///     "expected string, found table"
+/// );
+/// # }
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ItemKind {
+    /// [`Item::None`]
+    None,
+    /// [`Value::String`]
+    String,
+    /// [`Value::Integer`]
+    Integer,
+    /// [`Value::Float`]
+    Float,
+    /// [`Value::Boolean`]
+    Boolean,
+    /// [`Value::Datetime`]
+    Datetime,
+    /// [`Value::Array`]
+    Array,
+    /// [`Value::InlineTable`]
+    InlineTable,
+    /// [`Item::Table`]
+    Table,
+    /// [`Item::ArrayOfTables`]
+    ArrayOfTables,
+}
+
+impl ItemKind {
+    /// Text description of the type, as returned by [`Item::type_name`]
+    pub fn name(self) -> &'static str {
+        match self {
+            ItemKind::None => "none",
+            ItemKind::String => "string",
+            ItemKind::Integer => "integer",
+            ItemKind::Float => "float",
+            ItemKind::Boolean => "boolean",
+            ItemKind::Datetime => "datetime",
+            ItemKind::Array => "array",
+            ItemKind::InlineTable => "inline table",
+            ItemKind::Table => "table",
+            ItemKind::ArrayOfTables => "array of tables",
+        }
+    }
+}
+
+impl std::fmt::Display for ItemKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
 impl Item {
     /// Sets `self` to the given item if `self` is none and
     /// returns a mutable reference to `self`.
@@ -29,6 +97,53 @@ impl Item {
         }
         self
     }
+
+    /// Sets `self` to an empty table if `self` is none and returns the table
+    ///
+    /// Returns `None`, leaving `self` as-is, for anything other than a standard table.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "parse")] {
+    /// # #[cfg(feature = "display")] {
+    /// let mut doc = "a = 1\n".parse::<toml_edit::DocumentMut>().unwrap();
+    /// let deps = doc["dependencies"].get_or_insert_table().unwrap();
+    /// deps["serde"] = toml_edit::value("1.0");
+    /// assert!(doc["a"].get_or_insert_table().is_none());
+    /// assert_eq!(doc.to_string(), "a = 1\n\n[dependencies]\nserde = \"1.0\"\n");
+    /// # }
+    /// # }
+    /// ```
+    pub fn get_or_insert_table(&mut self) -> Option<&mut Table> {
+        self.or_insert(Item::Table(Table::new())).as_table_mut()
+    }
+
+    /// Sets `self` to an empty array of tables if `self` is none and returns the array
+    ///
+    /// Returns `None`, leaving `self` as-is, for anything other than an array of tables.
+    pub fn get_or_insert_array_of_tables(&mut self) -> Option<&mut ArrayOfTables> {
+        self.or_insert(Item::ArrayOfTables(ArrayOfTables::new()))
+            .as_array_of_tables_mut()
+    }
+
+    /// Sets `self` to an empty array if `self` is none and returns the array
+    ///
+    /// Returns `None`, leaving `self` as-is, for anything other than an array value.
+    pub fn get_or_insert_array(&mut self) -> Option<&mut Array> {
+        self.or_insert(Item::Value(Value::Array(Array::new())))
+            .as_array_mut()
+    }
+
+    /// Sets `self` to `value` if `self` is none and returns the value
+    ///
+    /// Returns `None`, leaving `self` as-is, for standard tables and arrays of tables.
+    pub fn get_or_insert_value(&mut self, value: impl Into<Value>) -> Option<&mut Value> {
+        if self.is_none() {
+            *self = Item::Value(value.into());
+        }
+        self.as_value_mut()
+    }
 }
 
 // TODO: This should be generated by macro or derive
@@ -36,11 +151,16 @@ impl Item {
 impl Item {
     /// Text description of value type
     pub fn type_name(&self) -> &'static str {
+        self.kind().name()
+    }
+
+    /// The type of the item
+    pub fn kind(&self) -> ItemKind {
         match self {
-            Item::None => "none",
-            Item::Value(v) => v.type_name(),
-            Item::Table(..) => "table",
-            Item::ArrayOfTables(..) => "array of tables",
+            Item::None => ItemKind::None,
+            Item::Value(v) => v.kind(),
+            Item::Table(..) => ItemKind::Table,
+            Item::ArrayOfTables(..) => ItemKind::ArrayOfTables,
         }
     }
 
@@ -189,6 +309,37 @@ impl Item {
     pub fn is_array_of_tables(&self) -> bool {
         self.as_array_of_tables().is_some()
     }
+    /// Returns true if `self` is a table written with a `[header]`
+    ///
+    /// This includes [implicit][Table::set_implicit] tables, whose header is left out, but not
+    /// [dotted tables][Table::set_dotted] or inline tables.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "parse")] {
+    /// let doc = "a.b = 1\nc = { d = 2 }\n[e]\n".parse::<toml_edit::DocumentMut>().unwrap();
+    /// assert!(doc["a"].is_dotted_table());
+    /// assert!(doc["c"].is_inline_table());
+    /// assert!(doc["e"].is_standard_table());
+    /// assert!(!doc["a"].is_standard_table() && !doc["c"].is_standard_table());
+    /// # }
+    /// ```
+    pub fn is_standard_table(&self) -> bool {
+        self.as_table().map(|t| !t.is_dotted()).unwrap_or(false)
+    }
+    /// Returns true if `self` is a table written with dotted keys, like `a.b = 1`
+    pub fn is_dotted_table(&self) -> bool {
+        self.as_table().map(Table::is_dotted).unwrap_or(false)
+    }
+    /// Returns true if `self` is a non-empty array of inline tables, like `a = [{ b = 1 }]`
+    ///
+    /// This is the inline counterpart of [`Item::is_array_of_tables`], for `[[a]]` headers.
+    pub fn is_array_of_inline_tables(&self) -> bool {
+        self.as_array()
+            .map(|a| !a.is_empty() && a.iter().all(Value::is_inline_table))
+            .unwrap_or(false)
+    }
     /// Returns true if `self` is `None`.
     pub fn is_none(&self) -> bool {
         matches!(*self, Item::None)
@@ -201,6 +352,13 @@ impl Item {
         self.as_value().and_then(Value::as_integer)
     }
 
+    /// Casts `self` to a mutable formatted integer, to edit it in place.
+    ///
+    /// See [`Value::as_integer_mut`].
+    pub fn as_integer_mut(&mut self) -> Option<&mut Formatted<i64>> {
+        self.as_value_mut().and_then(Value::as_integer_mut)
+    }
+
     /// Returns true if `self` is an integer.
     pub fn is_integer(&self) -> bool {
         self.as_integer().is_some()
@@ -211,6 +369,13 @@ impl Item {
         self.as_value().and_then(Value::as_float)
     }
 
+    /// Casts `self` to a mutable formatted float, to edit it in place.
+    ///
+    /// See [`Value::as_float_mut`].
+    pub fn as_float_mut(&mut self) -> Option<&mut Formatted<f64>> {
+        self.as_value_mut().and_then(Value::as_float_mut)
+    }
+
     /// Returns true if `self` is a float.
     pub fn is_float(&self) -> bool {
         self.as_float().is_some()
@@ -221,6 +386,13 @@ impl Item {
         self.as_value().and_then(Value::as_bool)
     }
 
+    /// Casts `self` to a mutable formatted boolean, to edit it in place.
+    ///
+    /// See [`Value::as_bool_mut`].
+    pub fn as_bool_mut(&mut self) -> Option<&mut Formatted<bool>> {
+        self.as_value_mut().and_then(Value::as_bool_mut)
+    }
+
     /// Returns true if `self` is a boolean.
     pub fn is_bool(&self) -> bool {
         self.as_bool().is_some()
@@ -231,6 +403,13 @@ impl Item {
         self.as_value().and_then(Value::as_str)
     }
 
+    /// Casts `self` to a mutable formatted string, to edit it in place.
+    ///
+    /// See [`Value::as_string_mut`].
+    pub fn as_string_mut(&mut self) -> Option<&mut Formatted<String>> {
+        self.as_value_mut().and_then(Value::as_string_mut)
+    }
+
     /// Returns true if `self` is a string.
     pub fn is_str(&self) -> bool {
         self.as_str().is_some()
@@ -241,6 +420,13 @@ impl Item {
         self.as_value().and_then(Value::as_datetime)
     }
 
+    /// Casts `self` to a mutable formatted date-time, to edit it in place.
+    ///
+    /// See [`Value::as_datetime_mut`].
+    pub fn as_datetime_mut(&mut self) -> Option<&mut Formatted<Datetime>> {
+        self.as_value_mut().and_then(Value::as_datetime_mut)
+    }
+
     /// Returns true if `self` is a date-time.
     pub fn is_datetime(&self) -> bool {
         self.as_datetime().is_some()
@@ -297,6 +483,26 @@ impl Item {
         self.as_table_like().is_some()
     }
 
+    /// Returns the comment lines preceding the table header, if `self` is a table.
+    ///
+    /// See [`Table::comments`].
+    pub fn comments(&self) -> impl Iterator<Item = &str> {
+        self.as_table().into_iter().flat_map(Table::comments)
+    }
+
+    /// Replaces the comment lines preceding the table header, if `self` is a table.
+    ///
+    /// See [`Table::set_comments`].
+    pub fn set_comments<I, S>(&mut self, comments: I)
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        if let Some(table) = self.as_table_mut() {
+            table.set_comments(comments);
+        }
+    }
+
     /// The location within the original document
     ///
     /// This generally requires an [`ImDocument`][crate::ImDocument].
@@ -319,6 +525,130 @@ impl Item {
     }
 }
 
+/// Typed access, reporting the type found on a mismatch
+///
+/// These pair with the `TryFrom<&Item>` impls, used by [`Table::get_as`].
+impl Item {
+    /// Casts `self` to integer, see [`Item::as_integer`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "parse")] {
+    /// let doc = "port = '8080'\n".parse::<toml_edit::DocumentMut>().unwrap();
+    /// let err = doc["port"].require_integer().unwrap_err();
+    /// assert_eq!(err.to_string(), "expected integer, found string");
+    /// # }
+    /// ```
+    pub fn require_integer(&self) -> Result<i64, TypeError> {
+        self.as_integer()
+            .ok_or_else(|| self.type_error(ItemKind::Integer))
+    }
+
+    /// Casts `self` to float, see [`Item::as_float`].
+    pub fn require_float(&self) -> Result<f64, TypeError> {
+        self.as_float()
+            .ok_or_else(|| self.type_error(ItemKind::Float))
+    }
+
+    /// Casts `self` to boolean, see [`Item::as_bool`].
+    pub fn require_bool(&self) -> Result<bool, TypeError> {
+        self.as_bool()
+            .ok_or_else(|| self.type_error(ItemKind::Boolean))
+    }
+
+    /// Casts `self` to str, see [`Item::as_str`].
+    pub fn require_str(&self) -> Result<&str, TypeError> {
+        self.as_str()
+            .ok_or_else(|| self.type_error(ItemKind::String))
+    }
+
+    /// Casts `self` to date-time, see [`Item::as_datetime`].
+    pub fn require_datetime(&self) -> Result<&Datetime, TypeError> {
+        self.as_datetime()
+            .ok_or_else(|| self.type_error(ItemKind::Datetime))
+    }
+
+    /// Casts `self` to array, see [`Item::as_array`].
+    pub fn require_array(&self) -> Result<&Array, TypeError> {
+        self.as_array()
+            .ok_or_else(|| self.type_error(ItemKind::Array))
+    }
+
+    fn type_error(&self, expected: ItemKind) -> TypeError {
+        TypeError {
+            key: None,
+            expected,
+            found: self.kind(),
+        }
+    }
+}
+
+macro_rules! impl_try_from_item {
+    ($($ty:ty => $require:ident,)*) => {
+        $(
+            impl<'a> TryFrom<&'a Item> for $ty {
+                type Error = TypeError;
+
+                fn try_from(item: &'a Item) -> Result<Self, Self::Error> {
+                    item.$require()
+                }
+            }
+        )*
+    };
+}
+
+impl_try_from_item! {
+    i64 => require_integer,
+    f64 => require_float,
+    bool => require_bool,
+    &'a str => require_str,
+    &'a Datetime => require_datetime,
+    &'a Array => require_array,
+}
+
+/// Error returned when an item is not of the expected type, see [`Item::require_integer`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TypeError {
+    key: Option<String>,
+    expected: ItemKind,
+    found: ItemKind,
+}
+
+impl TypeError {
+    pub(crate) fn with_key(mut self, key: &str) -> Self {
+        self.key = Some(key.to_owned());
+        self
+    }
+
+    /// The key of the item, when looked up with [`Table::get_as`]
+    pub fn key(&self) -> Option<&str> {
+        self.key.as_deref()
+    }
+
+    /// The type that was requested
+    pub fn expected(&self) -> ItemKind {
+        self.expected
+    }
+
+    /// The type of the item, [`ItemKind::None`] if it is missing
+    pub fn found(&self) -> ItemKind {
+        self.found
+    }
+}
+
+impl std::fmt::Display for TypeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "expected {}", self.expected)?;
+        if let Some(key) = &self.key {
+            write!(f, " for `{key}`")?;
+        }
+        write!(f, ", found {}", self.found)
+    }
+}
+
+impl std::error::Error for TypeError {}
+
 impl Clone for Item {
     #[inline(never)]
     fn clone(&self) -> Self {
@@ -0,0 +1,181 @@
+use serde_json::Value as JsonValue;
+
+use crate::table::TableLike;
+use crate::{Array, DocumentMut, InlineTable, Item, Key, PathSegment, Table, Value};
+
+/// Error returned by [`from_json_value`] for JSON without a TOML equivalent
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum FromJsonError {
+    /// The root is not an object
+    NotObject,
+    /// A `null`, which TOML has no way of writing
+    Null {
+        /// Location of the `null`
+        path: Vec<PathSegment>,
+    },
+}
+
+impl std::fmt::Display for FromJsonError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let path = match self {
+            FromJsonError::NotObject => return write!(f, "expected a JSON object at the root"),
+            FromJsonError::Null { path } => path,
+        };
+        write!(f, "`null` has no TOML equivalent at `")?;
+        for (i, segment) in path.iter().enumerate() {
+            match segment {
+                PathSegment::Key(key) if i == 0 => write!(f, "{}", key.get())?,
+                PathSegment::Key(key) => write!(f, ".{}", key.get())?,
+                PathSegment::Index(index) => write!(f, "[{index}]")?,
+            }
+        }
+        write!(f, "`")
+    }
+}
+
+impl std::error::Error for FromJsonError {}
+
+/// Convert the values in a document to JSON
+///
+/// - Tables and inline tables become objects, and arrays and arrays of tables become arrays
+/// - Integers and finite floats become numbers
+/// - `nan`, `inf` and `-inf` become strings spelled like in TOML, as JSON has no numbers for them
+/// - Datetimes become strings in the form TOML writes them, which is RFC 3339 for offset
+///   datetimes
+///
+/// Formatting and comments are dropped.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "parse")] {
+/// let doc = r#"
+/// name = "demo"
+/// released = 1979-05-27T07:32:00Z
+///
+/// [[bin]]
+/// ratio = 0.5
+/// "#.parse::<toml_edit::DocumentMut>().unwrap();
+/// let json = toml_edit::to_json_value(&doc);
+/// assert_eq!(
+///     json,
+///     serde_json::json!({
+///         "name": "demo",
+///         "released": "1979-05-27T07:32:00Z",
+///         "bin": [{ "ratio": 0.5 }],
+///     })
+/// );
+/// # }
+/// ```
+pub fn to_json_value(doc: &DocumentMut) -> JsonValue {
+    table_like_to_json(doc.as_table())
+}
+
+fn table_like_to_json(table: &dyn TableLike) -> JsonValue {
+    let map = table
+        .iter()
+        .filter(|(_, item)| !item.is_none())
+        .map(|(key, item)| (key.to_owned(), item_to_json(item)))
+        .collect();
+    JsonValue::Object(map)
+}
+
+fn item_to_json(item: &Item) -> JsonValue {
+    match item {
+        Item::None => JsonValue::Null,
+        Item::Value(value) => value_to_json(value),
+        Item::Table(table) => table_like_to_json(table),
+        Item::ArrayOfTables(array) => JsonValue::Array(
+            array
+                .iter()
+                .map(|table| table_like_to_json(table))
+                .collect(),
+        ),
+    }
+}
+
+fn value_to_json(value: &Value) -> JsonValue {
+    match value {
+        Value::String(s) => JsonValue::String(s.value().clone()),
+        Value::Integer(i) => JsonValue::from(*i.value()),
+        Value::Float(f) => {
+            let f = *f.value();
+            match serde_json::Number::from_f64(f) {
+                Some(number) => JsonValue::Number(number),
+                None if f.is_nan() => JsonValue::from("nan"),
+                None if f.is_sign_positive() => JsonValue::from("inf"),
+                None => JsonValue::from("-inf"),
+            }
+        }
+        Value::Boolean(b) => JsonValue::Bool(*b.value()),
+        Value::Datetime(d) => JsonValue::String(d.value().to_string()),
+        Value::Array(array) => JsonValue::Array(array.iter().map(value_to_json).collect()),
+        Value::InlineTable(table) => table_like_to_json(table),
+    }
+}
+
+/// Build a document from JSON, laid out the way it would be written by hand
+///
+/// The root must be an object.  Objects become tables, inline ones within arrays of other values,
+/// and arrays of objects become arrays of tables, like [`Table::from_map`].  Numbers become
+/// integers when they fit in an `i64` and floats otherwise.  Strings are kept as strings, even
+/// when they hold a datetime.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "parse")] #[cfg(feature = "display")] {
+/// let json = serde_json::json!({ "name": "demo", "server": { "ports": [80, 443] } });
+/// let doc = toml_edit::from_json_value(&json).unwrap();
+/// assert_eq!(doc.to_string(), "name = \"demo\"\n\n[server]\nports = [80, 443]\n");
+///
+/// let err = toml_edit::from_json_value(&serde_json::json!({ "a": [null] })).unwrap_err();
+/// assert_eq!(err.to_string(), "`null` has no TOML equivalent at `a[0]`");
+/// # }
+/// ```
+pub fn from_json_value(json: &JsonValue) -> Result<DocumentMut, FromJsonError> {
+    let JsonValue::Object(map) = json else {
+        return Err(FromJsonError::NotObject);
+    };
+    let mut path = Vec::new();
+    let table = object_to_inline_table(map, &mut path)?;
+    Ok(DocumentMut::from(Table::from_map(table)))
+}
+
+fn object_to_inline_table(
+    map: &serde_json::Map<String, JsonValue>,
+    path: &mut Vec<PathSegment>,
+) -> Result<InlineTable, FromJsonError> {
+    let mut table = InlineTable::new();
+    for (key, json) in map {
+        path.push(PathSegment::Key(Key::new(key.as_str())));
+        let value = json_to_value(json, path)?;
+        path.pop();
+        table.insert(key.as_str(), value);
+    }
+    Ok(table)
+}
+
+fn json_to_value(json: &JsonValue, path: &mut Vec<PathSegment>) -> Result<Value, FromJsonError> {
+    let value = match json {
+        JsonValue::Null => return Err(FromJsonError::Null { path: path.clone() }),
+        JsonValue::Bool(b) => Value::from(*b),
+        JsonValue::Number(number) => match number.as_i64() {
+            Some(i) => Value::from(i),
+            None => Value::from(number.as_f64().unwrap_or(f64::NAN)),
+        },
+        JsonValue::String(s) => Value::from(s.as_str()),
+        JsonValue::Array(elements) => {
+            let mut array = Array::new();
+            for (index, json) in elements.iter().enumerate() {
+                path.push(PathSegment::Index(index));
+                array.push(json_to_value(json, path)?);
+                path.pop();
+            }
+            Value::Array(array)
+        }
+        JsonValue::Object(map) => Value::InlineTable(object_to_inline_table(map, path)?),
+    };
+    Ok(value)
+}
@@ -1,4 +1,6 @@
+#[cfg(feature = "display")]
 use std::borrow::Cow;
+#[cfg(feature = "parse")]
 use std::str::FromStr;
 
 use crate::repr::{Decor, Repr};
@@ -26,12 +28,27 @@ use crate::InternalString;
 /// For details see [toml spec](https://github.com/toml-lang/toml/#keyvalue-pair).
 ///
 /// To parse a key use `FromStr` trait implementation: `"string".parse::<Key>()`.
+///
+/// Keys compare, hash, and order by their decoded name, ignoring their formatting.  Ordering is
+/// by Unicode scalar value, like for [`str`].
+///
+/// ```
+/// # #[cfg(feature = "parse")] {
+/// use toml_edit::Key;
+///
+/// let mut keys = ["'b'", "\"a\"", "B", "a"].map(|k| k.parse::<Key>().unwrap());
+/// keys.sort();
+/// assert_eq!(keys[1], keys[2]);
+/// assert_eq!(keys.map(|k| k.get().to_owned()), ["B", "a", "a", "b"]);
+/// # }
+/// ```
 #[derive(Debug)]
 pub struct Key {
     key: InternalString,
     pub(crate) repr: Option<Repr>,
     pub(crate) leaf_decor: Decor,
     pub(crate) dotted_decor: Decor,
+    pub(crate) position: Option<usize>,
 }
 
 impl Key {
@@ -42,9 +59,46 @@ impl Key {
             repr: None,
             leaf_decor: Default::default(),
             dotted_decor: Default::default(),
+            position: None,
         }
     }
 
+    /// Create a new table key, rejecting names TOML can't write within quotes as-is
+    ///
+    /// Empty keys are discouraged by the TOML spec and keys with line breaks or other control
+    /// characters can only be written with escape sequences, so these are refused.  The key is
+    /// rendered bare when possible, otherwise quoted with basic or literal quotes depending on the
+    /// characters it contains.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use toml_edit::{InvalidKey, Key};
+    ///
+    /// # #[cfg(feature = "parse")] #[cfg(feature = "display")] {
+    /// assert_eq!(Key::try_new("name").unwrap().to_string(), "name");
+    /// assert_eq!(Key::try_new("a b").unwrap().to_string(), r#""a b""#);
+    /// assert_eq!(Key::try_new(r#"say "hi""#).unwrap().to_string(), r#"'say "hi"'"#);
+    /// # }
+    /// assert_eq!(Key::try_new(""), Err(InvalidKey::Empty));
+    /// assert_eq!(
+    ///     Key::try_new("a\nb"),
+    ///     Err(InvalidKey::ControlCharacter { ch: '\n', index: 1 })
+    /// );
+    /// ```
+    pub fn try_new(key: &str) -> Result<Self, InvalidKey> {
+        if key.is_empty() {
+            return Err(InvalidKey::Empty);
+        }
+        if let Some((index, ch)) = key
+            .char_indices()
+            .find(|(_, ch)| matches!(ch, '\u{0}'..='\u{8}' | '\u{a}'..='\u{1f}' | '\u{7f}'))
+        {
+            return Err(InvalidKey::ControlCharacter { ch, index });
+        }
+        Ok(Self::new(key))
+    }
+
     /// Parse a TOML key expression
     ///
     /// Unlike `"".parse<Key>()`, this supports dotted keys.
@@ -53,6 +107,88 @@ impl Key {
         Self::try_parse_path(repr)
     }
 
+    /// Render a dotted key, the inverse of [`Key::parse`]
+    ///
+    /// Each key uses its own representation, with its [`dotted_decor`][Key::dotted_decor]
+    /// around the dots.  The [`leaf_decor`][Key::leaf_decor] surrounding the whole dotted key is
+    /// left out.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "parse")] {
+    /// use toml_edit::Key;
+    ///
+    /// let keys = Key::parse("tool . 'my.app'.name").unwrap();
+    /// let segments = keys.iter().map(|key| key.get()).collect::<Vec<_>>();
+    /// assert_eq!(segments, ["tool", "my.app", "name"]);
+    /// assert_eq!(Key::join_dotted(&keys), "tool . 'my.app'.name");
+    ///
+    /// let keys = ["a", "b c"].map(Key::new);
+    /// assert_eq!(Key::join_dotted(&keys), "a.\"b c\"");
+    /// # }
+    /// ```
+    #[cfg(feature = "display")]
+    pub fn join_dotted(keys: &[Key]) -> String {
+        let mut dotted = String::new();
+        for (i, key) in keys.iter().enumerate() {
+            let decor = key.dotted_decor();
+            if i != 0 {
+                dotted.push('.');
+                dotted.push_str(
+                    decor
+                        .prefix()
+                        .and_then(crate::RawString::as_str)
+                        .unwrap_or(""),
+                );
+            }
+            dotted.push_str(&key.display_repr());
+            if i + 1 != keys.len() {
+                dotted.push_str(
+                    decor
+                        .suffix()
+                        .and_then(crate::RawString::as_str)
+                        .unwrap_or(""),
+                );
+            }
+        }
+        dotted
+    }
+
+    /// Build the keys of a dotted key from its segments, see [`Key::join_dotted`]
+    ///
+    /// Each segment is checked with [`Key::try_new`]; the keys render with bare dots between
+    /// them.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use toml_edit::{InvalidKey, Key};
+    ///
+    /// let keys = Key::from_dotted(["tool", "my.app", "name"]).unwrap();
+    /// assert_eq!(Key::segments(&keys).collect::<Vec<_>>(), ["tool", "my.app", "name"]);
+    /// # #[cfg(feature = "parse")] #[cfg(feature = "display")] {
+    /// assert_eq!(Key::join_dotted(&keys), "tool.\"my.app\".name");
+    /// # }
+    /// assert_eq!(Key::from_dotted(["a", ""]), Err(InvalidKey::Empty));
+    /// ```
+    pub fn from_dotted<I, S>(segments: I) -> Result<Vec<Self>, InvalidKey>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        segments
+            .into_iter()
+            .map(|segment| Self::try_new(segment.as_ref()))
+            .collect()
+    }
+
+    /// The decoded segments of a dotted key, as returned by [`Key::parse`]
+    pub fn segments(keys: &[Key]) -> impl Iterator<Item = &str> + '_ {
+        keys.iter().map(Key::get)
+    }
+
+    #[cfg(feature = "parse")]
     pub(crate) fn with_repr_unchecked(mut self, repr: Repr) -> Self {
         self.repr = Some(repr);
         self
@@ -118,11 +254,35 @@ impl Key {
     }
 
     /// Returns the surrounding whitespace for the line entry
+    ///
+    /// Only the decor of the last key of a path is rendered.  Its prefix comes before the first
+    /// key, including any comment lines above a key/value pair and its indentation.  Its suffix
+    /// comes after the last key, before the `=` of a key/value pair or the `]` of a header.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "parse")] {
+    /// # #[cfg(feature = "display")] {
+    /// let mut doc = toml_edit::DocumentMut::new();
+    /// doc["a"] = toml_edit::value(1);
+    /// let mut key = doc.as_table_mut().key_mut("a").unwrap();
+    /// key.leaf_decor_mut().set_prefix("# leading\n  ");
+    /// key.leaf_decor_mut().set_suffix("  ");
+    /// assert_eq!(doc.to_string(), "# leading\n  a  = 1\n");
+    /// # }
+    /// # }
+    /// ```
     pub fn leaf_decor_mut(&mut self) -> &mut Decor {
         &mut self.leaf_decor
     }
 
     /// Returns the surrounding whitespace for between dots
+    ///
+    /// Within a dotted path, like `a . b . c`, the prefix comes between a key and the `.` before
+    /// it and the suffix between a key and the `.` after it.  The prefix of the first key and
+    /// the suffix of the last key are not rendered, as those are covered by
+    /// [`Key::leaf_decor`] of the last key.
     pub fn dotted_decor_mut(&mut self) -> &mut Decor {
         &mut self.dotted_decor
     }
@@ -134,15 +294,38 @@ impl Key {
     }
 
     /// Returns the surrounding whitespace for the line entry
+    ///
+    /// See [`Key::leaf_decor_mut`] for where it is rendered.
     pub fn leaf_decor(&self) -> &Decor {
         &self.leaf_decor
     }
 
     /// Returns the surrounding whitespace for between dots
+    ///
+    /// See [`Key::dotted_decor_mut`] for where it is rendered.
     pub fn dotted_decor(&self) -> &Decor {
         &self.dotted_decor
     }
 
+    /// Sets the position of the key/value pair among the key/value pairs of its table
+    ///
+    /// See [`Key::position`].
+    pub fn set_position(&mut self, position: usize) {
+        self.position = Some(position);
+    }
+
+    /// The position of the key/value pair among the key/value pairs of its table
+    ///
+    /// This orders key/value pairs across dotted keys, so `a.b`, `c`, `a.d` keep the order they
+    /// were written in rather than being grouped by `a`.  Pairs without a position are placed
+    /// after the pairs preceding them.
+    ///
+    /// Returns `None` if the `Key` was created manually (i.e. not via parsing) or the pairs were
+    /// sorted.
+    pub fn position(&self) -> Option<usize> {
+        self.position
+    }
+
     /// The location within the original document
     ///
     /// This generally requires an [`ImDocument`][crate::ImDocument].
@@ -165,6 +348,33 @@ impl Key {
         self.dotted_decor.clear();
     }
 
+    /// Returns a copy of the key, rendered in `style`
+    ///
+    /// Bare and literal keys are only used when valid for the key, falling back to quoting.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "parse")] #[cfg(feature = "display")] {
+    /// use toml_edit::{Key, KeyStyle};
+    ///
+    /// let key = Key::new("name");
+    /// assert_eq!(key.to_normalized(KeyStyle::BasicQuoted).to_string(), r#""name""#);
+    /// assert_eq!(key.to_normalized(KeyStyle::LiteralQuoted).to_string(), "'name'");
+    /// let key = Key::new("it's");
+    /// assert_eq!(key.to_normalized(KeyStyle::Bare).to_string(), r#""it's""#);
+    /// assert_eq!(key.to_normalized(KeyStyle::LiteralQuoted).to_string(), r#""it's""#);
+    /// # }
+    /// ```
+    #[cfg(feature = "display")]
+    pub fn to_normalized(&self, style: crate::KeyStyle) -> Self {
+        let mut key = self.clone();
+        if let Some(repr) = style.to_repr(key.get()) {
+            key.repr = Some(repr);
+        }
+        key
+    }
+
     #[cfg(feature = "parse")]
     fn try_parse_simple(s: &str) -> Result<Key, crate::TomlError> {
         let mut key = crate::parser::parse_key(s)?;
@@ -190,6 +400,7 @@ impl Clone for Key {
             repr: self.repr.clone(),
             leaf_decor: self.leaf_decor.clone(),
             dotted_decor: self.dotted_decor.clone(),
+            position: self.position,
         }
     }
 }
@@ -260,7 +471,7 @@ impl PartialEq<String> for Key {
 #[cfg(feature = "display")]
 impl std::fmt::Display for Key {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        crate::encode::encode_key(self, f, None)
+        crate::encode::encode_lf(f, |buf| crate::encode::encode_key(self, buf, None))
     }
 }
 
@@ -276,8 +487,36 @@ impl FromStr for Key {
     }
 }
 
+/// A key name rejected by [`Key::try_new`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum InvalidKey {
+    /// The key is empty
+    Empty,
+    /// The key contains a line break or another control character
+    ControlCharacter {
+        /// The offending character
+        ch: char,
+        /// Byte offset of the character within the key
+        index: usize,
+    },
+}
+
+impl std::fmt::Display for InvalidKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InvalidKey::Empty => write!(f, "key is empty"),
+            InvalidKey::ControlCharacter { ch, index } => {
+                write!(f, "key contains control character {ch:?} at byte {index}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for InvalidKey {}
+
 #[cfg(feature = "display")]
-fn to_key_repr(key: &str) -> Repr {
+pub(crate) fn to_key_repr(key: &str) -> Repr {
     #[cfg(feature = "parse")]
     {
         if key
@@ -372,12 +611,12 @@ impl<'k> KeyMut<'k> {
         self.key.decor_mut()
     }
 
-    /// Returns the surrounding whitespace for the line entry
+    /// Returns the surrounding whitespace for the line entry, see [`Key::leaf_decor_mut`]
     pub fn leaf_decor_mut(&mut self) -> &mut Decor {
         self.key.leaf_decor_mut()
     }
 
-    /// Returns the surrounding whitespace for between dots
+    /// Returns the surrounding whitespace for between dots, see [`Key::dotted_decor_mut`]
     pub fn dotted_decor_mut(&mut self) -> &mut Decor {
         self.key.dotted_decor_mut()
     }
@@ -399,10 +638,25 @@ impl<'k> KeyMut<'k> {
         self.key.dotted_decor()
     }
 
+    /// Sets the position of the key/value pair, see [`Key::position`]
+    pub fn set_position(&mut self, position: usize) {
+        self.key.set_position(position);
+    }
+
+    /// The position of the key/value pair, see [`Key::position`]
+    pub fn position(&self) -> Option<usize> {
+        self.key.position()
+    }
+
     /// Auto formats the key.
     pub fn fmt(&mut self) {
         self.key.fmt();
     }
+
+    #[cfg(feature = "display")]
+    pub(crate) fn set_repr(&mut self, repr: Repr) {
+        self.key.repr = Some(repr);
+    }
 }
 
 impl<'k> std::ops::Deref for KeyMut<'k> {
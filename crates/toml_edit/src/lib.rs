@@ -61,11 +61,51 @@
 //! # }
 //! ```
 //!
-//! ## Limitations
+//! To build a whole document in a consistent style, create it with a [`Profile`] through
+//! [`DocumentBuilder`].
 //!
-//! Things it does not preserve:
+//! ## Feature flags
 //!
-//! * Order of dotted keys, see [issue](https://github.com/toml-rs/toml/issues/163).
+//! The document model, i.e. [`DocumentMut`], [`Item`], [`Value`] and the other editing and
+//! inspection APIs, is always available.  The rest can be compiled out:
+//!
+//! - `parse` (default): reading TOML, including the `FromStr` impls, [`ImDocument::parse`],
+//!   [`Key::parse`], [`events`] and [`RawString::from_comment`].  Without it, documents can
+//!   only be built through the editing API.
+//! - `display` (default): writing TOML, including the `Display` impls, [`ToStringOptions`]
+//!   and [`Profile`] with [`DocumentMut::to_string_with`] and [`DocumentMut::reformat`], and the methods
+//!   producing default representations, like [`Key::display_repr`].  Leaving it out drops the
+//!   formatting machinery from parse-only builds.
+//! - `serde`: the [`de`] and [`ser`] modules and `Serialize`/`Deserialize` impls.  Converting
+//!   from and to strings, like [`de::from_str`] and [`ser::to_string`], additionally requires
+//!   `parse` and `display` respectively, while converting from and to [`DocumentMut`] and
+//!   [`Item`] requires neither.
+//! - `json`: [`to_json_value`] and [`from_json_value`], converting documents from and to
+//!   `serde_json` values.
+//! - `perf`: store short strings inline to reduce allocations.
+//! - `unbounded`: remove the parser's default limit on how deeply arrays and tables may nest,
+//!   see [`Limits::max_depth`].
+//!
+//! Every combination of these builds.
+//!
+//! The crate requires `std`: tables are backed by an `IndexMap` with the standard library's
+//! hasher and the errors implement `std::error::Error`.  Rendering does not need `std::io`
+//! though, as documents are written through [`core::fmt::Write`], so they can be rendered into
+//! any buffer implementing it:
+//!
+//! ```rust
+//! # #[cfg(feature = "display")] {
+//! # #[cfg(feature = "parse")] {
+//! use core::fmt::Write as _;
+//!
+//! let mut doc = toml_edit::DocumentMut::new();
+//! doc["speed"] = toml_edit::value(115200);
+//! let mut buffer = String::new();
+//! write!(buffer, "{doc}").unwrap();
+//! assert_eq!(buffer, "speed = 115200\n");
+//! # }
+//! # }
+//! ```
 //!
 //! [`toml`]: https://docs.rs/toml/latest/toml/
 
@@ -78,20 +118,29 @@
 
 mod array;
 mod array_of_tables;
+mod diff;
 mod document;
 #[cfg(feature = "display")]
 mod encode;
 mod error;
+mod flatten;
+#[cfg(feature = "display")]
+mod format;
 mod index;
 mod inline_table;
 mod internal_string;
+mod interpolate;
 mod item;
+#[cfg(feature = "json")]
+mod json;
 mod key;
 #[cfg(feature = "parse")]
 mod parser;
+mod query;
 mod raw_string;
 mod repr;
 mod table;
+mod validate;
 mod value;
 
 #[cfg(feature = "serde")]
@@ -102,28 +151,46 @@ pub mod ser;
 pub mod visit;
 pub mod visit_mut;
 
-pub use crate::array::{Array, ArrayIntoIter, ArrayIter, ArrayIterMut};
+pub use crate::array::{Array, ArrayIntoIter, ArrayIter, ArrayIterMut, ArrayIterWithDecor};
 pub use crate::array_of_tables::{
     ArrayOfTables, ArrayOfTablesIntoIter, ArrayOfTablesIter, ArrayOfTablesIterMut,
 };
+pub use crate::diff::{diff, Change, PathSegment};
 /// Deprecated, replaced with [`DocumentMut`]
 #[deprecated(since = "0.22.6", note = "Replaced with `DocumentMut`")]
 pub type Document = DocumentMut;
 pub use crate::document::DocumentMut;
+pub use crate::document::EmptyTableOptions;
 pub use crate::document::ImDocument;
-pub use crate::error::TomlError;
+pub use crate::document::LineEnding;
+pub use crate::error::{ErrorKind, TomlError};
+pub use crate::flatten::FlattenIter;
+#[cfg(feature = "display")]
+pub use crate::format::{DocumentBuilder, InlineTableWrap, KeyStyle, Profile, ToStringOptions};
 pub use crate::inline_table::{
     InlineEntry, InlineOccupiedEntry, InlineTable, InlineTableIntoIter, InlineTableIter,
     InlineTableIterMut, InlineVacantEntry,
 };
 pub use crate::internal_string::InternalString;
-pub use crate::item::{array, table, value, Item};
-pub use crate::key::{Key, KeyMut};
+pub use crate::interpolate::InterpolateError;
+pub use crate::item::{array, table, value, Item, ItemKind, TypeError};
+#[cfg(feature = "json")]
+pub use crate::json::{from_json_value, to_json_value, FromJsonError};
+pub use crate::key::{InvalidKey, Key, KeyMut};
+#[cfg(feature = "parse")]
+pub use crate::parser::events::{events, Event, Events};
+#[cfg(feature = "parse")]
+pub use crate::parser::limits::Limits;
+#[cfg(feature = "parse")]
+pub use crate::parser::options::{ParseOptions, TomlSpec};
+pub use crate::query::QueryError;
 pub use crate::raw_string::RawString;
-pub use crate::repr::{Decor, Formatted, Repr};
+pub use crate::repr::{Decor, ExponentStyle, Formatted, Radix, Repr, SpecialFloat, StringStyle};
 pub use crate::table::{
-    Entry, IntoIter, Iter, IterMut, OccupiedEntry, Table, TableLike, VacantEntry,
+    Drain, Entry, IntoIter, IntoPairs, Iter, IterMut, OccupiedEntry, RenameError, Table, TableLike,
+    VacantEntry,
 };
+pub use crate::validate::ValidationError;
 pub use crate::value::Value;
 pub use toml_datetime::*;
 
@@ -36,19 +36,27 @@ pub(crate) fn document<'s, 'i>(
             // Remove BOM if present
             opt(b"\xEF\xBB\xBF"),
             parse_ws(state_ref),
-            repeat(0.., (
-                dispatch! {peek(any);
-                    crate::parser::trivia::COMMENT_START_SYMBOL => cut_err(parse_comment(state_ref)),
-                    crate::parser::table::STD_TABLE_OPEN => cut_err(table(state_ref)),
-                    crate::parser::trivia::LF |
-                    crate::parser::trivia::CR => parse_newline(state_ref),
-                    _ => cut_err(keyval(state_ref)),
-                },
-                parse_ws(state_ref),
-            ))
-            .map(|()| ()),
+            repeat(0.., (expression(state_ref), parse_ws(state_ref))).map(|()| ()),
             eof,
-        ).void().parse_next(i)
+        )
+            .void()
+            .parse_next(i)
+    }
+}
+
+/// An expression, after its leading whitespace
+pub(crate) fn expression<'s, 'i>(
+    state_ref: &'s RefCell<ParseState>,
+) -> impl Parser<Input<'i>, (), ContextError> + 's {
+    move |i: &mut Input<'i>| {
+        dispatch! {peek(any);
+            crate::parser::trivia::COMMENT_START_SYMBOL => cut_err(parse_comment(state_ref)),
+            crate::parser::table::STD_TABLE_OPEN => cut_err(table(state_ref)),
+            crate::parser::trivia::LF |
+            crate::parser::trivia::CR => parse_newline(state_ref),
+            _ => cut_err(keyval(state_ref)),
+        }
+        .parse_next(i)
     }
 }
 
@@ -81,7 +89,7 @@ pub(crate) fn parse_newline<'s, 'i>(
     move |i: &mut Input<'i>| {
         newline
             .span()
-            .map(|span| state.borrow_mut().on_ws(span))
+            .map(|span| state.borrow_mut().on_blank_line(span))
             .parse_next(i)
     }
 }
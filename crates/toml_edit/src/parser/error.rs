@@ -14,8 +14,17 @@ pub(crate) enum CustomError {
         actual: &'static str,
     },
     OutOfRange,
-    #[cfg_attr(feature = "unbounded", allow(dead_code))]
+    IntegerOutOfRange {
+        literal: String,
+    },
     RecursionLimitExceeded,
+    ItemLimitExceeded {
+        max: usize,
+    },
+    UnsupportedFeature {
+        feature: &'static str,
+        spec: crate::TomlSpec,
+    },
 }
 
 impl CustomError {
@@ -80,7 +89,18 @@ impl Display for CustomError {
                 )
             }
             CustomError::OutOfRange => write!(f, "value is out of range"),
+            CustomError::IntegerOutOfRange { literal } => write!(
+                f,
+                "integer `{literal}` is out of range for a 64-bit signed integer"
+            ),
             CustomError::RecursionLimitExceeded => write!(f, "recursion limit exceeded"),
+            CustomError::ItemLimitExceeded { max } => write!(
+                f,
+                "document exceeds the limit of {max} values and table headers"
+            ),
+            CustomError::UnsupportedFeature { feature, spec } => {
+                write!(f, "{feature} requires {spec}")
+            }
         }
     }
 }
@@ -0,0 +1,286 @@
+use std::collections::VecDeque;
+use std::ops::Range;
+
+use winnow::combinator::cut_err;
+use winnow::combinator::opt;
+use winnow::combinator::peek;
+use winnow::stream::Location;
+use winnow::stream::Stream;
+use winnow::token::any;
+
+use crate::key::Key;
+use crate::parser::document::parse_keyval;
+use crate::parser::prelude::*;
+use crate::parser::table::table_header;
+use crate::parser::trivia::{comment, line_ending, newline, ws};
+use crate::{Item, TomlError, Value};
+
+/// Parse a TOML document into a stream of [`Event`]s, without building a [`DocumentMut`]
+///
+/// Each top-level expression is parsed as the iterator advances. Only the syntax is checked, so
+/// semantic errors, like duplicate keys or redefined tables, are not reported. After an error,
+/// the iterator ends.
+///
+/// # Examples
+///
+/// ```
+/// use toml_edit::Event;
+///
+/// let raw = "[package]\nname = 'demo'\n";
+/// for event in toml_edit::events(raw) {
+///     if let Event::Value { value, span } = event.unwrap() {
+///         assert_eq!(value.as_str(), Some("demo"));
+///         assert_eq!(&raw[span], "'demo'");
+///     }
+/// }
+/// ```
+///
+/// [`DocumentMut`]: crate::DocumentMut
+pub fn events(raw: &str) -> Events<'_> {
+    let mut input = new_input(raw);
+    // Remove BOM if present
+    let _: PResult<_> = opt(b"\xEF\xBB\xBF").parse_next(&mut input);
+    Events {
+        input,
+        raw,
+        pending: VecDeque::new(),
+        done: false,
+    }
+}
+
+/// A syntactic element of a TOML document, see [`events`]
+///
+/// Spans are byte offsets into the parsed document. Keys and values do not carry their
+/// surrounding whitespace.
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub enum Event {
+    /// A standard table header, e.g. `[a.b]`
+    TableHeader {
+        /// The header's path
+        keys: Vec<Key>,
+        /// The location of the header, including brackets
+        span: Range<usize>,
+    },
+    /// An array of tables header, e.g. `[[a.b]]`
+    ArrayOfTablesHeader {
+        /// The header's path
+        keys: Vec<Key>,
+        /// The location of the header, including brackets
+        span: Range<usize>,
+    },
+    /// The key of a key/value pair, followed by the events for its value
+    Key {
+        /// The path of a dotted key, or the key itself
+        keys: Vec<Key>,
+        /// The location from the first key to the last
+        span: Range<usize>,
+    },
+    /// A value that is not an array or inline table
+    Value {
+        /// The parsed value
+        value: Box<Value>,
+        /// The location of the value
+        span: Range<usize>,
+    },
+    /// The `[` of an array, followed by the events for its values
+    ArrayStart {
+        /// The location of the `[`
+        span: Range<usize>,
+    },
+    /// The `]` of an array
+    ArrayEnd {
+        /// The location of the `]`
+        span: Range<usize>,
+    },
+    /// The `{` of an inline table, followed by the events for its key/value pairs
+    ///
+    /// Dotted keys within an inline table are grouped by their parent table.
+    InlineTableStart {
+        /// The location of the `{`
+        span: Range<usize>,
+    },
+    /// The `}` of an inline table
+    InlineTableEnd {
+        /// The location of the `}`
+        span: Range<usize>,
+    },
+}
+
+impl Event {
+    /// The location of the event within the parsed document
+    pub fn span(&self) -> Range<usize> {
+        match self {
+            Event::TableHeader { span, .. }
+            | Event::ArrayOfTablesHeader { span, .. }
+            | Event::Key { span, .. }
+            | Event::Value { span, .. }
+            | Event::ArrayStart { span }
+            | Event::ArrayEnd { span }
+            | Event::InlineTableStart { span }
+            | Event::InlineTableEnd { span } => span.clone(),
+        }
+    }
+}
+
+/// Iterator over the [`Event`]s of a TOML document, see [`events`]
+#[derive(Debug)]
+pub struct Events<'i> {
+    input: Input<'i>,
+    raw: &'i str,
+    pending: VecDeque<Event>,
+    done: bool,
+}
+
+impl Events<'_> {
+    /// Parse the next expression, returning `false` at the end of the document
+    fn step(&mut self) -> Result<bool, TomlError> {
+        match expression.parse_next(&mut self.input) {
+            Ok(Some(Expression::Header(keys, span, is_array))) => {
+                let keys = self.despan_keys(keys);
+                let event = if is_array {
+                    Event::ArrayOfTablesHeader { keys, span }
+                } else {
+                    Event::TableHeader { keys, span }
+                };
+                self.pending.push_back(event);
+                Ok(true)
+            }
+            Ok(Some(Expression::KeyVal(keys, value))) => {
+                self.push_key(keys);
+                self.push_value(*value);
+                Ok(true)
+            }
+            Ok(None) => Ok(self.input.eof_offset() != 0),
+            Err(err) => {
+                let err = err
+                    .into_inner()
+                    .expect("complete parsers should not report `ErrMode::Incomplete(_)`");
+                let offset = self.input.location();
+                // Iteration stops at the first error, so the source is copied at most once
+                Err(TomlError::from_context(&err, self.raw.into(), offset))
+            }
+        }
+    }
+
+    fn push_key(&mut self, keys: Vec<Key>) {
+        let start = keys.first().and_then(Key::span);
+        let end = keys.last().and_then(Key::span);
+        let span = match (start, end) {
+            (Some(start), Some(end)) => start.start..end.end,
+            _ => unreachable!("parsed keys have spans"),
+        };
+        let keys = self.despan_keys(keys);
+        self.pending.push_back(Event::Key { keys, span });
+    }
+
+    fn push_value(&mut self, value: Value) {
+        let span = value.span().expect("parsed values have spans");
+        match value {
+            Value::Array(array) => {
+                self.pending.push_back(Event::ArrayStart {
+                    span: span.start..span.start + 1,
+                });
+                for value in array {
+                    self.push_value(value);
+                }
+                self.pending.push_back(Event::ArrayEnd {
+                    span: span.end - 1..span.end,
+                });
+            }
+            Value::InlineTable(table) => {
+                self.pending.push_back(Event::InlineTableStart {
+                    span: span.start..span.start + 1,
+                });
+                self.push_inline_entries(Vec::new(), table.items);
+                self.pending.push_back(Event::InlineTableEnd {
+                    span: span.end - 1..span.end,
+                });
+            }
+            mut value => {
+                value.despan(self.raw);
+                value.decor_mut().clear();
+                let value = Box::new(value);
+                self.pending.push_back(Event::Value { value, span });
+            }
+        }
+    }
+
+    fn push_inline_entries(&mut self, parent: Vec<Key>, items: crate::table::KeyValuePairs) {
+        for (key, item) in items {
+            let mut keys = parent.clone();
+            keys.push(key);
+            match item {
+                Item::Value(Value::InlineTable(table)) if table.is_dotted() => {
+                    self.push_inline_entries(keys, table.items);
+                }
+                Item::Value(value) => {
+                    self.push_key(keys);
+                    self.push_value(value);
+                }
+                _ => unreachable!("inline tables only contain values"),
+            }
+        }
+    }
+
+    fn despan_keys(&self, mut keys: Vec<Key>) -> Vec<Key> {
+        for key in &mut keys {
+            key.despan(self.raw);
+            key.leaf_decor_mut().clear();
+            key.dotted_decor_mut().clear();
+        }
+        keys
+    }
+}
+
+impl Iterator for Events<'_> {
+    type Item = Result<Event, TomlError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(event) = self.pending.pop_front() {
+                return Some(Ok(event));
+            }
+            if self.done {
+                return None;
+            }
+            match self.step() {
+                Ok(true) => {}
+                Ok(false) => self.done = true,
+                Err(err) => {
+                    self.done = true;
+                    return Some(Err(err));
+                }
+            }
+        }
+    }
+}
+
+enum Expression {
+    Header(Vec<Key>, Range<usize>, bool),
+    KeyVal(Vec<Key>, Box<Value>),
+}
+
+// expression = ( ( ws comment ) /
+//                ( ws keyval ws [ comment ] ) /
+//                ( ws table ws [ comment ] ) /
+//                  ws )
+fn expression(input: &mut Input<'_>) -> PResult<Option<Expression>> {
+    ws.parse_next(input)?;
+    if input.eof_offset() == 0 {
+        return Ok(None);
+    }
+    dispatch! {peek(any);
+        crate::parser::trivia::COMMENT_START_SYMBOL => cut_err((comment, line_ending)).map(|_| None),
+        crate::parser::table::STD_TABLE_OPEN => cut_err(table_header)
+            .map(|((keys, span), is_array)| Some(Expression::Header(keys, span, is_array))),
+        crate::parser::trivia::LF |
+        crate::parser::trivia::CR => newline.map(|_| None),
+        _ => cut_err(parse_keyval).map(|(mut keys, (key, item))| {
+            keys.push(key);
+            let value = item.into_value().expect("keyval parses a value");
+            Some(Expression::KeyVal(keys, Box::new(value)))
+        }),
+    }
+    .parse_next(input)
+}
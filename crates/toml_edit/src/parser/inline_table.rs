@@ -1,5 +1,7 @@
 use winnow::combinator::cut_err;
 use winnow::combinator::delimited;
+use winnow::combinator::opt;
+use winnow::combinator::peek;
 use winnow::combinator::separated;
 use winnow::combinator::trace;
 use winnow::token::one_of;
@@ -8,7 +10,7 @@ use crate::key::Key;
 use crate::parser::error::CustomError;
 use crate::parser::key::key;
 use crate::parser::prelude::*;
-use crate::parser::trivia::ws;
+use crate::parser::trivia::{ws, ws_comment_newline, COMMENT_START_SYMBOL};
 use crate::parser::value::value;
 use crate::{InlineTable, Item, RawString, Value};
 
@@ -19,28 +21,64 @@ use indexmap::map::Entry;
 // inline-table = inline-table-open inline-table-keyvals inline-table-close
 pub(crate) fn inline_table<'i>(input: &mut Input<'i>) -> PResult<InlineTable> {
     trace("inline-table", move |input: &mut Input<'i>| {
-        delimited(
-            INLINE_TABLE_OPEN,
-            cut_err(inline_table_keyvals.try_map(|(kv, p)| table_from_pairs(kv, p))),
-            cut_err(INLINE_TABLE_CLOSE)
-                .context(StrContext::Label("inline table"))
-                .context(StrContext::Expected(StrContextValue::CharLiteral('}'))),
-        )
-        .parse_next(input)
+        let state = &input.state;
+        if state.allow_inline_newlines || !state.hint_inline_newlines || state.in_hinted_table {
+            return inline_table_body().parse_next(input);
+        }
+        hinted_inline_table(input)
     })
     .parse_next(input)
 }
 
+/// Only blame TOML 1.1 features when the outermost inline table would parse with them
+fn hinted_inline_table(input: &mut Input<'_>) -> PResult<InlineTable> {
+    let start = input.clone();
+    input.state.in_hinted_table = true;
+    let result = inline_table_body().parse_next(input);
+    input.state.in_hinted_table = false;
+    if result.is_ok() {
+        return result;
+    }
+    let mut retry = start.clone();
+    retry.state.allow_inline_trailing_comma = true;
+    retry.state.allow_inline_newlines = true;
+    if inline_table_body().parse_next(&mut retry).is_ok() {
+        return result;
+    }
+    *input = start;
+    input.state.hint_inline_newlines = false;
+    let result = inline_table_body().parse_next(input);
+    input.state.hint_inline_newlines = true;
+    result
+}
+
+fn inline_table_body<'i>() -> impl Parser<Input<'i>, InlineTable, ContextError> {
+    delimited(
+        INLINE_TABLE_OPEN,
+        cut_err(inline_table_keyvals.try_map(|(kv, comma, ws)| table_from_pairs(kv, comma, ws))),
+        cut_err(INLINE_TABLE_CLOSE)
+            .context(StrContext::Label("inline table"))
+            .context(StrContext::Expected(StrContextValue::CharLiteral('}'))),
+    )
+}
+
 fn table_from_pairs(
     v: Vec<(Vec<Key>, (Key, Item))>,
-    preamble: RawString,
+    trailing_comma: bool,
+    ws: RawString,
 ) -> Result<InlineTable, CustomError> {
     let mut root = InlineTable::new();
-    root.set_preamble(preamble);
+    if trailing_comma {
+        root.set_trailing_comma(true);
+        root.set_trailing(ws);
+    } else {
+        root.set_preamble(ws);
+    }
     // Assuming almost all pairs will be directly in `root`
     root.items.reserve(v.len());
 
-    for (path, (key, value)) in v {
+    for (position, (path, (mut key, value))) in v.into_iter().enumerate() {
+        key.position = Some(position);
         let table = descend_path(&mut root, &path)?;
 
         // "Likewise, using dotted keys to redefine tables already defined in [table] form is not allowed"
@@ -117,27 +155,42 @@ pub(crate) const KEYVAL_SEP: u8 = b'=';
 
 fn inline_table_keyvals(
     input: &mut Input<'_>,
-) -> PResult<(Vec<(Vec<Key>, (Key, Item))>, RawString)> {
-    (
-        separated(0.., keyval, INLINE_TABLE_SEP),
-        ws.span().map(RawString::with_span),
-    )
-        .parse_next(input)
+) -> PResult<(Vec<(Vec<Key>, (Key, Item))>, bool, RawString)> {
+    let keyvals: Vec<_> = separated(0.., keyval, INLINE_TABLE_SEP).parse_next(input)?;
+    // Only accepted when parsing leniently, see `ParseOptions::allow_inline_trailing_comma`
+    let trailing_comma = if keyvals.is_empty() {
+        false
+    } else if input.state.allow_inline_trailing_comma {
+        opt(INLINE_TABLE_SEP).parse_next(input)?.is_some()
+    } else if opt(peek((INLINE_TABLE_SEP, ws, INLINE_TABLE_CLOSE)))
+        .parse_next(input)?
+        .is_some()
+    {
+        return Err(unsupported(input, "a trailing comma in an inline table"));
+    } else {
+        false
+    };
+    let ws = inline_ws.map(RawString::with_span).parse_next(input)?;
+    Ok((keyvals, trailing_comma, ws))
 }
 
 fn keyval(input: &mut Input<'_>) -> PResult<(Vec<Key>, (Key, Item))> {
     (
+        inline_ws,
         key,
         cut_err((
             one_of(KEYVAL_SEP)
                 .context(StrContext::Expected(StrContextValue::CharLiteral('.')))
                 .context(StrContext::Expected(StrContextValue::CharLiteral('='))),
-            (ws.span(), value, ws.span()),
+            (ws.span(), value, inline_ws),
         )),
     )
-        .map(|(key, (_, v))| {
+        .map(|(prefix, key, (_, v))| {
             let mut path = key;
-            let key = path.pop().expect("grammar ensures at least 1");
+            let mut key = path.pop().expect("grammar ensures at least 1");
+            // `inline_ws` leaves no whitespace for `key` to put in the prefix
+            key.leaf_decor_mut()
+                .set_prefix(RawString::with_span(prefix));
 
             let (pre, v, suf) = v;
             let pre = RawString::with_span(pre);
@@ -148,6 +201,41 @@ fn keyval(input: &mut Input<'_>) -> PResult<(Vec<Key>, (Key, Item))> {
         .parse_next(input)
 }
 
+/// Whitespace between the parts of an inline table, where TOML 1.1 also allows newlines and
+/// comments, see `ParseOptions::allow_inline_newlines`
+fn inline_ws(input: &mut Input<'_>) -> PResult<std::ops::Range<usize>> {
+    if input.state.allow_inline_newlines {
+        return ws_comment_newline.span().parse_next(input);
+    }
+    let span = ws.span().parse_next(input)?;
+    if input.state.hint_inline_newlines
+        && opt(peek(one_of((b'\n', b'\r', COMMENT_START_SYMBOL))))
+            .parse_next(input)?
+            .is_some()
+    {
+        return Err(unsupported(
+            input,
+            "a newline or comment in an inline table",
+        ));
+    }
+    Ok(span)
+}
+
+fn unsupported(
+    input: &mut Input<'_>,
+    feature: &'static str,
+) -> winnow::error::ErrMode<ContextError> {
+    winnow::error::ErrMode::from_external_error(
+        input,
+        winnow::error::ErrorKind::Verify,
+        CustomError::UnsupportedFeature {
+            feature,
+            spec: crate::TomlSpec::V1_1,
+        },
+    )
+    .cut()
+}
+
 #[cfg(test)]
 #[cfg(feature = "parse")]
 #[cfg(feature = "display")]
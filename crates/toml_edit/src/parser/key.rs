@@ -3,11 +3,11 @@ use std::ops::RangeInclusive;
 use winnow::combinator::peek;
 use winnow::combinator::separated;
 use winnow::combinator::trace;
+use winnow::stream::Stream as _;
 use winnow::token::any;
 use winnow::token::take_while;
 
 use crate::key::Key;
-use crate::parser::error::CustomError;
 use crate::parser::prelude::*;
 use crate::parser::strings::{basic_string, literal_string};
 use crate::parser::trivia::{from_utf8_unchecked, ws};
@@ -18,7 +18,8 @@ use crate::RawString;
 // key = simple-key / dotted-key
 // dotted-key = simple-key 1*( dot-sep simple-key )
 pub(crate) fn key(input: &mut Input<'_>) -> PResult<Vec<Key>> {
-    let mut key_path = trace(
+    let start = input.checkpoint();
+    let mut key_path: Vec<Key> = trace(
         "dotted-key",
         separated(
             1..,
@@ -32,14 +33,18 @@ pub(crate) fn key(input: &mut Input<'_>) -> PResult<Vec<Key>> {
             }),
             DOT_SEP,
         )
-        .context(StrContext::Label("key"))
-        .try_map(|k: Vec<_>| {
-            // Inserting the key will require recursion down the line
-            RecursionCheck::check_depth(k.len())?;
-            Ok::<_, CustomError>(k)
-        }),
+        .context(StrContext::Label("key")),
     )
     .parse_next(input)?;
+    // Inserting the key will require recursion down the line
+    if let Err(err) = input.state.check_depth(key_path.len()) {
+        input.reset(&start);
+        return Err(winnow::error::ErrMode::from_external_error(
+            input,
+            winnow::error::ErrorKind::Verify,
+            err,
+        ));
+    }
 
     let mut leaf_decor = Decor::new("", "");
     {
@@ -91,11 +96,12 @@ fn unquoted_key<'i>(input: &mut Input<'i>) -> PResult<&'i str> {
     trace(
         "unquoted-key",
         take_while(1.., UNQUOTED_CHAR)
-            .map(|b| unsafe { from_utf8_unchecked(b, "`is_unquoted_char` filters out on-ASCII") }),
+            .map(|b| unsafe { from_utf8_unchecked(b, "`is_unquoted_char` filters out non-ASCII") }),
     )
     .parse_next(input)
 }
 
+#[cfg(feature = "display")]
 pub(crate) fn is_unquoted_char(c: u8) -> bool {
     use winnow::stream::ContainsToken;
     UNQUOTED_CHAR.contains_token(c)
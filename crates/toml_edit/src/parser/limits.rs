@@ -0,0 +1,70 @@
+/// Bounds on the input accepted by [`ImDocument::parse_with_limits`][crate::ImDocument::parse_with_limits]
+///
+/// The defaults are used when parsing without explicit limits.
+///
+/// # Examples
+///
+/// ```
+/// use toml_edit::{ImDocument, Limits};
+///
+/// let limits = Limits::new().max_depth(3).max_len(1024).max_items(100);
+/// assert!(ImDocument::parse_with_limits("a = [[1]]", limits).is_ok());
+/// assert!(ImDocument::parse_with_limits("a = [[[1]]]", limits).is_err());
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Limits {
+    pub(crate) max_depth: usize,
+    pub(crate) max_len: Option<usize>,
+    pub(crate) max_items: Option<usize>,
+}
+
+impl Limits {
+    /// The limits used when parsing without explicit ones
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// How deeply arrays, inline tables, and dotted keys may nest
+    ///
+    /// Nesting is rejected once it reaches `depth`.  By default, this is 80, unless the
+    /// `unbounded` feature is enabled.  As the parser is recursive, nesting too deeply would
+    /// overflow the stack.
+    pub fn max_depth(mut self, depth: usize) -> Self {
+        self.max_depth = depth;
+        self
+    }
+
+    /// The longest document, in bytes, to parse
+    ///
+    /// By default, there's no limit.
+    pub fn max_len(mut self, len: usize) -> Self {
+        self.max_len = Some(len);
+        self
+    }
+
+    /// The most values and table headers to parse
+    ///
+    /// Every value counts, including each element of an array, as does every `[table]` and
+    /// `[[array]]` header.  Together with [`Limits::max_len`], this bounds the memory a parsed
+    /// document takes.  By default, there's no limit.
+    ///
+    /// [`ImDocument::item_count`][crate::ImDocument::item_count] reports how many a document
+    /// used.
+    pub fn max_items(mut self, items: usize) -> Self {
+        self.max_items = Some(items);
+        self
+    }
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Self {
+            #[cfg(not(feature = "unbounded"))]
+            max_depth: 80,
+            #[cfg(feature = "unbounded")]
+            max_depth: usize::MAX,
+            max_len: None,
+            max_items: None,
+        }
+    }
+}
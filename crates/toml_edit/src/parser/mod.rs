@@ -5,9 +5,12 @@ pub(crate) mod array;
 pub(crate) mod datetime;
 pub(crate) mod document;
 pub(crate) mod error;
+pub(crate) mod events;
 pub(crate) mod inline_table;
 pub(crate) mod key;
+pub(crate) mod limits;
 pub(crate) mod numbers;
+pub(crate) mod options;
 pub(crate) mod state;
 pub(crate) mod strings;
 pub(crate) mod table;
@@ -17,21 +20,103 @@ pub(crate) mod value;
 pub(crate) use crate::error::TomlError;
 
 pub(crate) fn parse_document<S: AsRef<str>>(raw: S) -> Result<crate::ImDocument<S>, TomlError> {
+    parse_document_with_limits(raw, limits::Limits::default())
+}
+
+pub(crate) fn parse_document_with_limits<S: AsRef<str>>(
+    raw: S,
+    limits: limits::Limits,
+) -> Result<crate::ImDocument<S>, TomlError> {
+    parse_document_with_options(raw, options::ParseOptions::new().limits(limits))
+}
+
+pub(crate) fn parse_document_with_options<S: AsRef<str>>(
+    raw: S,
+    options: options::ParseOptions,
+) -> Result<crate::ImDocument<S>, TomlError> {
     use prelude::*;
 
-    let b = new_input(raw.as_ref());
+    let limits = options.limits;
+
+    let len = raw.as_ref().len();
+    if let Some(max_len) = limits.max_len.filter(|max_len| *max_len < len) {
+        return Err(TomlError::length_limit_exceeded(len, max_len));
+    }
+
+    let mut b = new_input(raw.as_ref());
+    b.state = RecursionCheck::new(limits.max_depth);
+    b.state.max_items = limits.max_items;
+    b.state.allow_inline_trailing_comma = options.allow_inline_trailing_comma;
+    b.state.allow_inline_newlines = options.allow_inline_newlines;
     let state = RefCell::new(state::ParseState::new());
     let state_ref = &state;
-    document::document(state_ref)
+    // The count is read before `parse` drops the input along with its state
+    let items = (document::document(state_ref), |i: &mut Input<'_>| {
+        Ok(i.state.items())
+    })
+        .map(|((), items)| items)
         .parse(b.clone())
         .map_err(|e| TomlError::new(e, b))?;
-    let doc = state
+    let mut doc = state
         .into_inner()
         .into_document(raw)
         .map_err(|e| TomlError::custom(e.to_string(), None))?;
+    doc.items = items;
     Ok(doc)
 }
 
+pub(crate) fn parse_document_recover<S: AsRef<str>>(
+    raw: S,
+) -> (crate::ImDocument<S>, Vec<TomlError>) {
+    use prelude::*;
+    use winnow::stream::{Location as _, Stream as _};
+
+    let mut errors = Vec::new();
+    // Copied once the first error needs it, to be shared by the rest
+    let mut source: Option<std::sync::Arc<str>> = None;
+    let mut input = new_input(raw.as_ref());
+    let state = RefCell::new(state::ParseState::new());
+    let state_ref = &state;
+    // Remove BOM if present
+    let _: PResult<_> = winnow::combinator::opt(b"\xEF\xBB\xBF").parse_next(&mut input);
+    loop {
+        let _: PResult<_> = document::parse_ws(state_ref).parse_next(&mut input);
+        let Some(&first) = input.as_ref().first() else {
+            break;
+        };
+        if let Err(err) = document::expression(state_ref).parse_next(&mut input) {
+            let err = err
+                .into_inner()
+                .expect("complete parsers should not report `ErrMode::Incomplete(_)`");
+            let source = source.get_or_insert_with(|| raw.as_ref().into()).clone();
+            errors.push(TomlError::from_context(&err, source, input.location()));
+            if first == table::STD_TABLE_OPEN {
+                if let Err(err) = state.borrow_mut().discard_table() {
+                    errors.push(TomlError::custom(err.to_string(), None));
+                }
+            }
+            // Skip the rest of the line with the error
+            let line = input
+                .as_ref()
+                .iter()
+                .position(|b| *b == b'\n')
+                .map(|i| i + 1)
+                .unwrap_or_else(|| input.eof_offset());
+            let _ = input.next_slice(line);
+            state.borrow_mut().discard_trailing();
+        }
+    }
+    let items = input.state.items();
+    let mut state = state.into_inner();
+    let span = state.current_table_span();
+    if let Err(err) = state.finalize_last_table() {
+        errors.push(TomlError::custom(err.to_string(), span));
+    }
+    let mut doc = state.into_finalized_document(raw);
+    doc.items = items;
+    (doc, errors)
+}
+
 pub(crate) fn parse_key(raw: &str) -> Result<crate::Key, TomlError> {
     use prelude::*;
 
@@ -77,6 +162,32 @@ pub(crate) fn parse_value(raw: &str) -> Result<crate::Value, TomlError> {
     }
 }
 
+pub(crate) fn parse_whitespace(raw: &str) -> Result<(), TomlError> {
+    use prelude::*;
+
+    let b = new_input(raw);
+    trivia::ws_newline
+        .parse(b.clone())
+        .map_err(|e| TomlError::new(e, b))
+}
+
+pub(crate) fn parse_trivia(raw: &str) -> Result<(), TomlError> {
+    use prelude::*;
+
+    let b = new_input(raw);
+    winnow::combinator::repeat(
+        0..,
+        winnow::combinator::alt((
+            winnow::token::take_while(1.., trivia::WSCHAR).void(),
+            trivia::newline,
+            trivia::comment,
+        )),
+    )
+    .map(|()| ())
+    .parse(b.clone())
+    .map_err(|e| TomlError::new(e, b))
+}
+
 pub(crate) mod prelude {
     pub(crate) use winnow::combinator::dispatch;
     pub(crate) use winnow::error::ContextError;
@@ -95,19 +206,43 @@ pub(crate) mod prelude {
         }
     }
 
-    #[derive(Clone, Debug, Default, PartialEq, Eq)]
+    #[derive(Clone, Debug, PartialEq, Eq)]
     pub(crate) struct RecursionCheck {
-        #[cfg(not(feature = "unbounded"))]
         current: usize,
+        limit: usize,
+        items: usize,
+        pub(crate) max_items: Option<usize>,
+        pub(crate) allow_inline_trailing_comma: bool,
+        pub(crate) allow_inline_newlines: bool,
+        /// Whether to report newlines in inline tables as needing TOML 1.1, turned off when
+        /// the table wouldn't parse with them either
+        pub(crate) hint_inline_newlines: bool,
+        /// Whether an enclosing inline table already checks if its newlines are to blame
+        pub(crate) in_hinted_table: bool,
     }
 
-    #[cfg(not(feature = "unbounded"))]
-    const LIMIT: usize = 80;
+    impl Default for RecursionCheck {
+        fn default() -> Self {
+            Self::new(super::limits::Limits::default().max_depth)
+        }
+    }
 
     impl RecursionCheck {
-        pub(crate) fn check_depth(_depth: usize) -> Result<(), super::error::CustomError> {
-            #[cfg(not(feature = "unbounded"))]
-            if LIMIT <= _depth {
+        pub(crate) fn new(limit: usize) -> Self {
+            Self {
+                current: 0,
+                limit,
+                items: 0,
+                max_items: None,
+                allow_inline_trailing_comma: false,
+                allow_inline_newlines: false,
+                hint_inline_newlines: true,
+                in_hinted_table: false,
+            }
+        }
+
+        pub(crate) fn check_depth(&self, depth: usize) -> Result<(), super::error::CustomError> {
+            if self.limit <= depth {
                 return Err(super::error::CustomError::RecursionLimitExceeded);
             }
 
@@ -115,24 +250,55 @@ pub(crate) mod prelude {
         }
 
         fn enter(&mut self) -> Result<(), super::error::CustomError> {
-            #[cfg(not(feature = "unbounded"))]
-            {
-                self.current += 1;
-                if LIMIT <= self.current {
-                    return Err(super::error::CustomError::RecursionLimitExceeded);
-                }
-            }
-            Ok(())
+            self.current += 1;
+            self.check_depth(self.current)
         }
 
         fn exit(&mut self) {
-            #[cfg(not(feature = "unbounded"))]
-            {
-                self.current -= 1;
+            self.current -= 1;
+        }
+
+        /// The values and table headers counted so far
+        pub(crate) fn items(&self) -> usize {
+            self.items
+        }
+
+        fn count_item(&mut self) -> Result<(), super::error::CustomError> {
+            self.items += 1;
+            match self.max_items {
+                Some(max) if max < self.items => {
+                    Err(super::error::CustomError::ItemLimitExceeded { max })
+                }
+                _ => Ok(()),
             }
         }
     }
 
+    /// Count a value or table header against [`Limits::max_items`][crate::Limits::max_items]
+    ///
+    /// Only what `parser` successfully parses is counted, as the state isn't rewound when
+    /// backtracking.  Exceeding the limit is reported at the start of the item.
+    pub(crate) fn counted<'b, O>(
+        mut parser: impl Parser<Input<'b>, O, ContextError>,
+    ) -> impl Parser<Input<'b>, O, ContextError> {
+        move |input: &mut Input<'b>| {
+            use winnow::stream::Stream as _;
+
+            let start = input.checkpoint();
+            let output = parser.parse_next(input)?;
+            input.state.count_item().map_err(|err| {
+                input.reset(&start);
+                winnow::error::ErrMode::from_external_error(
+                    input,
+                    winnow::error::ErrorKind::Verify,
+                    err,
+                )
+                .cut()
+            })?;
+            Ok(output)
+        }
+    }
+
     pub(crate) fn check_recursion<'b, O>(
         mut parser: impl Parser<Input<'b>, O, ContextError>,
     ) -> impl Parser<Input<'b>, O, ContextError> {
@@ -11,6 +11,7 @@ use winnow::combinator::trace;
 use winnow::token::one_of;
 use winnow::token::take;
 
+use crate::parser::error::CustomError;
 use crate::parser::prelude::*;
 use crate::parser::trivia::from_utf8_unchecked;
 
@@ -38,17 +39,31 @@ const FALSE: &[u8] = b"false";
 
 // integer = dec-int / hex-int / oct-int / bin-int
 pub(crate) fn integer(input: &mut Input<'_>) -> PResult<i64> {
-    trace("integer",
-    dispatch! {peek(opt::<_, &[u8], _, _>(take(2usize)));
-        Some(b"0x") => cut_err(hex_int.try_map(|s| i64::from_str_radix(&s.replace('_', ""), 16))),
-        Some(b"0o") => cut_err(oct_int.try_map(|s| i64::from_str_radix(&s.replace('_', ""), 8))),
-        Some(b"0b") => cut_err(bin_int.try_map(|s| i64::from_str_radix(&s.replace('_', ""), 2))),
-        _ => dec_int.and_then(cut_err(rest
-            .try_map(|s: &str| s.replace('_', "").parse())))
-    })
+    trace(
+        "integer",
+        dispatch! {peek(opt::<_, &[u8], _, _>(take(2usize)));
+            Some(b"0x") => cut_err(hex_int.with_taken().try_map(|(s, raw)| from_radix(s, raw, 16))),
+            Some(b"0o") => cut_err(oct_int.with_taken().try_map(|(s, raw)| from_radix(s, raw, 8))),
+            Some(b"0b") => cut_err(bin_int.with_taken().try_map(|(s, raw)| from_radix(s, raw, 2))),
+            _ => dec_int.and_then(cut_err(rest
+                .try_map(|s: &str| s.replace('_', "").parse().map_err(|_| out_of_range(s)))))
+        },
+    )
     .parse_next(input)
 }
 
+fn from_radix(digits: &str, raw: &[u8], radix: u32) -> Result<i64, CustomError> {
+    i64::from_str_radix(&digits.replace('_', ""), radix).map_err(|_| {
+        out_of_range(unsafe { from_utf8_unchecked(raw, "`raw` is a prefix and digits") })
+    })
+}
+
+fn out_of_range(literal: &str) -> CustomError {
+    CustomError::IntegerOutOfRange {
+        literal: literal.to_owned(),
+    }
+}
+
 // dec-int = [ minus / plus ] unsigned-dec-int
 // unsigned-dec-int = DIGIT / digit1-9 1*( DIGIT / underscore DIGIT )
 pub(crate) fn dec_int<'i>(input: &mut Input<'i>) -> PResult<&'i str> {
@@ -0,0 +1,108 @@
+use crate::parser::limits::Limits;
+
+/// How [`ImDocument::parse_with_options`][crate::ImDocument::parse_with_options] reads a document
+///
+/// The defaults follow the TOML 1.0 specification, as with [`ImDocument::parse`][crate::ImDocument::parse].
+///
+/// # Examples
+///
+/// ```
+/// use toml_edit::{ImDocument, ParseOptions};
+///
+/// let raw = "point = { x = 1, y = 2, }\n";
+/// assert!(ImDocument::parse(raw).is_err());
+///
+/// let options = ParseOptions::new().allow_inline_trailing_comma(true);
+/// let doc = ImDocument::parse_with_options(raw, options).unwrap();
+/// assert!(doc["point"].as_inline_table().unwrap().trailing_comma());
+/// ```
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct ParseOptions {
+    pub(crate) limits: Limits,
+    pub(crate) allow_inline_trailing_comma: bool,
+    pub(crate) allow_inline_newlines: bool,
+}
+
+/// A version of the TOML specification, see [`ParseOptions::spec`]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[non_exhaustive]
+pub enum TomlSpec {
+    /// [TOML 1.0](https://toml.io/en/v1.0.0)
+    #[default]
+    V1_0,
+    /// The TOML 1.1 draft, as far as it is supported
+    ///
+    /// This adds trailing commas, newlines and comments in inline tables.
+    V1_1,
+}
+
+impl std::fmt::Display for TomlSpec {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TomlSpec::V1_0 => write!(f, "TOML 1.0"),
+            TomlSpec::V1_1 => write!(f, "TOML 1.1"),
+        }
+    }
+}
+
+impl ParseOptions {
+    /// The options used when parsing without explicit ones
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Bounds on the input, see [`Limits`]
+    pub fn limits(mut self, limits: Limits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// Accept the features of `spec`
+    ///
+    /// A document using features of a later version fails to parse, with an error naming the
+    /// feature.  The individual features can also be enabled on their own, like with
+    /// [`ParseOptions::allow_inline_trailing_comma`].  This only enables features, so ones enabled
+    /// on their own stay enabled, whether before or after this call.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use toml_edit::{ImDocument, ParseOptions, TomlSpec};
+    ///
+    /// let raw = "point = { x = 1, y = 2, }\n";
+    /// let err = ImDocument::parse_with_options(raw, ParseOptions::new()).unwrap_err();
+    /// assert!(err.message().contains("a trailing comma in an inline table requires TOML 1.1"));
+    ///
+    /// let options = ParseOptions::new().spec(TomlSpec::V1_1);
+    /// assert!(ImDocument::parse_with_options(raw, options).is_ok());
+    ///
+    /// let options = ParseOptions::new()
+    ///     .allow_inline_trailing_comma(true)
+    ///     .spec(TomlSpec::V1_0);
+    /// assert!(ImDocument::parse_with_options(raw, options).is_ok());
+    /// ```
+    pub fn spec(mut self, spec: TomlSpec) -> Self {
+        self.allow_inline_trailing_comma |= TomlSpec::V1_1 <= spec;
+        self.allow_inline_newlines |= TomlSpec::V1_1 <= spec;
+        self
+    }
+
+    /// Accept a comma after the last key/value pair of an inline table, like `{ a = 1, }`
+    ///
+    /// TOML 1.0 rejects these, so this is off by default.  The comma is kept, see
+    /// [`InlineTable::trailing_comma`][crate::InlineTable::trailing_comma].
+    pub fn allow_inline_trailing_comma(mut self, yes: bool) -> Self {
+        self.allow_inline_trailing_comma = yes;
+        self
+    }
+
+    /// Accept newlines and comments between the key/value pairs of an inline table
+    ///
+    /// TOML 1.0 requires inline tables to fit on one line, so this is off by default.  The
+    /// layout is kept in the decor of the keys and values, see
+    /// [`InlineTable::is_multiline`][crate::InlineTable::is_multiline].
+    pub fn allow_inline_newlines(mut self, yes: bool) -> Self {
+        self.allow_inline_newlines = yes;
+        self
+    }
+}
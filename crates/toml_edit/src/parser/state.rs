@@ -6,10 +6,17 @@ use crate::{ArrayOfTables, ImDocument, Item, RawString, Table};
 pub(crate) struct ParseState {
     root: Table,
     trailing: Option<std::ops::Range<usize>>,
+    // Whether `trailing` has had no blank line since the last key/value pair
+    after_keyval: bool,
+    // The end of the comment lines directly after the last key/value pair
+    trailing_comments_end: Option<usize>,
     current_table_position: usize,
+    current_keyval_position: usize,
     current_table: Table,
     current_is_array: bool,
     current_table_path: Vec<Key>,
+    // Whether the current table is dropped when finalized, after its header failed to parse
+    current_is_discarded: bool,
 }
 
 impl ParseState {
@@ -19,21 +26,77 @@ impl ParseState {
         Self {
             root: Table::new(),
             trailing: None,
+            after_keyval: false,
+            trailing_comments_end: None,
             current_table_position: 0,
+            current_keyval_position: 0,
             current_table: root,
             current_is_array: false,
             current_table_path: Vec::new(),
+            current_is_discarded: false,
         }
     }
 
     pub(crate) fn into_document<S>(mut self, raw: S) -> Result<ImDocument<S>, CustomError> {
-        self.finalize_table()?;
+        self.finalize_last_table()?;
+        Ok(self.into_finalized_document(raw))
+    }
+
+    /// Finalize the table being parsed at the end of the document
+    pub(crate) fn finalize_last_table(&mut self) -> Result<(), CustomError> {
+        self.split_table_trailing();
+        self.finalize_table()
+    }
+
+    /// Build the document, after [`ParseState::finalize_table`]
+    pub(crate) fn into_finalized_document<S>(self, raw: S) -> ImDocument<S> {
         let trailing = self.trailing.map(RawString::with_span).unwrap_or_default();
-        Ok(ImDocument {
+        ImDocument {
             root: Item::Table(self.root),
             trailing,
             raw,
-        })
+            items: 0,
+        }
+    }
+
+    /// The location of the table being parsed
+    pub(crate) fn current_table_span(&self) -> Option<std::ops::Range<usize>> {
+        self.current_table.span()
+    }
+
+    /// Forget the whitespace and comments since the last expression, as a line was skipped
+    pub(crate) fn discard_trailing(&mut self) {
+        self.trailing = None;
+        self.after_keyval = false;
+        self.trailing_comments_end = None;
+    }
+
+    /// Give the current table the comment lines directly after its last key/value pair, when
+    /// a blank line separates them from what follows
+    fn split_table_trailing(&mut self) {
+        let end = self.trailing_comments_end.take();
+        let is_separated = !std::mem::replace(&mut self.after_keyval, false);
+        if let (Some(end), true, Some(trailing)) = (end, is_separated, self.trailing.clone()) {
+            self.current_table
+                .set_trailing(RawString::with_span(trailing.start..end));
+            self.trailing = Some(end..trailing.end);
+        }
+    }
+
+    /// Drop the key/value pairs up to the next header, as their table could not be started
+    pub(crate) fn discard_table(&mut self) -> Result<(), CustomError> {
+        // The root is only filled in by finalizing its key/value pairs, so an empty path with a
+        // non-empty root means the header failed after finalizing the previous table
+        let is_finalized = self.current_table_path.is_empty() && !self.root.is_empty();
+        let result = if self.current_is_discarded || is_finalized {
+            Ok(())
+        } else {
+            self.finalize_table()
+        };
+        self.current_table = Table::new();
+        self.current_table_path = Vec::new();
+        self.current_is_discarded = true;
+        result
     }
 
     pub(crate) fn on_ws(&mut self, span: std::ops::Range<usize>) {
@@ -44,7 +107,16 @@ impl ParseState {
         }
     }
 
+    /// A line with nothing but whitespace
+    pub(crate) fn on_blank_line(&mut self, span: std::ops::Range<usize>) {
+        self.on_ws(span);
+        self.after_keyval = false;
+    }
+
     pub(crate) fn on_comment(&mut self, span: std::ops::Range<usize>) {
+        if self.after_keyval {
+            self.trailing_comments_end = Some(span.end);
+        }
         if let Some(old) = self.trailing.take() {
             self.trailing = Some(old.start..span.end);
         } else {
@@ -70,6 +142,10 @@ impl ParseState {
             key.leaf_decor
                 .set_prefix(prefix.map(RawString::with_span).unwrap_or_default());
         }
+        key.position = Some(self.current_keyval_position);
+        self.current_keyval_position += 1;
+        self.after_keyval = true;
+        self.trailing_comments_end = None;
 
         if let (Some(existing), Some(value)) = (self.current_table.span(), value.span()) {
             self.current_table.span = Some((existing.start)..(value.end));
@@ -130,6 +206,7 @@ impl ParseState {
         self.current_table.set_position(self.current_table_position);
         self.current_table.span = Some(span);
         self.current_is_array = true;
+        self.current_is_discarded = false;
         self.current_table_path = path;
 
         Ok(())
@@ -150,14 +227,15 @@ impl ParseState {
         let root = &mut self.root;
         let parent_table = Self::descend_path(root, &path[..path.len() - 1], false)?;
         let key = &path[path.len() - 1];
-        if let Some(entry) = parent_table.remove(key.get()) {
-            match entry {
-                Item::Table(t) if t.implicit && !t.is_dotted() => {
+        match parent_table.get(key.get()) {
+            Some(Item::Table(t)) if t.implicit && !t.is_dotted() => {
+                if let Some(Item::Table(t)) = parent_table.remove(key.get()) {
                     self.current_table = t;
                 }
-                // Since tables cannot be defined more than once, redefining such tables using a [table] header is not allowed. Likewise, using dotted keys to redefine tables already defined in [table] form is not allowed.
-                _ => return Err(CustomError::duplicate_key(&path, path.len() - 1)),
             }
+            // Since tables cannot be defined more than once, redefining such tables using a [table] header is not allowed. Likewise, using dotted keys to redefine tables already defined in [table] form is not allowed.
+            Some(_) => return Err(CustomError::duplicate_key(&path, path.len() - 1)),
+            None => {}
         }
 
         self.current_table_position += 1;
@@ -167,6 +245,7 @@ impl ParseState {
         self.current_table.set_position(self.current_table_position);
         self.current_table.span = Some(span);
         self.current_is_array = false;
+        self.current_is_discarded = false;
         self.current_table_path = path;
 
         Ok(())
@@ -177,7 +256,9 @@ impl ParseState {
         let path = std::mem::take(&mut self.current_table_path);
 
         let root = &mut self.root;
-        if path.is_empty() {
+        if self.current_is_discarded {
+            self.current_is_discarded = false;
+        } else if path.is_empty() {
             assert!(root.is_empty());
             std::mem::swap(&mut table, root);
         } else if self.current_is_array {
@@ -276,6 +357,7 @@ impl ParseState {
     ) -> Result<(), CustomError> {
         debug_assert!(!path.is_empty());
 
+        self.split_table_trailing();
         self.finalize_table()?;
         let leading = self
             .trailing
@@ -299,6 +381,7 @@ impl ParseState {
     ) -> Result<(), CustomError> {
         debug_assert!(!path.is_empty());
 
+        self.split_table_trailing();
         self.finalize_table()?;
         let leading = self
             .trailing
@@ -8,6 +8,7 @@ use winnow::combinator::peek;
 use winnow::token::take;
 
 // https://github.com/rust-lang/rust/issues/41358
+use crate::key::Key;
 use crate::parser::key::key;
 use crate::parser::prelude::*;
 use crate::parser::state::ParseState;
@@ -29,24 +30,29 @@ pub(crate) fn std_table<'s, 'i>(
     state: &'s RefCell<ParseState>,
 ) -> impl Parser<Input<'i>, (), ContextError> + 's {
     move |i: &mut Input<'i>| {
-        (
-            delimited(
-                STD_TABLE_OPEN,
-                cut_err(key),
-                cut_err(STD_TABLE_CLOSE)
-                    .context(StrContext::Expected(StrContextValue::CharLiteral('.')))
-                    .context(StrContext::Expected(StrContextValue::StringLiteral("]"))),
-            )
-            .with_span(),
-            cut_err(line_trailing)
-                .context(StrContext::Expected(StrContextValue::CharLiteral('\n')))
-                .context(StrContext::Expected(StrContextValue::CharLiteral('#'))),
-        )
+        std_table_header
             .try_map(|((h, span), t)| state.borrow_mut().deref_mut().on_std_header(h, t, span))
             .parse_next(i)
     }
 }
 
+fn std_table_header(i: &mut Input<'_>) -> PResult<(TableHeader, std::ops::Range<usize>)> {
+    counted((
+        delimited(
+            STD_TABLE_OPEN,
+            cut_err(key),
+            cut_err(STD_TABLE_CLOSE)
+                .context(StrContext::Expected(StrContextValue::CharLiteral('.')))
+                .context(StrContext::Expected(StrContextValue::StringLiteral("]"))),
+        )
+        .with_span(),
+        cut_err(line_trailing)
+            .context(StrContext::Expected(StrContextValue::CharLiteral('\n')))
+            .context(StrContext::Expected(StrContextValue::CharLiteral('#'))),
+    ))
+    .parse_next(i)
+}
+
 // ;; Array Table
 
 // array-table = array-table-open key *( table-key-sep key) array-table-close
@@ -54,24 +60,32 @@ pub(crate) fn array_table<'s, 'i>(
     state: &'s RefCell<ParseState>,
 ) -> impl Parser<Input<'i>, (), ContextError> + 's {
     move |i: &mut Input<'i>| {
-        (
-            delimited(
-                ARRAY_TABLE_OPEN,
-                cut_err(key),
-                cut_err(ARRAY_TABLE_CLOSE)
-                    .context(StrContext::Expected(StrContextValue::CharLiteral('.')))
-                    .context(StrContext::Expected(StrContextValue::StringLiteral("]]"))),
-            )
-            .with_span(),
-            cut_err(line_trailing)
-                .context(StrContext::Expected(StrContextValue::CharLiteral('\n')))
-                .context(StrContext::Expected(StrContextValue::CharLiteral('#'))),
-        )
+        array_table_header
             .try_map(|((h, span), t)| state.borrow_mut().deref_mut().on_array_header(h, t, span))
             .parse_next(i)
     }
 }
 
+fn array_table_header(i: &mut Input<'_>) -> PResult<(TableHeader, std::ops::Range<usize>)> {
+    counted((
+        delimited(
+            ARRAY_TABLE_OPEN,
+            cut_err(key),
+            cut_err(ARRAY_TABLE_CLOSE)
+                .context(StrContext::Expected(StrContextValue::CharLiteral('.')))
+                .context(StrContext::Expected(StrContextValue::StringLiteral("]]"))),
+        )
+        .with_span(),
+        cut_err(line_trailing)
+            .context(StrContext::Expected(StrContextValue::CharLiteral('\n')))
+            .context(StrContext::Expected(StrContextValue::CharLiteral('#'))),
+    ))
+    .parse_next(i)
+}
+
+// The keys of a header with the span of the header
+type TableHeader = (Vec<Key>, std::ops::Range<usize>);
+
 // ;; Table
 
 // table = std-table / array-table
@@ -87,3 +101,13 @@ pub(crate) fn table<'s, 'i>(
         .parse_next(i)
     }
 }
+
+/// Parse a table header without tracking the document, returning whether it is an array table
+pub(crate) fn table_header(i: &mut Input<'_>) -> PResult<(TableHeader, bool)> {
+    dispatch!(peek::<_, &[u8],_,_>(take(2usize));
+        b"[[" => array_table_header.map(|(h, _)| (h, true)),
+        _ => std_table_header.map(|(h, _)| (h, false)),
+    )
+    .context(StrContext::Label("table header"))
+    .parse_next(i)
+}
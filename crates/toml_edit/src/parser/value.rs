@@ -15,7 +15,7 @@ use crate::Value;
 
 // val = string / boolean / array / inline-table / date-time / float / integer
 pub(crate) fn value(input: &mut Input<'_>) -> PResult<Value> {
-    dispatch! {peek(any);
+    counted(dispatch! {peek(any);
             crate::parser::strings::QUOTATION_MARK |
             crate::parser::strings::APOSTROPHE => string.map(|s| {
                 Value::String(Formatted::new(
@@ -80,7 +80,7 @@ pub(crate) fn value(input: &mut Input<'_>) -> PResult<Value> {
             },
     }
     .with_span()
-    .map(|(value, span)| apply_raw(value, span))
+    .map(|(value, span)| apply_raw(value, span)))
     .parse_next(input)
 }
 
@@ -0,0 +1,186 @@
+use crate::{Item, Table, TableLike, Value};
+
+/// Error returned by [`Table::query`] for a malformed expression
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QueryError {
+    position: usize,
+    expected: &'static str,
+}
+
+impl QueryError {
+    /// The byte offset within the expression where parsing failed
+    pub fn position(&self) -> usize {
+        self.position
+    }
+}
+
+impl std::fmt::Display for QueryError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "expected {} at position {}",
+            self.expected, self.position
+        )
+    }
+}
+
+impl std::error::Error for QueryError {}
+
+impl Table {
+    /// Returns all items matching a path expression, in document order
+    ///
+    /// The expression is a dotted key, like `servers.alpha.port`, where each part may be:
+    /// - A bare key, or a quoted key, like `"a.b"` or `'c'`; within double quotes, `\` escapes
+    ///   the next character
+    /// - `*`, matching every item of a table, inline table, array, or array of tables
+    /// - Followed by indices, like `[0]`, or `[*]` for every element of an array or array of
+    ///   tables
+    ///
+    /// Parts that don't apply to an item, like a key of an array, match nothing.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "parse")] {
+    /// let doc = r#"
+    /// [servers.alpha]
+    /// port = 8080
+    ///
+    /// [servers.beta]
+    /// port = 8081
+    ///
+    /// [[bin]]
+    /// name = "a"
+    /// tags = ["x", "y"]
+    /// "#.parse::<toml_edit::DocumentMut>().unwrap();
+    /// let ports = doc.query("servers.*.port").unwrap();
+    /// let ports = ports.iter().filter_map(|i| i.as_integer()).collect::<Vec<_>>();
+    /// assert_eq!(ports, [8080, 8081]);
+    ///
+    /// let tags = doc.query("bin[0].tags[*]").unwrap();
+    /// assert_eq!(tags.len(), 2);
+    ///
+    /// let err = doc.query("bin[0").unwrap_err();
+    /// assert_eq!(err.to_string(), "expected `]` at position 5");
+    /// # }
+    /// ```
+    pub fn query(&self, expr: &str) -> Result<Vec<&Item>, QueryError> {
+        let segments = parse(expr)?;
+        let (first, rest) = segments.split_first().expect("always at least one segment");
+        let mut matched = Vec::new();
+        select_in_table(self, first, &mut matched);
+        for segment in rest {
+            let mut next = Vec::new();
+            for item in matched {
+                select(item, segment, &mut next);
+            }
+            matched = next;
+        }
+        Ok(matched)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Segment {
+    Key(String),
+    Index(usize),
+    // `*` or `[*]`
+    Any,
+}
+
+fn select<'a>(item: &'a Item, segment: &Segment, matched: &mut Vec<&'a Item>) {
+    let elements = match item {
+        Item::Value(Value::Array(array)) => &array.values,
+        Item::ArrayOfTables(array) => &array.values,
+        _ => {
+            if let Some(table) = item.as_table_like() {
+                select_in_table(table, segment, matched);
+            }
+            return;
+        }
+    };
+    match segment {
+        Segment::Key(_) => {}
+        Segment::Index(index) => matched.extend(elements.get(*index)),
+        Segment::Any => matched.extend(elements),
+    }
+}
+
+fn select_in_table<'a>(table: &'a dyn TableLike, segment: &Segment, matched: &mut Vec<&'a Item>) {
+    match segment {
+        Segment::Key(key) => matched.extend(table.get(key)),
+        Segment::Index(_) => {}
+        Segment::Any => matched.extend(table.iter().map(|(_, item)| item)),
+    }
+}
+
+fn parse(expr: &str) -> Result<Vec<Segment>, QueryError> {
+    let bytes = expr.as_bytes();
+    let error = |position, expected| QueryError { position, expected };
+    let mut segments = Vec::new();
+    let mut i = 0;
+    loop {
+        // A key, or `*`
+        match bytes.get(i) {
+            Some(b'*') => {
+                segments.push(Segment::Any);
+                i += 1;
+            }
+            Some(&quote @ (b'"' | b'\'')) => {
+                let (key, len) = quoted(&expr[i..], quote)
+                    .ok_or_else(|| error(expr.len(), if quote == b'"' { "`\"`" } else { "`'`" }))?;
+                segments.push(Segment::Key(key));
+                i += len;
+            }
+            _ => {
+                let len = expr[i..]
+                    .bytes()
+                    .take_while(|b| b.is_ascii_alphanumeric() || *b == b'-' || *b == b'_')
+                    .count();
+                if len == 0 {
+                    return Err(error(i, "a key"));
+                }
+                segments.push(Segment::Key(expr[i..i + len].to_owned()));
+                i += len;
+            }
+        }
+        // Indices
+        while bytes.get(i) == Some(&b'[') {
+            i += 1;
+            if bytes.get(i) == Some(&b'*') {
+                segments.push(Segment::Any);
+                i += 1;
+            } else {
+                let len = expr[i..].bytes().take_while(u8::is_ascii_digit).count();
+                let index = expr[i..i + len]
+                    .parse()
+                    .map_err(|_| error(i, "an index or `*`"))?;
+                segments.push(Segment::Index(index));
+                i += len;
+            }
+            if bytes.get(i) != Some(&b']') {
+                return Err(error(i, "`]`"));
+            }
+            i += 1;
+        }
+        match bytes.get(i) {
+            None => return Ok(segments),
+            Some(b'.') => i += 1,
+            Some(_) => return Err(error(i, "`.` or `[`")),
+        }
+    }
+}
+
+/// A quoted key at the start of `s` and its length, including the quotes
+fn quoted(s: &str, quote: u8) -> Option<(String, usize)> {
+    let mut key = String::new();
+    let mut chars = s.char_indices().skip(1);
+    while let Some((i, c)) = chars.next() {
+        match c {
+            c if c as u32 == quote as u32 => return Some((key, i + 1)),
+            '\\' if quote == b'"' => key.push(chars.next()?.1),
+            c => key.push(c),
+        }
+    }
+    None
+}
@@ -7,11 +7,15 @@ pub struct RawString(RawStringInner);
 #[derive(PartialEq, Eq, Clone, Hash)]
 enum RawStringInner {
     Empty,
+    // Common whitespace, cloned without allocating, see `RawString::explicit`
+    Static(&'static str),
     Explicit(InternalString),
+    #[cfg_attr(not(feature = "parse"), allow(dead_code))]
     Spanned(std::ops::Range<usize>),
 }
 
 impl RawString {
+    #[cfg(feature = "parse")]
     pub(crate) fn with_span(span: std::ops::Range<usize>) -> Self {
         if span.start == span.end {
             RawString(RawStringInner::Empty)
@@ -20,12 +24,52 @@ impl RawString {
         }
     }
 
+    /// Create decor consisting only of whitespace and newlines
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "parse")] {
+    /// use toml_edit::RawString;
+    ///
+    /// assert_eq!(RawString::from_whitespace("\n  ").unwrap().as_str(), Some("\n  "));
+    /// assert!(RawString::from_whitespace("# hi").is_err());
+    /// # }
+    /// ```
+    #[cfg(feature = "parse")]
+    pub fn from_whitespace(raw: &str) -> Result<Self, crate::TomlError> {
+        crate::parser::parse_whitespace(raw)?;
+        Ok(raw.into())
+    }
+
+    /// Create decor consisting of comments, whitespace and newlines
+    ///
+    /// A comment runs to the end of its line, so end `raw` with a newline when it precedes
+    /// anything on the same line, like a key's prefix.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "parse")] {
+    /// use toml_edit::RawString;
+    ///
+    /// assert_eq!(RawString::from_comment(" # hi").unwrap().as_str(), Some(" # hi"));
+    /// assert!(RawString::from_comment("# hi\nkey = 1").is_err());
+    /// # }
+    /// ```
+    #[cfg(feature = "parse")]
+    pub fn from_comment(raw: &str) -> Result<Self, crate::TomlError> {
+        crate::parser::parse_trivia(raw)?;
+        Ok(raw.into())
+    }
+
     /// Access the underlying string
     ///
     /// This generally requires a [`DocumentMut`][crate::DocumentMut].
     pub fn as_str(&self) -> Option<&str> {
         match &self.0 {
             RawStringInner::Empty => Some(""),
+            RawStringInner::Static(s) => Some(s),
             RawStringInner::Explicit(s) => Some(s.as_str()),
             RawStringInner::Spanned(_) => None,
         }
@@ -37,14 +81,17 @@ impl RawString {
     pub fn span(&self) -> Option<std::ops::Range<usize>> {
         match &self.0 {
             RawStringInner::Empty => None,
+            RawStringInner::Static(_) => None,
             RawStringInner::Explicit(_) => None,
             RawStringInner::Spanned(span) => Some(span.clone()),
         }
     }
 
+    #[cfg(feature = "display")]
     pub(crate) fn to_str<'s>(&'s self, input: &'s str) -> &'s str {
         match &self.0 {
             RawStringInner::Empty => "",
+            RawStringInner::Static(s) => s,
             RawStringInner::Explicit(s) => s.as_str(),
             RawStringInner::Spanned(span) => input
                 .get(span.clone())
@@ -52,6 +99,7 @@ impl RawString {
         }
     }
 
+    #[cfg(feature = "display")]
     pub(crate) fn to_str_with_default<'s>(
         &'s self,
         input: Option<&'s str>,
@@ -59,6 +107,7 @@ impl RawString {
     ) -> &'s str {
         match &self.0 {
             RawStringInner::Empty => "",
+            RawStringInner::Static(s) => s,
             RawStringInner::Explicit(s) => s.as_str(),
             RawStringInner::Spanned(span) => {
                 if let Some(input) = input {
@@ -75,6 +124,7 @@ impl RawString {
     pub(crate) fn despan(&mut self, input: &str) {
         match &self.0 {
             RawStringInner::Empty => {}
+            RawStringInner::Static(_) => {}
             RawStringInner::Explicit(_) => {}
             RawStringInner::Spanned(span) => {
                 *self = Self::from(input.get(span.clone()).unwrap_or_else(|| {
@@ -84,13 +134,10 @@ impl RawString {
         }
     }
 
+    /// Write the text as-is, leaving its line breaks to the caller's `LineEndingWriter`
     #[cfg(feature = "display")]
     pub(crate) fn encode(&self, buf: &mut dyn std::fmt::Write, input: &str) -> std::fmt::Result {
-        let raw = self.to_str(input);
-        for part in raw.split('\r') {
-            write!(buf, "{part}")?;
-        }
-        Ok(())
+        buf.write_str(self.to_str(input))
     }
 
     #[cfg(feature = "display")]
@@ -100,14 +147,42 @@ impl RawString {
         input: Option<&str>,
         default: &str,
     ) -> std::fmt::Result {
-        let raw = self.to_str_with_default(input, default);
-        for part in raw.split('\r') {
-            write!(buf, "{part}")?;
+        buf.write_str(self.to_str_with_default(input, default))
+    }
+}
+
+/// Up to two newlines followed by indentation, the bulk of the whitespace between items
+const COMMON: &str = "\n\n                                ";
+
+impl RawString {
+    /// Wrap a constant, like the default decor, without allocating
+    pub(crate) const fn from_static(s: &'static str) -> Self {
+        if s.is_empty() {
+            Self(RawStringInner::Empty)
+        } else {
+            Self(RawStringInner::Static(s))
+        }
+    }
+
+    /// Refer to `s` within [`COMMON`] rather than allocating, so documents clone cheaply
+    fn common(s: &str) -> Option<Self> {
+        if s.is_empty() {
+            return Some(Self(RawStringInner::Empty));
         }
-        Ok(())
+        let newlines = s.bytes().take_while(|b| *b == b'\n').count();
+        let start = 2usize.checked_sub(newlines)?;
+        let common = COMMON.get(start..start + s.len())?;
+        (common == s).then_some(Self(RawStringInner::Static(common)))
     }
 }
 
+/// Whether `raw` is known and spans several lines
+pub(crate) fn contains_newline(raw: Option<&RawString>) -> bool {
+    raw.and_then(RawString::as_str)
+        .map(|s| s.contains('\n'))
+        .unwrap_or(false)
+}
+
 impl Default for RawString {
     fn default() -> Self {
         Self(RawStringInner::Empty)
@@ -119,6 +194,7 @@ impl std::fmt::Debug for RawString {
     fn fmt(&self, formatter: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
         match &self.0 {
             RawStringInner::Empty => write!(formatter, "empty"),
+            RawStringInner::Static(s) => write!(formatter, "{s:?}"),
             RawStringInner::Explicit(s) => write!(formatter, "{s:?}"),
             RawStringInner::Spanned(s) => write!(formatter, "{s:?}"),
         }
@@ -128,61 +204,41 @@ impl std::fmt::Debug for RawString {
 impl From<&str> for RawString {
     #[inline]
     fn from(s: &str) -> Self {
-        if s.is_empty() {
-            Self(RawStringInner::Empty)
-        } else {
-            InternalString::from(s).into()
-        }
+        Self::common(s).unwrap_or_else(|| InternalString::from(s).into())
     }
 }
 
 impl From<String> for RawString {
     #[inline]
     fn from(s: String) -> Self {
-        if s.is_empty() {
-            Self(RawStringInner::Empty)
-        } else {
-            InternalString::from(s).into()
-        }
+        Self::common(&s).unwrap_or_else(|| InternalString::from(s).into())
     }
 }
 
 impl From<&String> for RawString {
     #[inline]
     fn from(s: &String) -> Self {
-        if s.is_empty() {
-            Self(RawStringInner::Empty)
-        } else {
-            InternalString::from(s).into()
-        }
+        Self::common(s).unwrap_or_else(|| InternalString::from(s).into())
     }
 }
 
 impl From<InternalString> for RawString {
     #[inline]
     fn from(inner: InternalString) -> Self {
-        Self(RawStringInner::Explicit(inner))
+        Self::common(&inner).unwrap_or(Self(RawStringInner::Explicit(inner)))
     }
 }
 
 impl From<&InternalString> for RawString {
     #[inline]
     fn from(s: &InternalString) -> Self {
-        if s.is_empty() {
-            Self(RawStringInner::Empty)
-        } else {
-            InternalString::from(s).into()
-        }
+        Self::common(s).unwrap_or_else(|| InternalString::from(s).into())
     }
 }
 
 impl From<Box<str>> for RawString {
     #[inline]
     fn from(s: Box<str>) -> Self {
-        if s.is_empty() {
-            Self(RawStringInner::Empty)
-        } else {
-            InternalString::from(s).into()
-        }
+        Self::common(&s).unwrap_or_else(|| InternalString::from(s).into())
     }
 }
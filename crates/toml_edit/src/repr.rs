@@ -1,3 +1,4 @@
+#[cfg(feature = "display")]
 use std::borrow::Cow;
 
 use crate::RawString;
@@ -24,6 +25,7 @@ where
         }
     }
 
+    #[cfg(feature = "parse")]
     pub(crate) fn set_repr_unchecked(&mut self, repr: Repr) {
         self.repr = Some(repr);
     }
@@ -38,6 +40,29 @@ where
         self.value
     }
 
+    /// Replace the wrapped value, keeping the surrounding whitespace and comments
+    ///
+    /// The representation is reset to the default, as it would no longer match the value.  To
+    /// keep a string's quoting, for example, reapply it with
+    /// [`Formatted::set_string_style`][Formatted::<String>::set_string_style].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "parse")] #[cfg(feature = "display")] {
+    /// let mut doc = "name = 'old' # the name\n".parse::<toml_edit::DocumentMut>().unwrap();
+    /// let name = doc["name"].as_value_mut().unwrap().as_string_mut().unwrap();
+    /// let style = name.string_style().unwrap();
+    /// name.set_value("new".to_owned());
+    /// name.set_string_style(style).unwrap();
+    /// assert_eq!(doc.to_string(), "name = 'new' # the name\n");
+    /// # }
+    /// ```
+    pub fn set_value(&mut self, value: T) {
+        self.value = value;
+        self.repr = None;
+    }
+
     /// Returns the raw representation, if available.
     pub fn as_repr(&self) -> Option<&Repr> {
         self.repr.as_ref()
@@ -60,6 +85,17 @@ where
             })
     }
 
+    /// Store the [default representation][Formatted::default_repr] if there is none
+    ///
+    /// The representation then stays the same until it is reset, like with
+    /// [`Formatted::set_value`] or [`Formatted::fmt`].
+    #[cfg(feature = "display")]
+    pub fn pin_repr(&mut self) {
+        if self.repr.is_none() {
+            self.repr = Some(self.default_repr());
+        }
+    }
+
     /// The location within the original document
     ///
     /// This generally requires an [`ImDocument`][crate::ImDocument].
@@ -90,6 +126,401 @@ where
     }
 }
 
+/// The base an integer is written in, see [`Formatted::set_radix`]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum Radix {
+    /// e.g. `255`
+    #[default]
+    Decimal,
+    /// e.g. `0xFF`
+    Hex,
+    /// e.g. `0o377`
+    Octal,
+    /// e.g. `0b11111111`
+    Binary,
+}
+
+#[cfg(feature = "parse")]
+impl Radix {
+    fn prefix(self) -> &'static str {
+        match self {
+            Radix::Decimal => "",
+            Radix::Hex => "0x",
+            Radix::Octal => "0o",
+            Radix::Binary => "0b",
+        }
+    }
+
+    fn digits(self, value: u64) -> String {
+        match self {
+            Radix::Decimal => value.to_string(),
+            Radix::Hex => format!("{value:X}"),
+            Radix::Octal => format!("{value:o}"),
+            Radix::Binary => format!("{value:b}"),
+        }
+    }
+}
+
+/// How a float is written, see [`Formatted::set_exponent_style`]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum ExponentStyle {
+    /// Without an exponent, e.g. `1500.0`
+    #[default]
+    Plain,
+    /// In scientific notation, e.g. `1.5e3`
+    Scientific,
+}
+
+/// How an infinite or NaN float is written, see [`Formatted::set_special_float`]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum SpecialFloat {
+    /// `inf`
+    Inf,
+    /// `+inf`
+    PosInf,
+    /// `-inf`
+    NegInf,
+    /// `nan`
+    Nan,
+    /// `+nan`
+    PosNan,
+    /// `-nan`, a NaN with its sign bit set
+    NegNan,
+}
+
+impl SpecialFloat {
+    /// The TOML token for the float
+    pub fn as_str(self) -> &'static str {
+        match self {
+            SpecialFloat::Inf => "inf",
+            SpecialFloat::PosInf => "+inf",
+            SpecialFloat::NegInf => "-inf",
+            SpecialFloat::Nan => "nan",
+            SpecialFloat::PosNan => "+nan",
+            SpecialFloat::NegNan => "-nan",
+        }
+    }
+
+    /// The value of the float
+    pub fn to_f64(self) -> f64 {
+        match self {
+            SpecialFloat::Inf | SpecialFloat::PosInf => f64::INFINITY,
+            SpecialFloat::NegInf => f64::NEG_INFINITY,
+            SpecialFloat::Nan | SpecialFloat::PosNan => f64::NAN,
+            SpecialFloat::NegNan => -f64::NAN,
+        }
+    }
+
+    fn from_str(raw: &str) -> Option<Self> {
+        let special = match raw {
+            "inf" => SpecialFloat::Inf,
+            "+inf" => SpecialFloat::PosInf,
+            "-inf" => SpecialFloat::NegInf,
+            "nan" => SpecialFloat::Nan,
+            "+nan" => SpecialFloat::PosNan,
+            "-nan" => SpecialFloat::NegNan,
+            _ => return None,
+        };
+        Some(special)
+    }
+}
+
+/// How a string is quoted, see [`Formatted::set_string_style`]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum StringStyle {
+    /// e.g. `"line\n"`
+    #[default]
+    Basic,
+    /// e.g. `'C:\path'`
+    Literal,
+    /// `"""` followed by a newline, with escapes
+    MultilineBasic,
+    /// `'''` followed by a newline, without escapes
+    MultilineLiteral,
+}
+
+impl Formatted<String> {
+    /// How the string is currently quoted, if it has a representation
+    pub fn string_style(&self) -> Option<StringStyle> {
+        let raw = self.as_repr()?.as_raw().as_str()?;
+        let style = if raw.starts_with("\"\"\"") {
+            StringStyle::MultilineBasic
+        } else if raw.starts_with("'''") {
+            StringStyle::MultilineLiteral
+        } else if raw.starts_with('"') {
+            StringStyle::Basic
+        } else if raw.starts_with('\'') {
+            StringStyle::Literal
+        } else {
+            return None;
+        };
+        Some(style)
+    }
+}
+
+#[cfg(feature = "parse")]
+#[cfg(feature = "display")]
+impl Formatted<String> {
+    /// Quote the string in `style`
+    ///
+    /// Multi-line strings start on the line after the opening delimiter.  Literal strings can't
+    /// contain control characters other than tabs (and, for multi-line ones, newlines), nor
+    /// their closing delimiter.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use toml_edit::{Formatted, StringStyle};
+    ///
+    /// let mut value = Formatted::new(String::from("SELECT *\nFROM \"users\";"));
+    /// value.set_string_style(StringStyle::MultilineLiteral).unwrap();
+    /// assert_eq!(
+    ///     value.as_repr().unwrap().as_raw().as_str(),
+    ///     Some("'''\nSELECT *\nFROM \"users\";'''")
+    /// );
+    ///
+    /// let mut value = Formatted::new(String::from("it's"));
+    /// assert!(value.set_string_style(StringStyle::Literal).is_err());
+    /// ```
+    pub fn set_string_style(&mut self, style: StringStyle) -> Result<(), crate::TomlError> {
+        use crate::encode::{to_string_repr, StringStyle as Delimiter};
+
+        let value = self.value.as_str();
+        let (delimiter, literal) = match style {
+            StringStyle::Basic => (Delimiter::OnelineSingle, false),
+            StringStyle::Literal => (Delimiter::OnelineSingle, true),
+            StringStyle::MultilineBasic => (Delimiter::NewlineTriple, false),
+            StringStyle::MultilineLiteral => (Delimiter::NewlineTriple, true),
+        };
+        if literal {
+            let multiline = delimiter == Delimiter::NewlineTriple;
+            let invalid = value.chars().find(|&c| {
+                (c.is_ascii_control() && c != '\t' && !(multiline && c == '\n'))
+                    || (!multiline && c == '\'')
+            });
+            if let Some(c) = invalid {
+                return Err(crate::TomlError::custom(
+                    format!("literal string can't contain {c:?}"),
+                    None,
+                ));
+            }
+            if multiline && value.contains("'''") {
+                return Err(crate::TomlError::custom(
+                    "multi-line literal string can't contain `'''`".to_owned(),
+                    None,
+                ));
+            }
+        }
+        let repr = to_string_repr(value, Some(delimiter), Some(literal));
+        let raw = repr.as_raw().as_str().unwrap_or_default().to_owned();
+
+        let repr = checked_repr(raw, |parsed| parsed.as_str() == Some(value))?;
+        self.set_repr_unchecked(repr);
+        Ok(())
+    }
+}
+
+#[cfg(feature = "parse")]
+impl Formatted<i64> {
+    /// Write the integer in `radix`, removing any digit grouping
+    ///
+    /// Only non-negative integers may be written in a radix other than [`Radix::Decimal`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use toml_edit::{Formatted, Radix};
+    ///
+    /// let mut value = Formatted::new(255);
+    /// value.set_radix(Radix::Hex).unwrap();
+    /// assert_eq!(value.as_repr().unwrap().as_raw().as_str(), Some("0xFF"));
+    ///
+    /// assert!(Formatted::new(-1).set_radix(Radix::Hex).is_err());
+    /// ```
+    pub fn set_radix(&mut self, radix: Radix) -> Result<(), crate::TomlError> {
+        self.set_integer_repr(radix, 0)
+    }
+
+    /// Separate every `group_size` digits with an underscore, keeping the current radix
+    ///
+    /// A `group_size` of `0` removes the separators.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use toml_edit::{Formatted, Radix};
+    ///
+    /// let mut value = Formatted::new(1_000_000);
+    /// value.set_digit_grouping(3).unwrap();
+    /// assert_eq!(value.as_repr().unwrap().as_raw().as_str(), Some("1_000_000"));
+    ///
+    /// value.set_radix(Radix::Binary).unwrap();
+    /// value.set_digit_grouping(8).unwrap();
+    /// assert_eq!(
+    ///     value.as_repr().unwrap().as_raw().as_str(),
+    ///     Some("0b1111_01000010_01000000")
+    /// );
+    /// ```
+    pub fn set_digit_grouping(&mut self, group_size: usize) -> Result<(), crate::TomlError> {
+        self.set_integer_repr(self.radix(), group_size)
+    }
+
+    fn radix(&self) -> Radix {
+        let raw = self
+            .as_repr()
+            .and_then(|r| r.as_raw().as_str())
+            .unwrap_or_default();
+        [Radix::Hex, Radix::Octal, Radix::Binary]
+            .into_iter()
+            .find(|radix| raw.starts_with(radix.prefix()))
+            .unwrap_or(Radix::Decimal)
+    }
+
+    fn set_integer_repr(
+        &mut self,
+        radix: Radix,
+        group_size: usize,
+    ) -> Result<(), crate::TomlError> {
+        let digits = radix.digits(self.value.unsigned_abs());
+        let mut raw = String::new();
+        if self.value < 0 {
+            raw.push('-');
+        }
+        raw.push_str(radix.prefix());
+        for (i, digit) in digits.chars().enumerate() {
+            if i != 0 && group_size != 0 && (digits.len() - i) % group_size == 0 {
+                raw.push('_');
+            }
+            raw.push(digit);
+        }
+
+        let value = self.value;
+        let repr = checked_repr(raw, |parsed| parsed.as_integer() == Some(value))?;
+        self.set_repr_unchecked(repr);
+        Ok(())
+    }
+}
+
+impl Formatted<f64> {
+    /// How the float is written if it is infinite or NaN
+    ///
+    /// This is the token in the source, like `+inf`, or for a float without a representation,
+    /// the one it defaults to.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "parse")] {
+    /// use toml_edit::{DocumentMut, SpecialFloat, Value};
+    ///
+    /// let doc = "a = +inf\nb = -nan\n".parse::<DocumentMut>().unwrap();
+    /// let Some(Value::Float(a)) = doc["a"].as_value() else { panic!() };
+    /// assert_eq!(a.special_float(), Some(SpecialFloat::PosInf));
+    /// let Some(Value::Float(b)) = doc["b"].as_value() else { panic!() };
+    /// assert_eq!(b.special_float(), Some(SpecialFloat::NegNan));
+    ///
+    /// assert_eq!(toml_edit::Formatted::new(1.0).special_float(), None);
+    /// # }
+    /// ```
+    pub fn special_float(&self) -> Option<SpecialFloat> {
+        let value = self.value;
+        if value.is_finite() {
+            return None;
+        }
+        if let Some(special) = self
+            .as_repr()
+            .and_then(|r| r.as_raw().as_str())
+            .and_then(SpecialFloat::from_str)
+        {
+            return Some(special);
+        }
+        let special = match (value.is_nan(), value.is_sign_negative()) {
+            (true, false) => SpecialFloat::Nan,
+            (true, true) => SpecialFloat::NegNan,
+            (false, false) => SpecialFloat::Inf,
+            (false, true) => SpecialFloat::NegInf,
+        };
+        Some(special)
+    }
+
+    /// Set the float to infinity or NaN, written as `special`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "display")] {
+    /// use toml_edit::{Formatted, SpecialFloat};
+    ///
+    /// let mut value = Formatted::new(1.0);
+    /// value.set_special_float(SpecialFloat::PosInf);
+    /// assert_eq!(*value.value(), f64::INFINITY);
+    /// assert_eq!(value.to_string(), "+inf");
+    ///
+    /// value.set_special_float(SpecialFloat::NegNan);
+    /// assert!(value.value().is_nan() && value.value().is_sign_negative());
+    /// assert_eq!(value.to_string(), "-nan");
+    /// # }
+    /// ```
+    pub fn set_special_float(&mut self, special: SpecialFloat) {
+        self.value = special.to_f64();
+        self.repr = Some(Repr::new_unchecked(special.as_str()));
+    }
+}
+
+#[cfg(feature = "parse")]
+impl Formatted<f64> {
+    /// Write the float with or without an exponent
+    ///
+    /// `nan` and `inf` are left as-is.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use toml_edit::{ExponentStyle, Formatted};
+    ///
+    /// let mut value = Formatted::new(1500.0);
+    /// value.set_exponent_style(ExponentStyle::Scientific).unwrap();
+    /// assert_eq!(value.as_repr().unwrap().as_raw().as_str(), Some("1.5e3"));
+    /// ```
+    pub fn set_exponent_style(&mut self, style: ExponentStyle) -> Result<(), crate::TomlError> {
+        let value = self.value;
+        if !value.is_finite() {
+            return Ok(());
+        }
+        let raw = match style {
+            ExponentStyle::Plain if value % 1.0 == 0.0 => format!("{value:.1}"),
+            ExponentStyle::Plain => format!("{value}"),
+            ExponentStyle::Scientific => format!("{value:e}"),
+        };
+
+        let repr = checked_repr(raw, |parsed| {
+            parsed
+                .as_float()
+                .map(|parsed| parsed.to_bits() == value.to_bits())
+                .unwrap_or(false)
+        })?;
+        self.set_repr_unchecked(repr);
+        Ok(())
+    }
+}
+
+/// Ensure `raw` parses back to the value it was generated from
+#[cfg(feature = "parse")]
+fn checked_repr(
+    raw: String,
+    round_trips: impl FnOnce(&crate::Value) -> bool,
+) -> Result<Repr, crate::TomlError> {
+    let parsed = crate::parser::parse_value(&raw)?;
+    if !round_trips(&parsed) {
+        return Err(crate::TomlError::custom(
+            format!("`{raw}` does not represent the original value"),
+            None,
+        ));
+    }
+    Ok(Repr::new_unchecked(raw))
+}
+
 impl<T> std::fmt::Debug for Formatted<T>
 where
     T: std::fmt::Debug,
@@ -113,7 +544,9 @@ where
     T: ValueRepr,
 {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        crate::encode::encode_formatted(self, f, None, ("", ""))
+        crate::encode::encode_lf(f, |buf| {
+            crate::encode::encode_formatted(self, buf, None, ("", ""))
+        })
     }
 }
 
@@ -195,6 +628,9 @@ impl Decor {
     }
 
     /// Go back to default decor
+    ///
+    /// This replaces spanned decor, rendering the whitespace usual for where the node is.  Use
+    /// `Decor::new("", "")` for no whitespace at all.
     pub fn clear(&mut self) {
         self.prefix = None;
         self.suffix = None;
@@ -248,6 +684,47 @@ impl Decor {
         self.suffix = Some(suffix.into());
     }
 
+    /// Whether the prefix and suffix hold their text rather than a [span][RawString::span]
+    ///
+    /// Decor from an [`ImDocument`][crate::ImDocument] refers to its source and only renders
+    /// correctly alongside it.  Decor from a [`DocumentMut`][crate::DocumentMut] or built by
+    /// hand is always despanned.
+    pub fn is_despanned(&self) -> bool {
+        [&self.prefix, &self.suffix]
+            .into_iter()
+            .flatten()
+            .all(|raw| raw.span().is_none())
+    }
+
+    /// A copy of the decor holding its text, looking up any spans in `input`
+    ///
+    /// `input` must be the document the decor was parsed from.  Use this before moving decor
+    /// from an [`ImDocument`][crate::ImDocument] elsewhere.
+    ///
+    /// # Panics
+    ///
+    /// If a span is out of bounds for `input`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "parse")] {
+    /// let raw = "a = 1 # one\n";
+    /// let doc = toml_edit::ImDocument::parse(raw).unwrap();
+    /// let decor = doc["a"].as_value().unwrap().decor();
+    /// assert!(!decor.is_despanned());
+    ///
+    /// let decor = decor.despanned(raw);
+    /// assert!(decor.is_despanned());
+    /// assert_eq!(decor.suffix().and_then(|s| s.as_str()), Some(" # one"));
+    /// # }
+    /// ```
+    pub fn despanned(&self, input: &str) -> Self {
+        let mut decor = self.clone();
+        decor.despan(input);
+        decor
+    }
+
     pub(crate) fn despan(&mut self, input: &str) {
         if let Some(prefix) = &mut self.prefix {
             prefix.despan(input);
@@ -256,6 +733,137 @@ impl Decor {
             suffix.despan(input);
         }
     }
+
+    pub(crate) fn clear_prefix(&mut self) {
+        self.prefix = None;
+    }
+
+    pub(crate) fn clear_suffix(&mut self) {
+        self.suffix = None;
+    }
+
+    pub(crate) fn replace_prefix(&mut self, prefix: Option<RawString>) -> Option<RawString> {
+        std::mem::replace(&mut self.prefix, prefix)
+    }
+
+    pub(crate) fn replace_suffix(&mut self, suffix: Option<RawString>) -> Option<RawString> {
+        std::mem::replace(&mut self.suffix, suffix)
+    }
+
+    /// The comment lines in the prefix, without the leading `#` and surrounding whitespace
+    pub(crate) fn prefix_comments(&self) -> impl Iterator<Item = &str> {
+        comment_lines(self.prefix().and_then(RawString::as_str).unwrap_or(""))
+    }
+
+    /// Replace the comment block in the prefix
+    ///
+    /// Whitespace before the first comment and after the last comment is kept as-is and new
+    /// comments are indented to match the line that follows the prefix.  A default prefix is
+    /// taken to be `default`.
+    pub(crate) fn set_prefix_comments<I, S>(&mut self, comments: I, default: &str)
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let prefix = match self.prefix() {
+            Some(prefix) => prefix.as_str().unwrap_or(""),
+            None => default,
+        };
+        self.set_prefix(replace_comment_lines(prefix, comments));
+    }
+}
+
+/// The comment lines in `raw`, without the leading `#` and surrounding whitespace
+pub(crate) fn comment_lines(raw: &str) -> impl Iterator<Item = &str> {
+    raw.lines()
+        .filter_map(|line| line.trim_start().strip_prefix('#'))
+        .map(str::trim)
+}
+
+/// Replace the comment block in `raw`, see [`Decor::set_prefix_comments`]
+pub(crate) fn replace_comment_lines<I, S>(raw: &str, comments: I) -> String
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    let lines = raw.split_inclusive('\n').collect::<Vec<_>>();
+    let is_comment = |line: &&str| line.trim_start().starts_with('#');
+    let (leading, trailing) = match (
+        lines.iter().position(is_comment),
+        lines.iter().rposition(is_comment),
+    ) {
+        (Some(first), Some(last)) => (lines[..first].concat(), lines[last + 1..].concat()),
+        _ => {
+            let split = raw.rfind('\n').map(|i| i + 1).unwrap_or(0);
+            (raw[..split].to_owned(), raw[split..].to_owned())
+        }
+    };
+    let indent = trailing.rsplit('\n').next().unwrap_or("");
+    let newline = if raw.contains("\r\n") { "\r\n" } else { "\n" };
+
+    let mut output = leading;
+    for comment in comments {
+        let comment = comment.as_ref();
+        let lines = if comment.is_empty() {
+            vec![""]
+        } else {
+            comment.lines().collect()
+        };
+        for line in lines {
+            let line = line.trim();
+            output.push_str(indent);
+            if line.is_empty() {
+                output.push('#');
+            } else {
+                output.push_str("# ");
+                output.push_str(line);
+            }
+            output.push_str(newline);
+        }
+    }
+    output.push_str(&trailing);
+    output
+}
+
+/// The comment ending the first line of `raw`, stripped of its `#` and surrounding whitespace
+pub(crate) fn line_comment(raw: &RawString) -> Option<&str> {
+    raw.as_str()?
+        .split('\n')
+        .next()?
+        .trim_start()
+        .strip_prefix('#')
+        .map(str::trim)
+}
+
+/// Replace the comment ending the first line of `raw`
+///
+/// When `raw` has no line break of its own and something follows it on the same line,
+/// `line_break` is added after a new comment.
+pub(crate) fn set_line_comment(
+    raw: &RawString,
+    comment: Option<&str>,
+    line_break: Option<&str>,
+) -> RawString {
+    let raw = raw.as_str().unwrap_or("");
+    let rest = match raw.find('\n') {
+        Some(i) => raw[i..].to_owned(),
+        None => match (comment, line_break) {
+            (Some(_), Some(line_break)) => line_break.to_owned(),
+            (None, _) if line_comment(&raw.into()).is_none() => raw.to_owned(),
+            _ => String::new(),
+        },
+    };
+    match comment {
+        Some(comment) => {
+            let comment = comment.lines().map(str::trim).collect::<Vec<_>>().join(" ");
+            if comment.is_empty() {
+                format!(" #{rest}").into()
+            } else {
+                format!(" # {comment}{rest}").into()
+            }
+        }
+        None => rest.into(),
+    }
 }
 
 impl std::fmt::Debug for Decor {
@@ -5,6 +5,7 @@
 mod array;
 mod key;
 mod map;
+#[cfg(feature = "display")]
 mod pretty;
 mod value;
 
@@ -12,6 +13,7 @@ pub(crate) use array::*;
 pub(crate) use key::*;
 pub(crate) use map::*;
 
+#[cfg(feature = "display")]
 use crate::visit_mut::VisitMut;
 
 /// Errors that can occur when deserializing a type.
@@ -133,7 +135,7 @@ pub fn to_string<T>(value: &T) -> Result<String, Error>
 where
     T: serde::ser::Serialize + ?Sized,
 {
-    to_document(value).map(|e| e.to_string())
+    to_inline_document(value).map(|e| e.to_string())
 }
 
 /// Serialize the given data structure as a "pretty" String of TOML.
@@ -145,7 +147,7 @@ pub fn to_string_pretty<T>(value: &T) -> Result<String, Error>
 where
     T: serde::ser::Serialize + ?Sized,
 {
-    let mut document = to_document(value)?;
+    let mut document = to_inline_document(value)?;
     pretty::Pretty.visit_document_mut(&mut document);
     Ok(document.to_string())
 }
@@ -153,7 +155,88 @@ where
 /// Serialize the given data structure into a TOML document.
 ///
 /// This would allow custom formatting to be applied, mixing with format preserving edits, etc.
+///
+/// Nested structs and maps become standard tables, and sequences of them arrays of tables,
+/// unless they are small: at most 3 entries, holding neither tables nor arrays of tables.  Those
+/// are kept as inline tables.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "parse")] #[cfg(feature = "display")] {
+/// use serde::Serialize;
+/// use std::collections::BTreeMap;
+///
+/// #[derive(Serialize)]
+/// struct Manifest {
+///     name: String,
+///     dependencies: BTreeMap<String, Dependency>,
+///     bin: Vec<Bin>,
+/// }
+///
+/// #[derive(Serialize)]
+/// struct Dependency {
+///     version: String,
+/// }
+///
+/// #[derive(Serialize)]
+/// struct Bin {
+///     name: String,
+///     path: String,
+///     test: bool,
+///     bench: bool,
+/// }
+///
+/// let manifest = Manifest {
+///     name: "demo".to_owned(),
+///     dependencies: BTreeMap::from([(
+///         "serde".to_owned(),
+///         Dependency {
+///             version: "1.0".to_owned(),
+///         },
+///     )]),
+///     bin: vec![Bin {
+///         name: "cli".to_owned(),
+///         path: "src/main.rs".to_owned(),
+///         test: false,
+///         bench: false,
+///     }],
+/// };
+///
+/// let mut doc = toml_edit::ser::to_document(&manifest).unwrap();
+/// doc["dependencies"]
+///     .as_table_mut()
+///     .unwrap()
+///     .decor_mut()
+///     .set_prefix("\n# pinned for MSRV\n");
+/// assert_eq!(
+///     doc.to_string(),
+///     r#"name = "demo"
+///
+/// ## pinned for MSRV
+/// [dependencies]
+/// serde = { version = "1.0" }
+///
+/// [[bin]]
+/// name = "cli"
+/// path = "src/main.rs"
+/// test = false
+/// bench = false
+/// "#
+/// );
+/// # }
+/// ```
 pub fn to_document<T>(value: &T) -> Result<crate::DocumentMut, Error>
+where
+    T: serde::ser::Serialize + ?Sized,
+{
+    let mut document = to_inline_document(value)?;
+    layout_tables(document.as_table_mut());
+    Ok(document)
+}
+
+/// Serialize into a document with every nested table inline
+fn to_inline_document<T>(value: &T) -> Result<crate::DocumentMut, Error>
 where
     T: serde::ser::Serialize + ?Sized,
 {
@@ -165,4 +248,46 @@ where
     Ok(root.into())
 }
 
+/// Turn all but small inline tables into standard tables, see [`to_document`]
+fn layout_tables(table: &mut crate::Table) {
+    for (_, item) in table.iter_mut() {
+        let standard = match &*item {
+            crate::Item::Value(crate::Value::InlineTable(table)) => !is_small(table),
+            crate::Item::Value(crate::Value::Array(array)) => {
+                array.iter().all(|value| value.is_inline_table())
+                    && array.iter().any(|value| {
+                        value
+                            .as_inline_table()
+                            .map(|t| !is_small(t))
+                            .unwrap_or(false)
+                    })
+            }
+            _ => false,
+        };
+        if !standard {
+            continue;
+        }
+        item.make_item();
+        match item {
+            crate::Item::Table(table) => layout_tables(table),
+            crate::Item::ArrayOfTables(array) => {
+                for table in array.iter_mut() {
+                    layout_tables(table);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn is_small(table: &crate::InlineTable) -> bool {
+    const MAX_INLINE_ENTRIES: usize = 3;
+    table.len() <= MAX_INLINE_ENTRIES
+        && table.iter().all(|(_, value)| match value {
+            crate::Value::InlineTable(_) => false,
+            crate::Value::Array(array) => !array.iter().any(|value| value.is_inline_table()),
+            _ => true,
+        })
+}
+
 pub use value::ValueSerializer;
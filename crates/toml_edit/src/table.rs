@@ -3,15 +3,18 @@ use std::iter::FromIterator;
 use indexmap::map::IndexMap;
 
 use crate::key::Key;
-use crate::repr::Decor;
+use crate::repr::{comment_lines, replace_comment_lines, Decor};
+#[cfg(feature = "display")]
 use crate::value::DEFAULT_VALUE_DECOR;
-use crate::{InlineTable, InternalString, Item, KeyMut, Value};
+use crate::{InlineTable, InternalString, Item, KeyMut, RawString, TypeError, Value};
 
 /// Type representing a TOML non-inline table
 #[derive(Clone, Debug, Default)]
 pub struct Table {
     // Comments/spaces before and after the header
     pub(crate) decor: Decor,
+    // Comments/spaces after the last key/value pair
+    trailing: RawString,
     // Whether to hide an empty table
     pub(crate) implicit: bool,
     // Whether this is a proxy for dotted keys
@@ -22,6 +25,9 @@ pub struct Table {
     doc_position: Option<usize>,
     pub(crate) span: Option<std::ops::Range<usize>>,
     pub(crate) items: KeyValuePairs,
+    // How key/value pairs are laid out as they are inserted, from the document's `Profile`
+    #[cfg(feature = "display")]
+    pub(crate) profile: Option<std::sync::Arc<crate::ToStringOptions>>,
 }
 
 /// Constructors
@@ -33,6 +39,11 @@ impl Table {
         Default::default()
     }
 
+    /// Creates an empty table with room for at least `capacity` key/value pairs
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self::with_pairs(KeyValuePairs::with_capacity(capacity))
+    }
+
     pub(crate) fn with_pos(doc_position: Option<usize>) -> Self {
         Self {
             doc_position,
@@ -40,6 +51,60 @@ impl Table {
         }
     }
 
+    /// Build a table from nested data, laid out the way it would be written by hand
+    ///
+    /// Inline tables become standard tables and arrays of inline tables become arrays of tables,
+    /// at any depth, while anything within an array of other values is left inline.  Nested maps
+    /// and vectors can be converted to [`Value`]s with `From`; for a table that stays inline,
+    /// collect the pairs into an [`InlineTable`] instead.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "parse")] #[cfg(feature = "display")] {
+    /// use std::collections::BTreeMap;
+    /// use toml_edit::{DocumentMut, Table, Value};
+    ///
+    /// let mut server = BTreeMap::new();
+    /// server.insert("host", Value::from("localhost"));
+    /// server.insert("ports", Value::from(vec![80, 443]));
+    /// let mut config = BTreeMap::new();
+    /// config.insert("name", Value::from("demo"));
+    /// config.insert("server", Value::from(server));
+    ///
+    /// let doc = DocumentMut::from(Table::from_map(config));
+    /// assert_eq!(
+    ///     doc.to_string(),
+    ///     "name = \"demo\"\n\n[server]\nhost = \"localhost\"\nports = [80, 443]\n"
+    /// );
+    /// # }
+    /// ```
+    pub fn from_map<I, K, V>(map: I) -> Self
+    where
+        I: IntoIterator<Item = (K, V)>,
+        K: Into<Key>,
+        V: Into<Item>,
+    {
+        let mut table: Table = map.into_iter().collect();
+        table.make_standard_tables();
+        table
+    }
+
+    fn make_standard_tables(&mut self) {
+        for (_, item) in self.items.iter_mut() {
+            item.make_item();
+            match item {
+                Item::Table(table) => table.make_standard_tables(),
+                Item::ArrayOfTables(array) => {
+                    for table in array.iter_mut() {
+                        table.make_standard_tables();
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
     pub(crate) fn with_pairs(items: KeyValuePairs) -> Self {
         Self {
             items,
@@ -48,12 +113,25 @@ impl Table {
     }
 
     /// Convert to an inline table
+    ///
+    /// Nested tables and arrays of tables are converted to inline tables and arrays of inline
+    /// tables.  The spacing around `=` and the dotted state are preserved, while indentation and
+    /// comments are reset to the defaults.
     pub fn into_inline_table(mut self) -> InlineTable {
-        for (_, value) in self.items.iter_mut() {
-            value.make_value();
+        use indexmap::map::MutableKeys;
+        for (key, value) in self.items.iter_mut2() {
+            if let Some(value) = value.as_value_mut() {
+                key.leaf_decor.clear_prefix();
+                value.decor_mut().clear_suffix();
+            } else {
+                key.leaf_decor.clear();
+                key.dotted_decor.clear();
+                value.make_value();
+            }
         }
         let mut t = InlineTable::with_pairs(self.items);
-        t.fmt();
+        t.set_implicit(self.implicit);
+        t.set_dotted(self.dotted);
         t
     }
 }
@@ -62,12 +140,12 @@ impl Table {
 impl Table {
     /// Get key/values for values that are visually children of this table
     ///
-    /// For example, this will return dotted keys
+    /// For example, this will return dotted keys, ordered by [`Key::position`]
     pub fn get_values(&self) -> Vec<(Vec<&Key>, &Value)> {
         let mut values = Vec::new();
         let root = Vec::new();
         self.append_values(&root, &mut values);
-        values
+        sort_by_position(values)
     }
 
     fn append_values<'s>(
@@ -98,6 +176,147 @@ impl Table {
         }
     }
 
+    /// Indent the key/value pairs `width` spaces deeper than the header, along with the
+    /// subtables
+    ///
+    /// Each subtable's header is aligned with the key/value pairs of its parent, and its own
+    /// key/value pairs are indented `width` spaces deeper still.  Comments keep their place,
+    /// only the indentation of the line holding a key or header changes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "parse")] {
+    /// # #[cfg(feature = "display")] {
+    /// let mut doc = "[[bin]]\nname = 'a'\n[bin.extra]\nx = 1\n"
+    ///     .parse::<toml_edit::DocumentMut>()
+    ///     .unwrap();
+    /// doc["bin"][0].as_table_mut().unwrap().set_indent(2);
+    /// assert_eq!(doc.to_string(), "[[bin]]\n  name = 'a'\n  [bin.extra]\n    x = 1\n");
+    /// # }
+    /// # }
+    /// ```
+    pub fn set_indent(&mut self, width: usize) {
+        let step = " ".repeat(width);
+        let entries = format!("{}{step}", self.header_indent());
+        self.apply_indent(&entries, &step, true);
+    }
+
+    /// Recompute the indentation of the subtree, like [`Table::set_indent`] with the indentation
+    /// already used by the table
+    ///
+    /// This is how the table's first indented key/value pair is indented relative to its header,
+    /// so after inserting entries into an indented table, they can be lined up with the existing
+    /// ones.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "parse")] {
+    /// # #[cfg(feature = "display")] {
+    /// let mut doc = "[[bin]]\n  name = 'a'\n".parse::<toml_edit::DocumentMut>().unwrap();
+    /// let bin = doc["bin"][0].as_table_mut().unwrap();
+    /// bin["path"] = toml_edit::value("main.rs");
+    /// bin["extra"] = toml_edit::table();
+    /// bin["extra"]["x"] = toml_edit::value(1);
+    /// bin.reindent();
+    /// assert_eq!(
+    ///     doc.to_string(),
+    ///     "[[bin]]\n  name = 'a'\n  path = \"main.rs\"\n\n  [bin.extra]\n    x = 1\n"
+    /// );
+    /// # }
+    /// # }
+    /// ```
+    pub fn reindent(&mut self) {
+        let header = self.header_indent().to_owned();
+        let entries = self
+            .items
+            .iter()
+            .filter(|(_, item)| is_entry(item))
+            .find_map(|(key, _)| key.leaf_decor().prefix().and_then(RawString::as_str))
+            .map(last_line)
+            .unwrap_or(&header)
+            .to_owned();
+        let step = entries
+            .strip_prefix(header.as_str())
+            .unwrap_or("")
+            .to_owned();
+        self.apply_indent(&entries, &step, true);
+    }
+
+    /// Separate the header from what precedes it by exactly `blank_lines` blank lines
+    ///
+    /// The blank lines at the start of the [prefix][Table::decor] are replaced, leaving any
+    /// comments above the header, and the blank lines between them, as-is.  Running this again
+    /// with the same count changes nothing.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "parse")] {
+    /// # #[cfg(feature = "display")] {
+    /// let mut doc = "a = 1\n\n\n# about b\n[b]\n".parse::<toml_edit::DocumentMut>().unwrap();
+    /// doc["b"].as_table_mut().unwrap().set_prefix_blank_lines(1);
+    /// assert_eq!(doc.to_string(), "a = 1\n\n# about b\n[b]\n");
+    /// # }
+    /// # }
+    /// ```
+    pub fn set_prefix_blank_lines(&mut self, blank_lines: usize) {
+        let prefix = self
+            .decor
+            .prefix()
+            .and_then(RawString::as_str)
+            .unwrap_or("\n");
+        let blank = prefix
+            .split_inclusive('\n')
+            .take_while(|line| line.ends_with('\n') && line.trim().is_empty())
+            .map(str::len)
+            .sum::<usize>();
+        let prefix = format!("{}{}", "\n".repeat(blank_lines), &prefix[blank..]);
+        self.decor.set_prefix(prefix);
+    }
+
+    /// The indentation of the line holding the header
+    fn header_indent(&self) -> &str {
+        self.decor
+            .prefix()
+            .and_then(RawString::as_str)
+            .map(last_line)
+            .unwrap_or("")
+    }
+
+    /// Indent key/value pairs by `entries`, and subtables by `step` more per level
+    fn apply_indent(&mut self, entries: &str, step: &str, with_entries: bool) {
+        use indexmap::map::MutableKeys;
+        for (key, item) in self.items.iter_mut2() {
+            if with_entries && is_entry(item) {
+                let prefix = replace_last_line(key.leaf_decor().prefix(), "", entries);
+                key.leaf_decor_mut().set_prefix(prefix);
+            }
+            match item {
+                Item::Table(table) if table.is_dotted() => {
+                    table.apply_indent(entries, step, false);
+                }
+                Item::Table(table) => table.apply_header_indent(entries, step),
+                Item::ArrayOfTables(array) => {
+                    for table in array.iter_mut() {
+                        table.apply_header_indent(entries, step);
+                    }
+                }
+                Item::None | Item::Value(_) => {}
+            }
+        }
+    }
+
+    fn apply_header_indent(&mut self, header: &str, step: &str) {
+        if self.decor.prefix().is_some() || !header.is_empty() {
+            // Starting from the default decor for headers
+            let prefix = replace_last_line(self.decor.prefix(), "\n", header);
+            self.decor.set_prefix(prefix);
+        }
+        self.apply_indent(&format!("{header}{step}"), step, true);
+    }
+
     /// Auto formats the table.
     pub fn fmt(&mut self) {
         decorate_table(self);
@@ -108,6 +327,7 @@ impl Table {
     /// Doesn't affect subtables or subarrays.
     pub fn sort_values(&mut self) {
         // Assuming standard tables have their doc_position set and this won't negatively impact them
+        self.clear_value_positions();
         self.items.sort_keys();
         for value in self.items.values_mut() {
             match value {
@@ -127,6 +347,7 @@ impl Table {
     where
         F: FnMut(&Key, &Item, &Key, &Item) -> std::cmp::Ordering,
     {
+        self.clear_value_positions();
         self.sort_values_by_internal(&mut compare);
     }
 
@@ -151,6 +372,222 @@ impl Table {
         }
     }
 
+    /// Sort Key/Value Pairs of the table and all nested tables using the comparison function
+    /// `compare`.
+    ///
+    /// Unlike [`Table::sort_values_by`], this also sorts the contents of standard tables, inline
+    /// tables, and the tables of arrays of tables.  The order of the tables within an array of
+    /// tables is left intact.
+    pub fn sort_values_recursive_by<F>(&mut self, mut compare: F)
+    where
+        F: FnMut(&Key, &Item, &Key, &Item) -> std::cmp::Ordering,
+    {
+        self.sort_values_recursive_by_internal(&mut compare);
+    }
+
+    fn sort_values_recursive_by_internal<F>(&mut self, compare: &mut F)
+    where
+        F: FnMut(&Key, &Item, &Key, &Item) -> std::cmp::Ordering,
+    {
+        self.clear_value_positions();
+        self.items
+            .sort_by(|key1, val1, key2, val2| compare(key1, val1, key2, val2));
+
+        for value in self.items.values_mut() {
+            match value {
+                Item::Table(table) => {
+                    table.sort_values_recursive_by_internal(compare);
+                }
+                Item::ArrayOfTables(array) => {
+                    for table in array.iter_mut() {
+                        table.sort_values_recursive_by_internal(compare);
+                    }
+                }
+                Item::Value(Value::InlineTable(table)) => {
+                    table.sort_items_recursive_by(compare);
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Sort the pairs of the table to follow `order`, placing keys not in it last
+    ///
+    /// Keys missing from `order` keep their relative order.  Standard tables and arrays of
+    /// tables are moved along with their nested tables, so their headers follow `order` too.
+    /// Nested tables are not sorted.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "parse")] {
+    /// # #[cfg(feature = "display")] {
+    /// let mut doc = "\
+    /// version = '1.0'
+    /// extra = true
+    /// name = 'demo'
+    ///
+    /// [dependencies]
+    /// [package]
+    /// "
+    /// .parse::<toml_edit::DocumentMut>()
+    /// .unwrap();
+    /// doc.sort_by_order(&["package", "name", "version", "dependencies"]);
+    /// assert_eq!(
+    ///     doc.to_string(),
+    ///     "\
+    /// name = 'demo'
+    /// version = '1.0'
+    /// extra = true
+    /// [package]
+    ///
+    /// [dependencies]
+    /// "
+    /// );
+    /// # }
+    /// # }
+    /// ```
+    pub fn sort_by_order(&mut self, order: &[&str]) {
+        self.clear_value_positions();
+        let rank = |key: &Key| {
+            order
+                .iter()
+                .position(|k| *k == key.get())
+                .unwrap_or(order.len())
+        };
+        self.items
+            .sort_by(|key1, _, key2, _| rank(key1).cmp(&rank(key2)));
+        self.reorder_table_positions();
+    }
+
+    /// Sort the pairs of the table and all nested tables to follow the order for their path
+    ///
+    /// `orders` pairs the path of a table, relative to this one, with the order of its keys, see
+    /// [`Table::sort_by_order`].  Tables within an array of tables share the array's path.
+    /// Tables without an order are left as-is.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "parse")] {
+    /// # #[cfg(feature = "display")] {
+    /// let mut doc = "\
+    /// [[bin]]
+    /// path = 'main.rs'
+    /// name = 'cli'
+    /// [package]
+    /// version = '1.0'
+    /// name = 'demo'
+    /// "
+    /// .parse::<toml_edit::DocumentMut>()
+    /// .unwrap();
+    /// doc.sort_by_order_recursive(&[
+    ///     (&[], &["package", "bin"]),
+    ///     (&["package"], &["name", "version"]),
+    ///     (&["bin"], &["name", "path"]),
+    /// ]);
+    /// assert_eq!(
+    ///     doc.to_string(),
+    ///     "\
+    /// [package]
+    /// name = 'demo'
+    /// version = '1.0'
+    /// [[bin]]
+    /// name = 'cli'
+    /// path = 'main.rs'
+    /// "
+    /// );
+    /// # }
+    /// # }
+    /// ```
+    pub fn sort_by_order_recursive(&mut self, orders: &[(&[&str], &[&str])]) {
+        let mut path = Vec::new();
+        self.sort_by_order_recursive_internal(&mut path, orders);
+    }
+
+    fn sort_by_order_recursive_internal(
+        &mut self,
+        path: &mut Vec<String>,
+        orders: &[(&[&str], &[&str])],
+    ) {
+        let order = orders
+            .iter()
+            .find(|(p, _)| p.iter().copied().eq(path.iter().map(String::as_str)))
+            .map(|(_, order)| *order);
+        if let Some(order) = order {
+            self.sort_by_order(order);
+        }
+
+        for (key, value) in self.items.iter_mut() {
+            path.push(key.get().to_owned());
+            match value {
+                Item::Table(table) => table.sort_by_order_recursive_internal(path, orders),
+                Item::ArrayOfTables(array) => {
+                    for table in array.iter_mut() {
+                        table.sort_by_order_recursive_internal(path, orders);
+                    }
+                }
+                _ => {}
+            }
+            path.pop();
+        }
+    }
+
+    /// Reassign the positions of nested tables so each child's headers follow the order of the
+    /// pairs
+    ///
+    /// The positions in use are kept, only shuffled between children, so tables elsewhere in
+    /// the document stay where they are.
+    fn reorder_table_positions(&mut self) {
+        fn collect<'t>(item: &'t mut Item, positions: &mut Vec<&'t mut usize>) {
+            let tables: Vec<&mut Table> = match item {
+                Item::Table(table) => vec![table],
+                Item::ArrayOfTables(array) => array.iter_mut().collect(),
+                _ => Vec::new(),
+            };
+            for table in tables {
+                if let Some(position) = table.doc_position.as_mut() {
+                    positions.push(position);
+                }
+                for item in table.items.values_mut() {
+                    collect(item, positions);
+                }
+            }
+        }
+
+        let mut blocks = Vec::new();
+        for item in self.items.values_mut() {
+            let mut positions = Vec::new();
+            collect(item, &mut positions);
+            positions.sort_by_key(|position| **position);
+            blocks.push(positions);
+        }
+        let mut available = blocks
+            .iter()
+            .flatten()
+            .map(|position| **position)
+            .collect::<Vec<_>>();
+        available.sort_unstable();
+        for (position, new) in blocks.into_iter().flatten().zip(available) {
+            *position = new;
+        }
+    }
+
+    /// Forget the order key/value pairs were parsed in, so they are rendered in map order
+    pub(crate) fn clear_value_positions(&mut self) {
+        use indexmap::map::MutableKeys;
+        for (key, value) in self.items.iter_mut2() {
+            key.position = None;
+            match value {
+                Item::Table(table) if table.is_dotted() => table.clear_value_positions(),
+                Item::Value(Value::InlineTable(table)) if table.is_dotted() => {
+                    table.clear_value_positions();
+                }
+                _ => {}
+            }
+        }
+    }
+
     /// If a table has no key/value pairs and implicit, it will not be displayed.
     ///
     /// # Examples
@@ -181,6 +618,35 @@ impl Table {
         self.implicit
     }
 
+    /// Change the implicit status of this table and all of its nested tables.
+    ///
+    /// Tables within arrays of tables are left as-is.  See [`Table::set_implicit`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "parse")] #[cfg(feature = "display")] {
+    /// use toml_edit::{table, value, DocumentMut};
+    ///
+    /// let mut doc = DocumentMut::new();
+    /// doc["a"] = table();
+    /// doc["a"]["b"] = table();
+    /// doc["a"]["b"]["c"] = value(1);
+    /// assert_eq!(doc.to_string(), "[a]\n\n[a.b]\nc = 1\n");
+    ///
+    /// doc["a"].as_table_mut().unwrap().set_implicit_recursive(true);
+    /// assert_eq!(doc.to_string(), "[a.b]\nc = 1\n");
+    /// # }
+    /// ```
+    pub fn set_implicit_recursive(&mut self, implicit: bool) {
+        self.implicit = implicit;
+        for value in self.items.values_mut() {
+            if let Item::Table(table) = value {
+                table.set_implicit_recursive(implicit);
+            }
+        }
+    }
+
     /// Change this table's dotted status
     pub fn set_dotted(&mut self, yes: bool) {
         self.dotted = yes;
@@ -192,6 +658,31 @@ impl Table {
     }
 
     /// Sets the position of the `Table` within the [`DocumentMut`][crate::DocumentMut].
+    ///
+    /// When rendering, tables are gathered depth-first in the order of their keys, each parent
+    /// before its children, and then stably sorted by position.  A table without a position
+    /// takes that of the table gathered before it, so it stays right after its parent or
+    /// preceding sibling.  Positions only order headers relative to each other: a table's
+    /// key/value pairs are always written right after its header.  Parsing numbers the tables in
+    /// the order their headers appear.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "parse")] {
+    /// # #[cfg(feature = "display")] {
+    /// let mut doc = "[a]\n[b]\n[c]\n".parse::<toml_edit::DocumentMut>().unwrap();
+    /// let a = doc["a"].as_table().unwrap().position().unwrap();
+    /// doc["c"].as_table_mut().unwrap().set_position(a);
+    /// assert_eq!(doc.to_string(), "[a]\n[c]\n[b]\n");
+    ///
+    /// // New tables follow the table before them, here `c`
+    /// doc["d"] = toml_edit::table();
+    /// doc["d"]["x"] = toml_edit::value(1);
+    /// assert_eq!(doc.to_string(), "[a]\n[c]\n\n[d]\nx = 1\n[b]\n");
+    /// # }
+    /// # }
+    /// ```
     pub fn set_position(&mut self, doc_position: usize) {
         self.doc_position = Some(doc_position);
     }
@@ -215,12 +706,148 @@ impl Table {
         &self.decor
     }
 
+    /// Set whitespace and comments after the last key/value pair, before whatever follows
+    ///
+    /// This is written out even without any key/value pairs, unless the table is hidden for being
+    /// [implicit][Table::set_implicit] or [dotted][Table::set_dotted].  The root table's trailing
+    /// comes before the first header, while the
+    /// [document's][crate::DocumentMut::set_trailing] comes at the very end.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "parse")] {
+    /// # #[cfg(feature = "display")] {
+    /// let mut doc = "[a]\nx = 1\n[b]\ny = 2\n".parse::<toml_edit::DocumentMut>().unwrap();
+    /// doc["a"].as_table_mut().unwrap().set_trailing("\n\n");
+    /// assert_eq!(doc.to_string(), "[a]\nx = 1\n\n\n[b]\ny = 2\n");
+    /// # }
+    /// # }
+    /// ```
+    pub fn set_trailing(&mut self, trailing: impl Into<RawString>) {
+        self.trailing = trailing.into();
+    }
+
+    /// Whitespace and comments after the last key/value pair
+    ///
+    /// When parsing, this holds the comment lines directly after the last key/value pair when a
+    /// blank line separates them from what follows.  Other blank lines and comments before a
+    /// header belong to that header's [decor][Table::decor].
+    pub fn trailing(&self) -> &RawString {
+        &self.trailing
+    }
+
+    /// Returns the comment lines after the last key/value pair, see [`Table::trailing`]
+    ///
+    /// Each line is stripped of its leading `#` and surrounding whitespace.
+    ///
+    /// This generally requires a [`DocumentMut`][crate::DocumentMut].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "parse")] {
+    /// let doc = "[a]\nx = 1\n# end of a\n\n# about b\n[b]\n".parse::<toml_edit::DocumentMut>().unwrap();
+    /// let comments = doc["a"].as_table().unwrap().trailing_comments().collect::<Vec<_>>();
+    /// assert_eq!(comments, ["end of a"]);
+    /// assert_eq!(doc["b"].as_table().unwrap().comments().collect::<Vec<_>>(), ["about b"]);
+    /// # }
+    /// ```
+    pub fn trailing_comments(&self) -> impl Iterator<Item = &str> {
+        comment_lines(self.trailing.as_str().unwrap_or(""))
+    }
+
+    /// Replaces the comment lines after the last key/value pair
+    ///
+    /// Blank lines before and after the existing comment block are preserved.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "parse")] {
+    /// # #[cfg(feature = "display")] {
+    /// let mut doc = "[a]\nx = 1\n\n[b]\n".parse::<toml_edit::DocumentMut>().unwrap();
+    /// doc["a"].as_table_mut().unwrap().set_trailing_comments(["end of a"]);
+    /// assert_eq!(doc.to_string(), "[a]\nx = 1\n# end of a\n\n[b]\n");
+    /// # }
+    /// # }
+    /// ```
+    pub fn set_trailing_comments<I, S>(&mut self, comments: I)
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let trailing = self.trailing.as_str().unwrap_or("");
+        self.trailing = RawString::from(replace_comment_lines(trailing, comments));
+    }
+
+    /// Returns the comment lines preceding the table header
+    ///
+    /// Each line is stripped of its leading `#` and surrounding whitespace.
+    ///
+    /// This generally requires a [`DocumentMut`][crate::DocumentMut].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "parse")] {
+    /// let doc = "# Hello\n  #World\n[a]\n".parse::<toml_edit::DocumentMut>().unwrap();
+    /// let comments = doc["a"].as_table().unwrap().comments().collect::<Vec<_>>();
+    /// assert_eq!(comments, ["Hello", "World"]);
+    /// # }
+    /// ```
+    pub fn comments(&self) -> impl Iterator<Item = &str> {
+        self.decor.prefix_comments()
+    }
+
+    /// Replaces the comment lines preceding the table header
+    ///
+    /// Blank lines before and after the existing comment block are preserved, as is the blank line
+    /// a table with default decor is rendered with.  The new comments are indented like the
+    /// header and end in `\r\n` when the existing lines do.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "parse")] {
+    /// # #[cfg(feature = "display")] {
+    /// let mut doc = "a = 1\n\n# Old\n[b]\n".parse::<toml_edit::DocumentMut>().unwrap();
+    /// doc["b"].as_table_mut().unwrap().set_comments(["New", "Lines"]);
+    /// assert_eq!(doc.to_string(), "a = 1\n\n# New\n# Lines\n[b]\n");
+    /// # }
+    /// # }
+    /// ```
+    pub fn set_comments<I, S>(&mut self, comments: I)
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        // Keep the blank line a table is rendered with by default
+        self.decor.set_prefix_comments(comments, "\n");
+    }
+
     /// Returns an accessor to a key's formatting
     pub fn key(&self, key: &str) -> Option<&'_ Key> {
         self.items.get_full(key).map(|(_, key, _)| key)
     }
 
     /// Returns an accessor to a key's formatting
+    ///
+    /// The key's decor covers the whitespace and comments around the key itself; a comment at the
+    /// end of the line belongs to the value, see [`Table::get_key_value_mut`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "parse")] #[cfg(feature = "display")] {
+    /// let mut doc = "name = 'demo'\n".parse::<toml_edit::DocumentMut>().unwrap();
+    /// doc.key_mut("name").unwrap().leaf_decor_mut().set_prefix("    ");
+    ///
+    /// let (_, item) = doc.get_key_value_mut("name").unwrap();
+    /// item.as_value_mut().unwrap().decor_mut().set_suffix(" # package name");
+    /// assert_eq!(doc.to_string(), "    name = 'demo' # package name\n");
+    /// # }
+    /// ```
     pub fn key_mut(&mut self, key: &str) -> Option<KeyMut<'_>> {
         use indexmap::map::MutableKeys;
         self.items
@@ -256,6 +883,7 @@ impl Table {
         use indexmap::map::MutableKeys;
         self.span = None;
         self.decor.despan(input);
+        self.trailing.despan(input);
         for (key, value) in self.items.iter_mut2() {
             key.despan(input);
             value.despan(input);
@@ -300,22 +928,31 @@ impl Table {
         self.items.clear();
     }
 
+    /// Reserves room for at least `additional` more key/value pairs
+    pub fn reserve(&mut self, additional: usize) {
+        self.items.reserve(additional);
+    }
+
     /// Gets the given key's corresponding entry in the Table for in-place manipulation.
     pub fn entry<'a>(&'a mut self, key: &str) -> Entry<'a> {
         // Accept a `&str` rather than an owned type to keep `InternalString`, well, internal
-        match self.items.entry(key.into()) {
-            indexmap::map::Entry::Occupied(entry) => Entry::Occupied(OccupiedEntry { entry }),
-            indexmap::map::Entry::Vacant(entry) => Entry::Vacant(VacantEntry { entry }),
-        }
+        let entry = self.items.entry(key.into());
+        Entry::new(
+            entry,
+            #[cfg(feature = "display")]
+            self.profile.as_ref(),
+        )
     }
 
     /// Gets the given key's corresponding entry in the Table for in-place manipulation.
     pub fn entry_format<'a>(&'a mut self, key: &Key) -> Entry<'a> {
         // Accept a `&Key` to be consistent with `entry`
-        match self.items.entry(key.clone()) {
-            indexmap::map::Entry::Occupied(entry) => Entry::Occupied(OccupiedEntry { entry }),
-            indexmap::map::Entry::Vacant(entry) => Entry::Vacant(VacantEntry { entry }),
-        }
+        let entry = self.items.entry(key.clone());
+        Entry::new(
+            entry,
+            #[cfg(feature = "display")]
+            self.profile.as_ref(),
+        )
     }
 
     /// Returns an optional reference to an item given the key.
@@ -332,6 +969,65 @@ impl Table {
             .and_then(|value| if !value.is_none() { Some(value) } else { None })
     }
 
+    /// Returns an optional reference to an item given the key, ignoring ASCII case.
+    ///
+    /// An exact match is preferred, otherwise the first key in the table's order that matches is
+    /// used.  See [`Table::keys_ignore_case`] to detect keys that only differ by case.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "parse")] {
+    /// let doc = "Name = 'a'\nNAME = 'b'\nname = 'c'\n".parse::<toml_edit::DocumentMut>().unwrap();
+    /// assert_eq!(doc.get_ignore_case("name").and_then(|i| i.as_str()), Some("c"));
+    /// assert_eq!(doc.get_ignore_case("nAmE").and_then(|i| i.as_str()), Some("a"));
+    /// assert!(doc.get_ignore_case("version").is_none());
+    /// # }
+    /// ```
+    pub fn get_ignore_case<'a>(&'a self, key: &str) -> Option<&'a Item> {
+        let index = self.index_ignore_case(key)?;
+        self.items.get_index(index).map(|(_, value)| value)
+    }
+
+    /// Returns an optional mutable reference to an item given the key, ignoring ASCII case.
+    ///
+    /// See [`Table::get_ignore_case`].
+    pub fn get_mut_ignore_case<'a>(&'a mut self, key: &str) -> Option<&'a mut Item> {
+        let index = self.index_ignore_case(key)?;
+        self.items.get_index_mut(index).map(|(_, value)| value)
+    }
+
+    /// Returns the keys matching `key` when ignoring ASCII case, in the table's order.
+    ///
+    /// More than one key means the lookup is ambiguous.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "parse")] {
+    /// let doc = "Name = 'a'\nversion = 1\nname = 'b'\n".parse::<toml_edit::DocumentMut>().unwrap();
+    /// let keys = doc.keys_ignore_case("NAME");
+    /// assert_eq!(keys.iter().map(|k| k.get()).collect::<Vec<_>>(), ["Name", "name"]);
+    /// # }
+    /// ```
+    pub fn keys_ignore_case<'a>(&'a self, key: &str) -> Vec<&'a Key> {
+        self.items
+            .iter()
+            .filter(|(k, value)| !value.is_none() && k.get().eq_ignore_ascii_case(key))
+            .map(|(k, _)| k)
+            .collect()
+    }
+
+    fn index_ignore_case(&self, key: &str) -> Option<usize> {
+        match self.items.get_full(key) {
+            Some((index, _, value)) if !value.is_none() => Some(index),
+            _ => self
+                .items
+                .iter()
+                .position(|(k, value)| !value.is_none() && k.get().eq_ignore_ascii_case(key)),
+        }
+    }
+
     /// Return references to the key-value pair stored for key, if it is present, else None.
     pub fn get_key_value<'a>(&'a self, key: &str) -> Option<(&'a Key, &'a Item)> {
         self.items.get_full(key).and_then(|(_, key, value)| {
@@ -355,6 +1051,146 @@ impl Table {
         })
     }
 
+    /// Returns the item for `key` converted to `T`, or an error naming the key and the type found
+    ///
+    /// `T` may be any type converting from `&Item` with a [`TypeError`], like `i64`, `f64`,
+    /// `bool`, `&str`, [`&Datetime`][crate::Datetime] and [`&Array`][crate::Array].  A missing
+    /// key is reported as being of the type [`ItemKind::None`][crate::ItemKind::None].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "parse")] {
+    /// let doc = "name = 'demo'\nport = '8080'\n".parse::<toml_edit::DocumentMut>().unwrap();
+    /// assert_eq!(doc.get_as::<&str>("name"), Ok("demo"));
+    ///
+    /// let err = doc.get_as::<i64>("port").unwrap_err();
+    /// assert_eq!(err.to_string(), "expected integer for `port`, found string");
+    /// let err = doc.get_as::<bool>("debug").unwrap_err();
+    /// assert_eq!(err.to_string(), "expected boolean for `debug`, found none");
+    /// # }
+    /// ```
+    pub fn get_as<'a, T>(&'a self, key: &str) -> Result<T, TypeError>
+    where
+        T: TryFrom<&'a Item, Error = TypeError>,
+    {
+        let item = self.get(key).unwrap_or(&Item::None);
+        T::try_from(item).map_err(|err| err.with_key(key))
+    }
+
+    /// Returns an optional reference to an item given a path of keys.
+    ///
+    /// Each key but the last must name a table or an inline table.  Returns `None` if the path is
+    /// empty, a key is missing, or an intermediate item is not a table.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "parse")] {
+    /// let doc = "[a]\nb = { c = 1 }\n".parse::<toml_edit::DocumentMut>().unwrap();
+    /// assert_eq!(doc.get_path(&["a", "b", "c"]).and_then(|i| i.as_integer()), Some(1));
+    /// assert!(doc.get_path(&["a", "b", "c", "d"]).is_none());
+    /// assert!(doc.get_path(&["a", "z"]).is_none());
+    /// # }
+    /// ```
+    pub fn get_path<'a>(&'a self, path: &[&str]) -> Option<&'a Item> {
+        let (last, parents) = path.split_last()?;
+        let mut table: &dyn TableLike = self;
+        for key in parents {
+            table = table.get(key)?.as_table_like()?;
+        }
+        table.get(last)
+    }
+
+    /// Returns an optional mutable reference to an item given a path of keys.
+    ///
+    /// See [`Table::get_path`].
+    pub fn get_path_mut<'a>(&'a mut self, path: &[&str]) -> Option<&'a mut Item> {
+        let (last, parents) = path.split_last()?;
+        let mut table: &mut dyn TableLike = self;
+        for key in parents {
+            table = table.get_mut(key)?.as_table_like_mut()?;
+        }
+        table.get_mut(last)
+    }
+
+    /// Returns the table at the given path of keys, creating any missing tables along the way
+    ///
+    /// Created tables are [implicit][Table::set_implicit], except for the last one, so only the
+    /// headers of tables holding key/value pairs are rendered.  Returns `None` if an item along
+    /// the path is not a standard table, like a value or an array of tables.  An empty path
+    /// returns `self`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "parse")] {
+    /// # #[cfg(feature = "display")] {
+    /// let mut doc = "a = 1\n".parse::<toml_edit::DocumentMut>().unwrap();
+    /// let table = doc.get_or_insert_table(&["x", "y", "z"]).unwrap();
+    /// table["key"] = toml_edit::value("value");
+    /// assert_eq!(doc.to_string(), "a = 1\n\n[x.y.z]\nkey = \"value\"\n");
+    /// assert!(doc.get_or_insert_table(&["a", "b"]).is_none());
+    /// # }
+    /// # }
+    /// ```
+    pub fn get_or_insert_table<'a>(&'a mut self, path: &[&str]) -> Option<&'a mut Table> {
+        self.get_or_insert_tables(path, false)
+    }
+
+    /// Walk `path`, inserting implicit tables where missing, except for a last one that isn't
+    /// `implicit`
+    fn get_or_insert_tables<'a>(
+        &'a mut self,
+        path: &[&str],
+        implicit: bool,
+    ) -> Option<&'a mut Table> {
+        let mut table = self;
+        for (i, key) in path.iter().enumerate() {
+            let item = table.entry(key).or_insert(Item::None);
+            if item.is_none() {
+                let mut child = Table::new();
+                child.set_implicit(implicit || i + 1 < path.len());
+                *item = Item::Table(child);
+            }
+            table = item.as_table_mut()?;
+        }
+        Some(table)
+    }
+
+    /// Gets the entry at the given path of keys for in-place manipulation, creating any missing
+    /// tables leading to it
+    ///
+    /// Created tables are [implicit][Table::set_implicit].  Returns `None` if the path is empty or
+    /// an item before the last key is not a standard table, see [`Table::get_or_insert_table`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "parse")] {
+    /// # #[cfg(feature = "display")] {
+    /// let mut doc = "a = 1\n".parse::<toml_edit::DocumentMut>().unwrap();
+    /// doc.entry_path(&["x", "y", "z"])
+    ///     .unwrap()
+    ///     .or_insert(toml_edit::value(true));
+    /// assert_eq!(doc.to_string(), "a = 1\n\n[x.y]\nz = true\n");
+    /// assert!(doc.entry_path(&["a", "b"]).is_none());
+    /// # }
+    /// # }
+    /// ```
+    pub fn entry_path<'a>(&'a mut self, path: &[&str]) -> Option<Entry<'a>> {
+        let (last, parents) = path.split_last()?;
+        let table = self.get_or_insert_tables(parents, true)?;
+        Some(table.entry(last))
+    }
+
+    /// Returns true if the table contains an item at the given path of keys.
+    ///
+    /// See [`Table::get_path`].
+    pub fn contains_path(&self, path: &[&str]) -> bool {
+        self.get_path(path).is_some()
+    }
+
     /// Returns true if the table contains an item with the given key.
     pub fn contains_key(&self, key: &str) -> bool {
         if let Some(value) = self.items.get(key) {
@@ -365,6 +1201,28 @@ impl Table {
     }
 
     /// Returns true if the table contains a table with the given key.
+    ///
+    /// Inline tables are values; to accept either kind of table, check
+    /// [`Item::is_table_like`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "parse")] {
+    /// let doc = r#"
+    /// package = { name = "demo" }
+    ///
+    /// [dependencies]
+    ///
+    /// [[bin]]
+    /// "#.parse::<toml_edit::DocumentMut>().unwrap();
+    /// assert!(doc.contains_table("dependencies"));
+    /// assert!(doc.contains_array_of_tables("bin"));
+    /// assert!(doc.contains_value("package"));
+    /// assert!(!doc.contains_table("package"));
+    /// assert!(doc.get("package").map(|i| i.is_table_like()).unwrap_or(false));
+    /// # }
+    /// ```
     pub fn contains_table(&self, key: &str) -> bool {
         if let Some(value) = self.items.get(key) {
             value.is_table()
@@ -392,16 +1250,15 @@ impl Table {
     }
 
     /// Inserts a key-value pair into the map.
+    ///
+    /// In a document with a [`Profile`][crate::Profile], the pair is laid out by the profile.
     pub fn insert(&mut self, key: &str, item: Item) -> Option<Item> {
-        use indexmap::map::MutableEntryKey;
-        let key = Key::new(key);
-        match self.items.entry(key.clone()) {
-            indexmap::map::Entry::Occupied(mut entry) => {
+        match self.entry(key) {
+            Entry::Occupied(mut entry) => {
                 entry.key_mut().fmt();
-                let old = std::mem::replace(entry.get_mut(), item);
-                Some(old)
+                Some(entry.insert(item))
             }
-            indexmap::map::Entry::Vacant(entry) => {
+            Entry::Vacant(entry) => {
                 entry.insert(item);
                 None
             }
@@ -409,8 +1266,17 @@ impl Table {
     }
 
     /// Inserts a key-value pair into the map.
+    ///
+    /// The pair is kept as formatted, even in a document with a [`Profile`][crate::Profile],
+    /// though the profile lays out what is later inserted into the tables within `item`.
     pub fn insert_formatted(&mut self, key: &Key, item: Item) -> Option<Item> {
         use indexmap::map::MutableEntryKey;
+        #[cfg(feature = "display")]
+        let item = {
+            let mut item = item;
+            crate::format::adopt_profile(&mut item, self.profile.as_ref());
+            item
+        };
         match self.items.entry(key.clone()) {
             indexmap::map::Entry::Occupied(mut entry) => {
                 *entry.key_mut() = key.clone();
@@ -424,11 +1290,132 @@ impl Table {
         }
     }
 
+    /// Changes the key of an item, keeping its position and formatting.
+    ///
+    /// The decor of the old key is carried over to the new one.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "parse")] {
+    /// # #[cfg(feature = "display")] {
+    /// let mut doc = "# the name\nname = 'demo' # old\nversion = 1\n".parse::<toml_edit::DocumentMut>().unwrap();
+    /// doc.rename_key("name", "package-name").unwrap();
+    /// assert_eq!(doc.to_string(), "# the name\npackage-name = 'demo' # old\nversion = 1\n");
+    /// assert!(doc.rename_key("package-name", "version").is_err());
+    /// # }
+    /// # }
+    /// ```
+    pub fn rename_key(&mut self, from: &str, to: impl Into<Key>) -> Result<(), RenameError> {
+        rename_key(&mut self.items, from, to.into())
+    }
+
     /// Removes an item given the key.
+    ///
+    /// The key's formatting, including the comments on the lines above it, is removed along with
+    /// it; see [`Table::remove_preserving_comment`] to keep them.
     pub fn remove(&mut self, key: &str) -> Option<Item> {
         self.items.shift_remove(key)
     }
 
+    /// Removes an item given the key, moving the comments above it to the next key/value pair
+    ///
+    /// The blank lines and comments preceding a key/value pair are attached to its key, so
+    /// [`Table::remove`] deletes them too.  Instead, they are prepended to those of the key/value
+    /// pair rendered next in the table, as ordered by [`Key::position`], or, after the last one,
+    /// to the table's [trailing][Table::trailing].  A dotted key like `a` in `a.b = 1` removes
+    /// each of its pairs this way.  For tables, whose comments sit above their header, this is
+    /// the same as [`Table::remove`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "parse")] {
+    /// # #[cfg(feature = "display")] {
+    /// let mut doc = "# package metadata\nlicense-file = 'LICENSE'\nlicense = 'MIT'\n"
+    ///     .parse::<toml_edit::DocumentMut>()
+    ///     .unwrap();
+    /// doc.remove_preserving_comment("license-file");
+    /// assert_eq!(doc.to_string(), "# package metadata\nlicense = 'MIT'\n");
+    /// # }
+    /// # }
+    /// ```
+    pub fn remove_preserving_comment(&mut self, key: &str) -> Option<Item> {
+        use indexmap::map::MutableKeys;
+        let index = self.items.get_index_of(key)?;
+        if !is_entry(&self.items[index]) {
+            return self.items.shift_remove_index(index).map(|(_, item)| item);
+        }
+
+        // The pairs in map order, with the index of the kept ones among those that remain or the
+        // comments of the removed ones
+        let mut pairs = Vec::new();
+        let mut kept = 0;
+        for (i, (key, item)) in self.items.iter().enumerate() {
+            let mut keys = Vec::new();
+            leaf_keys(key, item, &mut keys);
+            for key in keys {
+                if i == index {
+                    let prefix = key.leaf_decor().prefix().and_then(RawString::as_str);
+                    let prefix = prefix.unwrap_or("");
+                    // Leaving out the indentation of the removed key
+                    let comments = &prefix[..prefix.len() - last_line(prefix).len()];
+                    pairs.push((key.position(), None, comments));
+                } else {
+                    pairs.push((key.position(), Some(kept), ""));
+                    kept += 1;
+                }
+            }
+        }
+        let mut max_position = 0;
+        let mut pairs = pairs
+            .into_iter()
+            .map(|(position, kept, comments)| {
+                let position = match position {
+                    Some(position) => {
+                        max_position = max_position.max(position);
+                        position
+                    }
+                    None => max_position,
+                };
+                (position, kept, comments)
+            })
+            .collect::<Vec<_>>();
+        pairs.sort_by_key(|(position, _, _)| *position);
+        let mut comments = String::new();
+        let mut moves = Vec::new();
+        for (_, kept, removed) in pairs {
+            match kept {
+                None => comments.push_str(removed),
+                Some(next) if !comments.is_empty() => {
+                    moves.push((next, std::mem::take(&mut comments)));
+                }
+                Some(_) => {}
+            }
+        }
+
+        let (_, item) = self.items.shift_remove_index(index)?;
+        let mut keys = Vec::new();
+        for (key, item) in self.items.iter_mut2() {
+            leaf_keys_mut(key, item, &mut keys);
+        }
+        for (next, comments) in moves {
+            let next = &mut keys[next];
+            let prefix = next
+                .leaf_decor()
+                .prefix()
+                .and_then(RawString::as_str)
+                .unwrap_or("");
+            let prefix = format!("{comments}{prefix}");
+            next.leaf_decor_mut().set_prefix(prefix);
+        }
+        if !comments.is_empty() {
+            let trailing = self.trailing.as_str().unwrap_or("");
+            self.trailing = format!("{comments}{trailing}").into();
+        }
+        Some(item)
+    }
+
     /// Removes a key from the map, returning the stored key and value if the key was previously in the map.
     pub fn remove_entry(&mut self, key: &str) -> Option<(Key, Item)> {
         self.items.shift_remove_entry(key)
@@ -446,29 +1433,121 @@ impl Table {
     {
         self.items.retain(|key, value| keep(key, value));
     }
+
+    /// Removes all key/value pairs, returning them in order
+    ///
+    /// The keys keep their formatting, so the pairs can be moved to another table with
+    /// [`Table::insert_formatted`].  The table's own decor is left as-is.
+    pub fn drain(&mut self) -> Drain<'_> {
+        Box::new(self.items.drain(..))
+    }
+
+    /// Removes the pairs for which `remove(&key, &mut item)` returns `true`, returning them in
+    /// order
+    ///
+    /// Unlike [`Table::drain`], the pairs are removed up front, whether or not the returned
+    /// iterator is consumed.  The remaining pairs and their formatting are left untouched, as is
+    /// the whole table should `remove` panic.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "parse")] {
+    /// # #[cfg(feature = "display")] {
+    /// let mut doc = "name = 'demo'\nunknown = 1 # typo?\nversion = 2\n"
+    ///     .parse::<toml_edit::DocumentMut>()
+    ///     .unwrap();
+    /// let known = ["name", "version"];
+    /// let mut extra = toml_edit::Table::new();
+    /// for (key, item) in doc.drain_filter(|key, _| !known.contains(&key)) {
+    ///     extra.insert_formatted(&key, item);
+    /// }
+    /// assert_eq!(doc.to_string(), "name = 'demo'\nversion = 2\n");
+    /// assert_eq!(extra.to_string(), "unknown = 1 # typo?\n");
+    /// # }
+    /// # }
+    /// ```
+    pub fn drain_filter<F>(&mut self, mut remove: F) -> Drain<'_>
+    where
+        F: FnMut(&str, &mut Item) -> bool,
+    {
+        // Deciding first leaves the table intact should `remove` panic
+        let flags = self
+            .items
+            .iter_mut()
+            .map(|(key, item)| remove(key, item))
+            .collect::<Vec<_>>();
+        let mut removed = Vec::new();
+        for ((key, item), flag) in std::mem::take(&mut self.items).into_iter().zip(flags) {
+            if flag {
+                removed.push((key, item));
+            } else {
+                self.items.insert(key, item);
+            }
+        }
+        Box::new(removed.into_iter())
+    }
+
+    /// Consumes the table, returning its key/value pairs in order
+    ///
+    /// Unlike iterating over an owned `Table`, which yields only the key's string, this keeps
+    /// the keys' formatting, like [`Table::drain`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "parse")] {
+    /// # #[cfg(feature = "display")] {
+    /// let doc = "name = 'demo' # the name\n'version' = 2\n"
+    ///     .parse::<toml_edit::DocumentMut>()
+    ///     .unwrap();
+    /// let mut other = toml_edit::Table::new();
+    /// for (key, item) in doc.as_table().clone().into_pairs() {
+    ///     other.insert_formatted(&key, item);
+    /// }
+    /// assert_eq!(other.to_string(), "name = 'demo' # the name\n'version' = 2\n");
+    /// # }
+    /// # }
+    /// ```
+    pub fn into_pairs(self) -> IntoPairs {
+        Box::new(self.items.into_iter())
+    }
+}
+
+#[cfg(feature = "parse")]
+impl std::str::FromStr for Table {
+    type Err = crate::TomlError;
+
+    /// Parses the body of a document, as its root table, from a &str
+    ///
+    /// Whitespace and comments after the last item are dropped.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut doc = s.parse::<crate::DocumentMut>()?;
+        Ok(std::mem::take(doc.as_table_mut()))
+    }
 }
 
 #[cfg(feature = "display")]
 impl std::fmt::Display for Table {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let children = self.get_values();
-        // print table body
-        for (key_path, value) in children {
-            crate::encode::encode_key_path_ref(&key_path, f, None, DEFAULT_KEY_DECOR)?;
-            write!(f, "=")?;
-            crate::encode::encode_value(value, f, None, DEFAULT_VALUE_DECOR)?;
-            writeln!(f)?;
-        }
-        Ok(())
+        crate::encode::encode_lf(f, |buf| {
+            let children = self.get_values();
+            // print table body
+            for (key_path, value) in children {
+                crate::encode::encode_key_path_ref(&key_path, buf, None, DEFAULT_KEY_DECOR)?;
+                write!(buf, "=")?;
+                crate::encode::encode_value(value, buf, None, DEFAULT_VALUE_DECOR)?;
+                writeln!(buf)?;
+            }
+            self.trailing.encode_with_default(buf, None, "")
+        })
     }
 }
 
 impl<K: Into<Key>, V: Into<Item>> Extend<(K, V)> for Table {
     fn extend<T: IntoIterator<Item = (K, V)>>(&mut self, iter: T) {
         for (key, value) in iter {
-            let key = key.into();
-            let value = value.into();
-            self.items.insert(key, value);
+            self.insert_formatted(&key.into(), value.into());
         }
     }
 }
@@ -504,6 +1583,154 @@ impl<'s> IntoIterator for &'s Table {
 
 pub(crate) type KeyValuePairs = IndexMap<Key, Item>;
 
+pub(crate) fn rename_key(
+    items: &mut KeyValuePairs,
+    from: &str,
+    to: Key,
+) -> Result<(), RenameError> {
+    let mut index = match items.get_full(from) {
+        Some((index, _, item)) if !item.is_none() => index,
+        _ => {
+            return Err(RenameError::Missing {
+                key: from.to_owned(),
+            })
+        }
+    };
+    if to.get() != from {
+        match items.get_full(to.get()) {
+            Some((_, _, item)) if !item.is_none() => {
+                return Err(RenameError::Exists {
+                    key: to.get().to_owned(),
+                });
+            }
+            Some((placeholder, _, _)) => {
+                items.shift_remove_index(placeholder);
+                if placeholder < index {
+                    index -= 1;
+                }
+            }
+            None => {}
+        }
+    }
+    let (old, item) = items
+        .shift_remove_index(index)
+        .expect("index was just looked up");
+    let mut key = to
+        .with_leaf_decor(old.leaf_decor().clone())
+        .with_dotted_decor(old.dotted_decor().clone());
+    key.position = old.position;
+    items.shift_insert(index, key, item);
+    Ok(())
+}
+
+/// Error returned by [`Table::rename_key`] and [`InlineTable::rename_key`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum RenameError {
+    /// The key to rename is not in the table
+    Missing {
+        /// The key that was looked up
+        key: String,
+    },
+    /// The new key is already in the table
+    Exists {
+        /// The key that was requested
+        key: String,
+    },
+}
+
+impl std::fmt::Display for RenameError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RenameError::Missing { key } => write!(f, "key `{key}` does not exist"),
+            RenameError::Exists { key } => write!(f, "key `{key}` already exists"),
+        }
+    }
+}
+
+impl std::error::Error for RenameError {}
+
+/// Order flattened key/value pairs by [`Key::position`], keeping pairs without one after the
+/// pairs preceding them
+pub(crate) fn sort_by_position<'s>(
+    values: Vec<(Vec<&'s Key>, &'s Value)>,
+) -> Vec<(Vec<&'s Key>, &'s Value)> {
+    let mut max_position = 0;
+    let mut values = values
+        .into_iter()
+        .map(|(path, value)| {
+            let position = match path.last().and_then(|key| key.position()) {
+                Some(position) => {
+                    max_position = max_position.max(position);
+                    position
+                }
+                None => max_position,
+            };
+            (position, path, value)
+        })
+        .collect::<Vec<_>>();
+    values.sort_by_key(|(position, _, _)| *position);
+    values
+        .into_iter()
+        .map(|(_, path, value)| (path, value))
+        .collect()
+}
+
+/// Whether the item is written as a key/value pair, rather than under a header
+fn is_entry(item: &Item) -> bool {
+    match item {
+        Item::Value(_) => true,
+        Item::Table(table) => table.is_dotted(),
+        Item::None | Item::ArrayOfTables(_) => false,
+    }
+}
+
+/// The keys of the key/value pairs `key` and `item` are rendered as, in map order
+fn leaf_keys<'t>(key: &'t Key, item: &'t Item, keys: &mut Vec<&'t Key>) {
+    let items = match item {
+        Item::Table(table) if table.is_dotted() => &table.items,
+        Item::Value(Value::InlineTable(table)) if table.is_dotted() => &table.items,
+        Item::Value(_) => {
+            keys.push(key);
+            return;
+        }
+        _ => return,
+    };
+    for (key, item) in items.iter() {
+        leaf_keys(key, item, keys);
+    }
+}
+
+/// Like [`leaf_keys`]
+fn leaf_keys_mut<'t>(key: &'t mut Key, item: &'t mut Item, keys: &mut Vec<&'t mut Key>) {
+    use indexmap::map::MutableKeys;
+    let items = match item {
+        Item::Table(table) if table.is_dotted() => &mut table.items,
+        Item::Value(Value::InlineTable(table)) if table.is_dotted() => &mut table.items,
+        Item::Value(_) => {
+            keys.push(key);
+            return;
+        }
+        _ => return,
+    };
+    for (key, item) in items.iter_mut2() {
+        leaf_keys_mut(key, item, keys);
+    }
+}
+
+/// The indentation of whatever follows `raw`
+fn last_line(raw: &str) -> &str {
+    let start = raw.rfind('\n').map(|i| i + 1).unwrap_or(0);
+    &raw[start..]
+}
+
+/// Replace the indentation of whatever follows `raw`
+fn replace_last_line(raw: Option<&RawString>, default: &str, indent: &str) -> String {
+    let raw = raw.and_then(RawString::as_str).unwrap_or(default);
+    let start = raw.len() - last_line(raw).len();
+    format!("{}{indent}", &raw[..start])
+}
+
 fn decorate_table(table: &mut Table) {
     use indexmap::map::MutableKeys;
     for (mut key, value) in table
@@ -519,9 +1746,13 @@ fn decorate_table(table: &mut Table) {
 }
 
 // `key1 = value1`
+#[cfg(feature = "display")]
 pub(crate) const DEFAULT_ROOT_DECOR: (&str, &str) = ("", "");
+#[cfg(feature = "display")]
 pub(crate) const DEFAULT_KEY_DECOR: (&str, &str) = ("", " ");
+#[cfg(feature = "display")]
 pub(crate) const DEFAULT_TABLE_DECOR: (&str, &str) = ("\n", "");
+#[cfg(feature = "display")]
 pub(crate) const DEFAULT_KEY_PATH_DECOR: (&str, &str) = ("", "");
 
 /// An owned iterator type over `Table`'s key/value pairs.
@@ -530,6 +1761,10 @@ pub type IntoIter = Box<dyn Iterator<Item = (InternalString, Item)>>;
 pub type Iter<'a> = Box<dyn Iterator<Item = (&'a str, &'a Item)> + 'a>;
 /// A mutable iterator type over `Table`'s key/value pairs.
 pub type IterMut<'a> = Box<dyn Iterator<Item = (KeyMut<'a>, &'a mut Item)> + 'a>;
+/// An iterator type over the key/value pairs removed from a `Table`.
+pub type Drain<'a> = Box<dyn Iterator<Item = (Key, Item)> + 'a>;
+/// An owned iterator type over `Table`'s formatted key/value pairs, see [`Table::into_pairs`].
+pub type IntoPairs = Box<dyn Iterator<Item = (Key, Item)>>;
 
 /// This trait represents either a `Table`, or an `InlineTable`.
 pub trait TableLike: crate::private::Sealed {
@@ -673,6 +1908,24 @@ pub enum Entry<'a> {
 }
 
 impl<'a> Entry<'a> {
+    pub(crate) fn new(
+        entry: indexmap::map::Entry<'a, Key, Item>,
+        #[cfg(feature = "display")] profile: Option<&'a std::sync::Arc<crate::ToStringOptions>>,
+    ) -> Self {
+        match entry {
+            indexmap::map::Entry::Occupied(entry) => Entry::Occupied(OccupiedEntry {
+                entry,
+                #[cfg(feature = "display")]
+                profile,
+            }),
+            indexmap::map::Entry::Vacant(entry) => Entry::Vacant(VacantEntry {
+                entry,
+                #[cfg(feature = "display")]
+                profile,
+            }),
+        }
+    }
+
     /// Returns the entry key
     ///
     /// # Examples
@@ -713,6 +1966,8 @@ impl<'a> Entry<'a> {
 /// A view into a single occupied location in a `IndexMap`.
 pub struct OccupiedEntry<'a> {
     pub(crate) entry: indexmap::map::OccupiedEntry<'a, Key, Item>,
+    #[cfg(feature = "display")]
+    profile: Option<&'a std::sync::Arc<crate::ToStringOptions>>,
 }
 
 impl<'a> OccupiedEntry<'a> {
@@ -754,7 +2009,17 @@ impl<'a> OccupiedEntry<'a> {
     }
 
     /// Sets the value of the entry, and returns the entry's old value
+    ///
+    /// In a document with a [`Profile`][crate::Profile], the pair is laid out by the profile.
     pub fn insert(&mut self, value: Item) -> Item {
+        #[cfg(feature = "display")]
+        let value = {
+            use indexmap::map::MutableEntryKey;
+            let mut value = value;
+            let key = self.entry.key_mut().as_mut();
+            crate::format::format_inserted(self.profile, key, &mut value);
+            value
+        };
         self.entry.insert(value)
     }
 
@@ -767,6 +2032,8 @@ impl<'a> OccupiedEntry<'a> {
 /// A view into a single empty location in a `IndexMap`.
 pub struct VacantEntry<'a> {
     pub(crate) entry: indexmap::map::VacantEntry<'a, Key, Item>,
+    #[cfg(feature = "display")]
+    profile: Option<&'a std::sync::Arc<crate::ToStringOptions>>,
 }
 
 impl<'a> VacantEntry<'a> {
@@ -787,8 +2054,17 @@ impl<'a> VacantEntry<'a> {
 
     /// Sets the value of the entry with the `VacantEntry`'s key,
     /// and returns a mutable reference to it
+    ///
+    /// In a document with a [`Profile`][crate::Profile], the pair is laid out by the profile.
     pub fn insert(self, value: Item) -> &'a mut Item {
-        let entry = self.entry;
-        entry.insert(value)
+        #[cfg(feature = "display")]
+        {
+            use indexmap::map::MutableEntryKey;
+            let (mut entry, mut value) = (self.entry, value);
+            crate::format::format_inserted(self.profile, entry.key_mut().as_mut(), &mut value);
+            entry.insert(value)
+        }
+        #[cfg(not(feature = "display"))]
+        self.entry.insert(value)
     }
 }
@@ -0,0 +1,152 @@
+use crate::{DocumentMut, InlineTable, Item, PathSegment, Table, Value};
+
+/// A problem that keeps a document from being rendered faithfully, see [`DocumentMut::validate`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ValidationError {
+    /// A table that can only be written with a header, like the root or an element of an array
+    /// of tables, is marked as [dotted][Table::set_dotted]
+    ///
+    /// Its contents are left out when rendering.
+    DottedHeader {
+        /// Location of the table
+        path: Vec<PathSegment>,
+    },
+    /// A standard table or array of tables within an inline table
+    NotInline {
+        /// Location of the item
+        path: Vec<PathSegment>,
+    },
+    /// An element of an array of tables that is not a table
+    NotTable {
+        /// Location of the item
+        path: Vec<PathSegment>,
+    },
+}
+
+impl ValidationError {
+    /// Location of the problem
+    pub fn path(&self) -> &[PathSegment] {
+        match self {
+            ValidationError::DottedHeader { path } => path,
+            ValidationError::NotInline { path } => path,
+            ValidationError::NotTable { path } => path,
+        }
+    }
+}
+
+impl std::fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let message = match self {
+            ValidationError::DottedHeader { .. } => "table requiring a header is marked as dotted",
+            ValidationError::NotInline { .. } => "inline table contains a standard table",
+            ValidationError::NotTable { .. } => "array of tables contains a non-table",
+        };
+        write!(f, "{message} at `")?;
+        for (i, segment) in self.path().iter().enumerate() {
+            match segment {
+                PathSegment::Key(key) if i == 0 => write!(f, "{}", key.get())?,
+                PathSegment::Key(key) => write!(f, ".{}", key.get())?,
+                PathSegment::Index(index) => write!(f, "[{index}]")?,
+            }
+        }
+        write!(f, "`")
+    }
+}
+
+impl std::error::Error for ValidationError {}
+
+impl DocumentMut {
+    /// Check for edits that the document can't represent when rendered
+    ///
+    /// Parsed documents are always valid, but the editing API allows building items that would be
+    /// silently dropped or fail to parse again, like a standard table within an inline table.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "parse")] {
+    /// use toml_edit::{DocumentMut, ValidationError};
+    ///
+    /// let mut doc = "[[bin]]\nname = 'demo'\n".parse::<DocumentMut>().unwrap();
+    /// assert!(doc.validate().is_ok());
+    ///
+    /// doc["bin"][0] = toml_edit::value(1);
+    /// let errors = doc.validate().unwrap_err();
+    /// assert!(matches!(errors[0], ValidationError::NotTable { .. }));
+    /// assert_eq!(errors[0].to_string(), "array of tables contains a non-table at `bin[0]`");
+    /// # }
+    /// ```
+    pub fn validate(&self) -> Result<(), Vec<ValidationError>> {
+        let mut errors = Vec::new();
+        let mut path = Vec::new();
+        if self.as_table().is_dotted() {
+            errors.push(ValidationError::DottedHeader { path: Vec::new() });
+        }
+        validate_table(&mut path, self.as_table(), &mut errors);
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+fn validate_table(path: &mut Vec<PathSegment>, table: &Table, errors: &mut Vec<ValidationError>) {
+    for (key, item) in table.items.iter() {
+        path.push(PathSegment::Key(key.clone()));
+        match item {
+            Item::None => {}
+            Item::Value(value) => validate_value(path, value, errors),
+            Item::Table(table) => validate_table(path, table, errors),
+            Item::ArrayOfTables(array) => {
+                for (index, item) in array.values.iter().enumerate() {
+                    path.push(PathSegment::Index(index));
+                    match item {
+                        Item::Table(table) => {
+                            if table.is_dotted() {
+                                errors.push(ValidationError::DottedHeader { path: path.clone() });
+                            }
+                            validate_table(path, table, errors);
+                        }
+                        _ => errors.push(ValidationError::NotTable { path: path.clone() }),
+                    }
+                    path.pop();
+                }
+            }
+        }
+        path.pop();
+    }
+}
+
+fn validate_inline_table(
+    path: &mut Vec<PathSegment>,
+    table: &InlineTable,
+    errors: &mut Vec<ValidationError>,
+) {
+    for (key, item) in table.items.iter() {
+        path.push(PathSegment::Key(key.clone()));
+        match item {
+            Item::None => {}
+            Item::Value(value) => validate_value(path, value, errors),
+            Item::Table(_) | Item::ArrayOfTables(_) => {
+                errors.push(ValidationError::NotInline { path: path.clone() });
+            }
+        }
+        path.pop();
+    }
+}
+
+fn validate_value(path: &mut Vec<PathSegment>, value: &Value, errors: &mut Vec<ValidationError>) {
+    match value {
+        Value::Array(array) => {
+            for (index, value) in array.iter().enumerate() {
+                path.push(PathSegment::Index(index));
+                validate_value(path, value, errors);
+                path.pop();
+            }
+        }
+        Value::InlineTable(table) => validate_inline_table(path, table, errors),
+        _ => {}
+    }
+}
@@ -1,11 +1,12 @@
 use std::iter::FromIterator;
+#[cfg(feature = "parse")]
 use std::str::FromStr;
 
 use toml_datetime::{Date, Datetime, Time};
 
 use crate::key::Key;
 use crate::repr::{Decor, Formatted};
-use crate::{Array, InlineTable, InternalString, RawString};
+use crate::{Array, InlineTable, InternalString, Item, ItemKind, RawString};
 
 /// Representation of a TOML Value (as part of a Key/Value Pair).
 #[derive(Debug, Clone)]
@@ -30,14 +31,19 @@ pub enum Value {
 impl Value {
     /// Text description of value type
     pub fn type_name(&self) -> &'static str {
+        self.kind().name()
+    }
+
+    /// The type of the value, see [`Item::kind`]
+    pub fn kind(&self) -> ItemKind {
         match self {
-            Value::String(..) => "string",
-            Value::Integer(..) => "integer",
-            Value::Float(..) => "float",
-            Value::Boolean(..) => "boolean",
-            Value::Datetime(..) => "datetime",
-            Value::Array(..) => "array",
-            Value::InlineTable(..) => "inline table",
+            Value::String(..) => ItemKind::String,
+            Value::Integer(..) => ItemKind::Integer,
+            Value::Float(..) => ItemKind::Float,
+            Value::Boolean(..) => ItemKind::Boolean,
+            Value::Datetime(..) => ItemKind::Datetime,
+            Value::Array(..) => ItemKind::Array,
+            Value::InlineTable(..) => ItemKind::InlineTable,
         }
     }
 
@@ -49,6 +55,16 @@ impl Value {
         }
     }
 
+    /// Casts `self` to a mutable formatted string, to edit it in place.
+    ///
+    /// See [`Formatted::set_value`].
+    pub fn as_string_mut(&mut self) -> Option<&mut Formatted<String>> {
+        match *self {
+            Value::String(ref mut value) => Some(value),
+            _ => None,
+        }
+    }
+
     /// Returns true if `self` is a string.
     pub fn is_str(&self) -> bool {
         self.as_str().is_some()
@@ -62,6 +78,16 @@ impl Value {
         }
     }
 
+    /// Casts `self` to a mutable formatted integer, to edit it in place.
+    ///
+    /// See [`Formatted::set_value`].
+    pub fn as_integer_mut(&mut self) -> Option<&mut Formatted<i64>> {
+        match *self {
+            Value::Integer(ref mut value) => Some(value),
+            _ => None,
+        }
+    }
+
     /// Returns true if `self` is an integer.
     pub fn is_integer(&self) -> bool {
         self.as_integer().is_some()
@@ -75,11 +101,31 @@ impl Value {
         }
     }
 
+    /// Casts `self` to a mutable formatted float, to edit it in place.
+    ///
+    /// See [`Formatted::set_value`].
+    pub fn as_float_mut(&mut self) -> Option<&mut Formatted<f64>> {
+        match *self {
+            Value::Float(ref mut value) => Some(value),
+            _ => None,
+        }
+    }
+
     /// Returns true if `self` is a float.
     pub fn is_float(&self) -> bool {
         self.as_float().is_some()
     }
 
+    /// Returns true if `self` is an infinite float, `inf`, `+inf` or `-inf`.
+    pub fn is_infinite(&self) -> bool {
+        self.as_float().map(f64::is_infinite).unwrap_or(false)
+    }
+
+    /// Returns true if `self` is a float that is not a number, `nan`, `+nan` or `-nan`.
+    pub fn is_nan(&self) -> bool {
+        self.as_float().map(f64::is_nan).unwrap_or(false)
+    }
+
     /// Casts `self` to boolean.
     pub fn as_bool(&self) -> Option<bool> {
         match *self {
@@ -88,6 +134,16 @@ impl Value {
         }
     }
 
+    /// Casts `self` to a mutable formatted boolean, to edit it in place.
+    ///
+    /// See [`Formatted::set_value`].
+    pub fn as_bool_mut(&mut self) -> Option<&mut Formatted<bool>> {
+        match *self {
+            Value::Boolean(ref mut value) => Some(value),
+            _ => None,
+        }
+    }
+
     /// Returns true if `self` is a boolean.
     pub fn is_bool(&self) -> bool {
         self.as_bool().is_some()
@@ -101,6 +157,16 @@ impl Value {
         }
     }
 
+    /// Casts `self` to a mutable formatted date-time, to edit it in place.
+    ///
+    /// See [`Formatted::set_value`].
+    pub fn as_datetime_mut(&mut self) -> Option<&mut Formatted<Datetime>> {
+        match *self {
+            Value::Datetime(ref mut value) => Some(value),
+            _ => None,
+        }
+    }
+
     /// Returns true if `self` is a date-time.
     pub fn is_datetime(&self) -> bool {
         self.as_datetime().is_some()
@@ -206,6 +272,51 @@ impl Value {
         *decor = Decor::new(prefix, suffix);
     }
 
+    /// Returns the comment following the value on its line, stripped of its `#` and surrounding
+    /// whitespace
+    ///
+    /// This reads the value's suffix, which holds the comment for the value of a key/value pair
+    /// in a standard table.  For elements of an array, see [`Array::trailing_comment`].
+    ///
+    /// This generally requires a [`DocumentMut`][crate::DocumentMut].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "parse")] {
+    /// let doc = "port = 80 # default\n".parse::<toml_edit::DocumentMut>().unwrap();
+    /// let port = doc["port"].as_value().unwrap();
+    /// assert_eq!(port.trailing_comment(), Some("default"));
+    /// # }
+    /// ```
+    pub fn trailing_comment(&self) -> Option<&str> {
+        self.decor().suffix().and_then(crate::repr::line_comment)
+    }
+
+    /// Replaces or, with `None`, removes the comment following the value on its line
+    ///
+    /// This is only valid for the value of a key/value pair in a standard table, as a comment
+    /// runs to the end of the line.  For elements of an array, see
+    /// [`Array::set_trailing_comment`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "parse")] {
+    /// # #[cfg(feature = "display")] {
+    /// let mut doc = "port = 80 # default\n".parse::<toml_edit::DocumentMut>().unwrap();
+    /// let port = doc["port"].as_value_mut().unwrap();
+    /// port.set_trailing_comment(Some("http"));
+    /// assert_eq!(doc.to_string(), "port = 80 # http\n");
+    /// # }
+    /// # }
+    /// ```
+    pub fn set_trailing_comment(&mut self, comment: Option<&str>) {
+        let decor = self.decor_mut();
+        let suffix = decor.suffix().cloned().unwrap_or_default();
+        decor.set_suffix(crate::repr::set_line_comment(&suffix, comment, None));
+    }
+
     /// The location within the original document
     ///
     /// This generally requires an [`ImDocument`][crate::ImDocument].
@@ -221,6 +332,46 @@ impl Value {
         }
     }
 
+    /// Store how each scalar within the value is currently rendered as its representation
+    ///
+    /// Values record the representation they were parsed with, like `0xff` or `'literal'`, which
+    /// is rendered as-is for as long as the value isn't replaced, no matter what else in the
+    /// document is edited.  Values created through the API have no representation and are
+    /// rendered in the default style, which may differ between versions of `toml_edit`.
+    /// Pinning freezes that rendering, see [`Formatted::pin_repr`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # #[cfg(feature = "display")] {
+    /// let mut value = toml_edit::Value::from(vec![1.5]);
+    /// value.pin_repr();
+    /// let float = value.as_array_mut().unwrap().get_mut(0).unwrap();
+    /// let repr = float.as_float_mut().unwrap().as_repr().unwrap();
+    /// assert_eq!(repr.as_raw().as_str(), Some("1.5"));
+    /// # }
+    /// ```
+    #[cfg(feature = "display")]
+    pub fn pin_repr(&mut self) {
+        match self {
+            Value::String(f) => f.pin_repr(),
+            Value::Integer(f) => f.pin_repr(),
+            Value::Float(f) => f.pin_repr(),
+            Value::Boolean(f) => f.pin_repr(),
+            Value::Datetime(f) => f.pin_repr(),
+            Value::Array(a) => {
+                for value in a.iter_mut() {
+                    value.pin_repr();
+                }
+            }
+            Value::InlineTable(t) => {
+                for (_, value) in t.iter_mut() {
+                    value.pin_repr();
+                }
+            }
+        }
+    }
+
     pub(crate) fn despan(&mut self, input: &str) {
         match self {
             Value::String(f) => f.despan(input),
@@ -244,6 +395,11 @@ impl FromStr for Value {
     }
 }
 
+#[cfg(feature = "parse")]
+pub(crate) fn unexpected_kind(expected: ItemKind, value: &Value) -> crate::TomlError {
+    crate::TomlError::custom(format!("expected {expected}, found {}", value.kind()), None)
+}
+
 impl<'b> From<&'b Value> for Value {
     fn from(s: &'b Value) -> Self {
         s.clone()
@@ -331,6 +487,24 @@ impl From<InlineTable> for Value {
     }
 }
 
+impl<V: Into<Value>> From<Vec<V>> for Value {
+    fn from(values: Vec<V>) -> Self {
+        values.into_iter().collect()
+    }
+}
+
+impl<K: Into<Key>, V: Into<Value>> From<std::collections::BTreeMap<K, V>> for Value {
+    fn from(map: std::collections::BTreeMap<K, V>) -> Self {
+        map.into_iter().collect()
+    }
+}
+
+impl<K: Into<Key>, V: Into<Value>, S> From<std::collections::HashMap<K, V, S>> for Value {
+    fn from(map: std::collections::HashMap<K, V, S>) -> Self {
+        map.into_iter().collect()
+    }
+}
+
 impl<V: Into<Value>> FromIterator<V> for Value {
     fn from_iter<I>(iter: I) -> Self
     where
@@ -351,16 +525,170 @@ impl<K: Into<Key>, V: Into<Value>> FromIterator<(K, V)> for Value {
     }
 }
 
+// Compare against the underlying value, ignoring formatting. Integers and floats are never
+// equal to each other.
+macro_rules! impl_partial_eq {
+    ($($this:ty),*) => {$(
+        impl PartialEq<i64> for $this {
+            fn eq(&self, other: &i64) -> bool {
+                self.as_integer() == Some(*other)
+            }
+        }
+
+        impl PartialEq<f64> for $this {
+            fn eq(&self, other: &f64) -> bool {
+                self.as_float() == Some(*other)
+            }
+        }
+
+        impl PartialEq<bool> for $this {
+            fn eq(&self, other: &bool) -> bool {
+                self.as_bool() == Some(*other)
+            }
+        }
+
+        impl PartialEq<str> for $this {
+            fn eq(&self, other: &str) -> bool {
+                self.as_str() == Some(other)
+            }
+        }
+
+        impl PartialEq<String> for $this {
+            fn eq(&self, other: &String) -> bool {
+                self.as_str() == Some(other.as_str())
+            }
+        }
+
+        impl PartialEq<$this> for i64 {
+            fn eq(&self, other: &$this) -> bool {
+                other == self
+            }
+        }
+
+        impl PartialEq<$this> for f64 {
+            fn eq(&self, other: &$this) -> bool {
+                other == self
+            }
+        }
+
+        impl PartialEq<$this> for bool {
+            fn eq(&self, other: &$this) -> bool {
+                other == self
+            }
+        }
+
+        impl PartialEq<$this> for str {
+            fn eq(&self, other: &$this) -> bool {
+                other == self
+            }
+        }
+
+        impl PartialEq<$this> for String {
+            fn eq(&self, other: &$this) -> bool {
+                other == self
+            }
+        }
+    )*};
+}
+
+impl_partial_eq!(Value, &Value, &mut Value, Item, &Item, &mut Item);
+
+// References to `Value` and `Item` get these through the blanket `&A == &B` impl
+macro_rules! impl_partial_eq_str_ref {
+    ($($this:ty),*) => {$(
+        impl<'b> PartialEq<&'b str> for $this {
+            fn eq(&self, other: &&'b str) -> bool {
+                self.as_str() == Some(*other)
+            }
+        }
+
+        impl<'b> PartialEq<$this> for &'b str {
+            fn eq(&self, other: &$this) -> bool {
+                other == self
+            }
+        }
+    )*};
+}
+
+impl_partial_eq_str_ref!(Value, Item);
+
+/// Compare the underlying values, ignoring formatting
+///
+/// Integers and floats are never equal to each other and inline tables are compared regardless
+/// of the order of their keys.  Unlike `f64`, a `NaN` float is equal to itself, so values can be
+/// used within a `HashSet` or as the keys of a `HashMap`.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "parse")] {
+/// use std::collections::HashSet;
+/// use toml_edit::Value;
+///
+/// let values = ["0x10", "16", "{ a = 1, b = nan }", "{b=nan,a=1}", "16.0"];
+/// let unique = values
+///     .iter()
+///     .map(|v| v.parse::<Value>().unwrap())
+///     .collect::<HashSet<_>>();
+/// assert_eq!(unique.len(), 3);
+/// # }
+/// ```
+impl PartialEq for Value {
+    fn eq(&self, other: &Self) -> bool {
+        crate::diff::value_eq(self, other)
+    }
+}
+
+impl Eq for Value {}
+
+impl std::hash::Hash for Value {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        std::mem::discriminant(self).hash(state);
+        match self {
+            Value::String(f) => f.value().hash(state),
+            Value::Integer(f) => f.value().hash(state),
+            Value::Float(f) => {
+                // Consistent with `PartialEq`, where `-0.0 == 0.0` and `NaN == NaN`
+                let f = *f.value();
+                let f = if f == 0.0 {
+                    0.0
+                } else if f.is_nan() {
+                    f64::NAN
+                } else {
+                    f
+                };
+                f.to_bits().hash(state);
+            }
+            Value::Boolean(f) => f.value().hash(state),
+            Value::Datetime(f) => f.value().hash(state),
+            Value::Array(array) => {
+                array.len().hash(state);
+                for value in array.iter() {
+                    value.hash(state);
+                }
+            }
+            Value::InlineTable(table) => {
+                let mut pairs = table.iter().collect::<Vec<_>>();
+                pairs.sort_by_key(|(key, _)| *key);
+                pairs.hash(state);
+            }
+        }
+    }
+}
+
 #[cfg(feature = "display")]
 impl std::fmt::Display for Value {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        crate::encode::encode_value(self, f, None, ("", ""))
+        crate::encode::encode_lf(f, |buf| {
+            crate::encode::encode_value(self, buf, None, ("", ""))
+        })
     }
 }
 
 // `key1 = value1`
 pub(crate) const DEFAULT_VALUE_DECOR: (&str, &str) = (" ", "");
 // `{ key = value }`
+#[cfg(feature = "display")]
 pub(crate) const DEFAULT_TRAILING_VALUE_DECOR: (&str, &str) = (" ", " ");
 // `[value1, value2]`
 pub(crate) const DEFAULT_LEADING_VALUE_DECOR: (&str, &str) = ("", "");
@@ -377,6 +705,42 @@ mod tests {
         let features: Value = features.iter().cloned().collect();
         assert_eq!(features.to_string(), r#"["node", "mouth"]"#);
     }
+
+    #[test]
+    fn eq_hash_ignore_formatting() {
+        use std::collections::hash_map::DefaultHasher;
+        use std::hash::{Hash, Hasher};
+
+        fn hash(value: &Value) -> u64 {
+            let mut hasher = DefaultHasher::new();
+            value.hash(&mut hasher);
+            hasher.finish()
+        }
+
+        let equal = [
+            ("'a'", r#""a""#),
+            ("1_000", "1000"),
+            ("-0.0", "0.0"),
+            ("nan", "-nan"),
+            ("[ 1, [2] ]", "[1,[2,],]"),
+            ("{ a = 1, b.c = 2 }", "{ b = { c = 2 }, a = 1 }"),
+            ("1979-05-27T07:32:00Z", "1979-05-27 07:32:00Z"),
+        ];
+        for (lhs, rhs) in equal {
+            let (lhs, rhs) = (lhs.parse::<Value>().unwrap(), rhs.parse::<Value>().unwrap());
+            assert_eq!(lhs, rhs);
+            assert_eq!(hash(&lhs), hash(&rhs), "{lhs} and {rhs}");
+        }
+
+        let unequal = [
+            ("1", "1.0"),
+            ("[1, 2]", "[2, 1]"),
+            ("{ a = 1 }", "{ a = 1, b = 2 }"),
+        ];
+        for (lhs, rhs) in unequal {
+            assert_ne!(lhs.parse::<Value>().unwrap(), rhs.parse::<Value>().unwrap());
+        }
+    }
 }
 
 #[test]
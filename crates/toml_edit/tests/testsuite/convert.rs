@@ -86,3 +86,33 @@ table= [{ string = "value", array = [1, 2, 3], inline = { "1" = 1, "2" = 2 }, ch
 
 "#]].raw());
 }
+
+#[test]
+fn table_into_inline_preserves_formatting() {
+    let toml = r#"
+[table]
+# comment
+  dotted.key = 1 # trailing
+  spaced   =   2
+"#;
+    let mut doc = toml.parse::<DocumentMut>().unwrap();
+
+    let t = doc.remove("table").unwrap().into_table().unwrap();
+    let t = t.into_inline_table();
+    doc.insert("table", toml_edit::value(t.clone()));
+    doc.insert("roundtrip", Item::Table(t.into_table()));
+
+    let actual = doc.to_string();
+    assert_data_eq!(
+        actual,
+        str![[r#"
+table = { dotted.key = 1, spaced   =   2 }
+
+[roundtrip]
+dotted.key = 1
+spaced   =   2
+
+"#]]
+        .raw()
+    );
+}
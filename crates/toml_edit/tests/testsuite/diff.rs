@@ -0,0 +1,159 @@
+use toml_edit::{diff, Change, DocumentMut, PathSegment};
+
+fn paths(changes: &[Change]) -> Vec<(&'static str, String)> {
+    changes
+        .iter()
+        .map(|change| {
+            let kind = match change {
+                Change::Added { .. } => "added",
+                Change::Removed { .. } => "removed",
+                Change::Modified { .. } => "modified",
+            };
+            let path = change
+                .path()
+                .iter()
+                .map(|segment| match segment {
+                    PathSegment::Key(key) => key.get().to_owned(),
+                    PathSegment::Index(index) => index.to_string(),
+                })
+                .collect::<Vec<_>>()
+                .join(".");
+            (kind, path)
+        })
+        .collect()
+}
+
+#[test]
+fn formatting_only() {
+    let old = r#"
+[package]
+name = "foo"
+version = "1.0"
+authors = ["a", "b"]
+inline = { x = 1 }
+"#
+    .parse::<DocumentMut>()
+    .unwrap();
+    let new = r#"
+# Reordered and reformatted
+[package]
+version =   '1.0'
+name = "foo" # comment
+authors = [
+  "a",
+  "b",
+]
+
+[package.inline]
+x = 0x1
+"#
+    .parse::<DocumentMut>()
+    .unwrap();
+    assert_eq!(paths(&diff(&old, &new)), []);
+    assert!(old.semantic_eq(&new));
+    assert!(new.semantic_eq(&old));
+}
+
+#[test]
+fn changes() {
+    let old = r#"
+a = 1
+b = [1, 2, 3]
+c = { d = true }
+
+[[bin]]
+name = "one"
+
+[[bin]]
+name = "two"
+"#
+    .parse::<DocumentMut>()
+    .unwrap();
+    let new = r#"
+a = 2
+b = [1, 5]
+c = { d = true, e = false }
+f = "new"
+
+[[bin]]
+name = "one"
+"#
+    .parse::<DocumentMut>()
+    .unwrap();
+    assert!(!old.semantic_eq(&new));
+    let changes = diff(&old, &new);
+    assert_eq!(
+        paths(&changes),
+        [
+            ("modified", "a".to_owned()),
+            ("modified", "b.1".to_owned()),
+            ("removed", "b.2".to_owned()),
+            ("added", "c.e".to_owned()),
+            ("removed", "bin.1".to_owned()),
+            ("added", "f".to_owned()),
+        ]
+    );
+    match &changes[0] {
+        Change::Modified { old, new, .. } => {
+            assert_eq!(old.as_integer(), Some(1));
+            assert_eq!(new.as_integer(), Some(2));
+        }
+        _ => unreachable!(),
+    }
+}
+
+#[test]
+fn semantic_eq_arrays_of_tables() {
+    let lhs = r#"
+[[bin]]
+name = "one"
+
+[[bin]]
+name = "two"
+"#
+    .parse::<DocumentMut>()
+    .unwrap();
+    let rhs = r#"bin = [{ name = "one" }, { name = "two" }]"#.parse::<DocumentMut>().unwrap();
+    let swapped = r#"bin = [{ name = "two" }, { name = "one" }]"#.parse::<DocumentMut>().unwrap();
+    assert!(lhs.semantic_eq(&rhs));
+    assert!(!lhs.semantic_eq(&swapped));
+
+    let mut removed = lhs.clone();
+    removed["other"] = toml_edit::value(1);
+    assert!(!lhs.semantic_eq(&removed));
+    removed["other"] = toml_edit::Item::None;
+    assert!(lhs.semantic_eq(&removed));
+}
+
+#[test]
+fn content_hash() {
+    let lhs = r#"
+[package]
+name = "foo"
+floats = [0.0, nan, 1e3]
+
+[[bin]]
+name = "one"
+"#
+    .parse::<DocumentMut>()
+    .unwrap();
+    let rhs = r#"
+bin = [{ name = "one" }] # as inline tables
+package = { floats = [ -0.0, -nan, 1000.0 ], name = 'foo' }
+"#
+    .parse::<DocumentMut>()
+    .unwrap();
+    assert_eq!(lhs.content_hash(), rhs.content_hash());
+    assert_ne!(lhs.content_hash_ordered(), rhs.content_hash_ordered());
+
+    let other = lhs.to_string().replace("one", "two");
+    let other = other.parse::<DocumentMut>().unwrap();
+    assert_ne!(lhs.content_hash(), other.content_hash());
+    let int = "a = 1".parse::<DocumentMut>().unwrap();
+    let float = "a = 1.0".parse::<DocumentMut>().unwrap();
+    assert_ne!(int.content_hash(), float.content_hash());
+
+    // Stable across versions
+    let empty = DocumentMut::new();
+    assert_eq!(empty.content_hash(), 0x3c4b_2e4f_af32_7503);
+}
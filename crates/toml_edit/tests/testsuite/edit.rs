@@ -3,7 +3,10 @@ use std::iter::FromIterator;
 use snapbox::assert_data_eq;
 use snapbox::prelude::*;
 use snapbox::str;
-use toml_edit::{array, table, value, DocumentMut, Item, Key, Table, Value};
+use toml_edit::{
+    array, table, value, DocumentMut, EmptyTableOptions, ExponentStyle, Item, Key, Radix,
+    RawString, StringStyle, Table, Value,
+};
 
 macro_rules! parse_key {
     ($s:expr) => {{
@@ -278,6 +281,45 @@ hello = "world"
 "#]]);
 }
 
+#[test]
+fn test_push_into_parsed_array() {
+    given(
+        r#"
+        [[bin]]
+        name = "first"
+
+        [package]
+        title = "witharray"
+
+        [[bin]]
+        name = "second""#,
+    )
+    .running(|root| {
+        let array = root["bin"].as_array_of_tables_mut().unwrap();
+        let mut table = Table::new();
+        table["name"] = value("third");
+        array.push(table);
+        let first = array.get_mut(0).unwrap();
+        first["path"] = value("src/first.rs");
+    })
+    .produces_display(str![[r#"
+
+        [[bin]]
+        name = "first"
+path = "src/first.rs"
+
+        [package]
+        title = "witharray"
+
+        [[bin]]
+        name = "second"
+
+[[bin]]
+name = "third"
+
+"#]]);
+}
+
 #[test]
 fn test_insert_values() {
     given(
@@ -330,6 +372,135 @@ name = "dep"
 "#]]);
 }
 
+#[test]
+fn test_insert_validated_keys() {
+    given("")
+        .running(|root| {
+            for name in ["plain", "with space", "it's", r#"say "hi""#, "tab\there"] {
+                root.insert_formatted(&Key::try_new(name).unwrap(), value(1));
+            }
+            assert!(Key::try_new("").is_err());
+            assert_eq!(
+                Key::try_new("line\r\nbreak").unwrap_err().to_string(),
+                r#"key contains control character '\r' at byte 4"#
+            );
+        })
+        .produces_display(str![[r#"
+plain = 1
+"with space" = 1
+"it's" = 1
+'say "hi"' = 1
+"tab\there" = 1
+
+"#]]);
+}
+
+#[test]
+fn test_rename_key() {
+    given(
+        r#"
+        # leading
+        old = { a = 1, b = 2 } # trailing
+        keep = 1
+
+        # about the table
+        [ old-table ]
+        x = 1"#,
+    )
+    .running(|root| {
+        root.rename_key("old", "new").unwrap();
+        root.rename_key("old-table", parse_key!("'new table'"))
+            .unwrap();
+        let inline = root["new"].as_inline_table_mut().unwrap();
+        inline.rename_key("a", "c").unwrap();
+        assert_eq!(
+            inline.rename_key("b", "c").unwrap_err().to_string(),
+            "key `c` already exists"
+        );
+        assert_eq!(
+            root.rename_key("missing", "keep").unwrap_err().to_string(),
+            "key `missing` does not exist"
+        );
+        assert!(root.rename_key("keep", "new").is_err());
+        root.rename_key("keep", "keep").unwrap();
+    })
+    .produces_display(str![[r#"
+
+        # leading
+        new = { c = 1, b = 2 } # trailing
+        keep = 1
+
+        # about the table
+        [ 'new table' ]
+        x = 1
+
+"#]]);
+}
+
+#[test]
+fn test_insert_table_from_map() {
+    given(
+        r#"
+        [package]
+        name = "demo""#,
+    )
+    .running(|root| {
+        let mut bin = std::collections::BTreeMap::new();
+        bin.insert("name", Value::from("cli"));
+        bin.insert("features", Value::from(vec!["a", "b"]));
+        let matrix: Value = vec![Value::from_iter([("os", "linux")])].into();
+        let mut tool = std::collections::BTreeMap::new();
+        tool.insert("bin", Value::from(vec![Value::from(bin)]));
+        tool.insert("matrix", Value::from(vec![matrix]));
+        tool.insert("empty", Value::from(Vec::<Value>::new()));
+        root.insert("tool", Item::Table(Table::from_map(tool)));
+    })
+    .produces_display(str![[r#"
+
+        [package]
+        name = "demo"
+
+[tool]
+empty = []
+matrix = [[{ os = "linux" }]]
+
+[[tool.bin]]
+features = ["a", "b"]
+name = "cli"
+
+"#]]);
+}
+
+#[test]
+fn test_get_or_insert_item_kinds() {
+    given(
+        r#"
+        a = 1
+        [t]"#,
+    )
+    .running(|root| {
+        assert!(root["a"].get_or_insert_array().is_none());
+        assert!(root["t"].get_or_insert_value(1).is_none());
+        assert!(root["t"].get_or_insert_table().is_some());
+        root["t"]["list"].get_or_insert_array().unwrap().push(1);
+        root["t"]["list"].get_or_insert_array().unwrap().push(2);
+        root["t"]["name"].get_or_insert_value("x");
+        root["t"]["name"].get_or_insert_value("y");
+        let bins = root["bin"].get_or_insert_array_of_tables().unwrap();
+        bins.push(Table::new());
+    })
+    .produces_display(str![[r#"
+
+        a = 1
+        [t]
+list = [1, 2]
+name = "x"
+
+[[bin]]
+
+"#]]);
+}
+
 // removal
 
 #[test]
@@ -521,6 +692,67 @@ fn test_remove_value() {
 "#]]);
 }
 
+#[test]
+fn test_remove_value_preserving_comment() {
+    given(
+        r#"
+        name = "hello"
+        # keep this
+        version = "1.0.0" # please
+        documentation = "https://docs.rs/hello"
+        # kept after the last key
+        readme = "README.md""#,
+    )
+    .running(|root| {
+        let value = root.remove_preserving_comment("version");
+        assert_eq!(value.unwrap().as_str(), Some("1.0.0"));
+        let value = root.remove_preserving_comment("readme");
+        assert_eq!(value.unwrap().as_str(), Some("README.md"));
+    })
+    .produces_display(str![[r#"
+
+        name = "hello"
+        # keep this
+        documentation = "https://docs.rs/hello"
+        # kept after the last key
+
+"#]]);
+}
+
+#[test]
+fn test_remove_dotted_preserving_comment() {
+    given(
+        r#"
+        # about a.b
+        a.b = 1
+        # about x
+        x = 1
+        # about a.c
+        a.c = 2
+        # about y
+        y = 2
+        # about z
+        z = 3"#,
+    )
+    .running(|root| {
+        let value = root.remove_preserving_comment("x");
+        assert_eq!(value.unwrap().as_integer(), Some(1));
+        let value = root.remove_preserving_comment("a");
+        assert!(value.unwrap().is_table());
+    })
+    .produces_display(str![[r#"
+
+        # about a.b
+        # about x
+        # about a.c
+        # about y
+        y = 2
+        # about z
+        z = 3
+
+"#]]);
+}
+
 #[test]
 fn test_remove_last_value_from_implicit() {
     given(
@@ -543,6 +775,77 @@ fn test_remove_last_value_from_implicit() {
     .produces_display(str![]);
 }
 
+#[test]
+fn test_drain_filter_into_other_table() {
+    given(
+        r#"
+        [package]
+        name = "demo"
+        # not a package field
+        debug = true
+        version = "1.0"
+
+        [package.metadata]
+        x = 1"#,
+    )
+    .running(|root| {
+        let package = root.get_mut("package").unwrap();
+        let package = as_table!(package);
+        let drained = package
+            .drain_filter(|key, _| key == "debug")
+            .collect::<Vec<_>>();
+        let profile = root.get_or_insert_table(&["profile"]).unwrap();
+        for (key, item) in drained {
+            profile.insert_formatted(&key, item);
+        }
+        let metadata = root.get_mut("package").unwrap();
+        let metadata = as_table!(metadata).get_mut("metadata").unwrap();
+        let metadata = as_table!(metadata);
+        assert_eq!(metadata.drain().count(), 1);
+        assert!(metadata.is_empty());
+    })
+    .produces_display(str![[r#"
+
+        [package]
+        name = "demo"
+        version = "1.0"
+
+        [package.metadata]
+
+[profile]
+        # not a package field
+        debug = true
+
+"#]]);
+}
+
+#[test]
+fn test_drain_filter_panic_keeps_table() {
+    given(
+        r#"
+        a = 1
+        b = 2
+        c = 3"#,
+    )
+    .running(|root| {
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            root.drain_filter(|key, _| {
+                assert_ne!(key, "b");
+                true
+            })
+            .count()
+        }));
+        assert!(result.is_err());
+    })
+    .produces_display(str![[r#"
+
+        a = 1
+        b = 2
+        c = 3
+
+"#]]);
+}
+
 // values
 
 #[test]
@@ -615,6 +918,125 @@ fn test_sort_values_by() {
 "#]]);
 }
 
+#[test]
+fn test_sort_inline_table_values() {
+    given(
+        r#"
+        a = { c = 3, b.y = 2, a = 1, b.x = 1 }
+        b = {z=1, y=2}"#,
+    )
+    .running(|root| {
+        let a = root.get_mut("a").unwrap().as_inline_table_mut().unwrap();
+        a.sort_values();
+        let b = root.get_mut("b").unwrap().as_inline_table_mut().unwrap();
+        b.sort_values_by(|k1, _, k2, _| k1.get().cmp(k2.get()));
+    })
+    .produces_display(str![[r#"
+
+        a = { a = 1, b.x = 1, b.y = 2, c = 3 }
+        b = {y=2, z=1}
+
+"#]]);
+}
+
+#[test]
+fn test_sort_values_recursive_by() {
+    given(
+        r#"
+        b = { z = 1, y = { d = 4, c = 3 } }
+        a = 0
+
+        [t]
+        z = 1 # comment on z
+        y = 2
+
+        [[t.arr]]
+        b = 2
+        a = 1
+        [[t.arr]]
+        d = 4
+        c = 3"#,
+    )
+    .running(|root| {
+        root.sort_values_recursive_by(|k1, _, k2, _| k1.get().cmp(k2.get()));
+    })
+    .produces_display(str![[r#"
+        a = 0
+
+        b = { y = { c = 3, d = 4 }, z = 1 }
+
+        [t]
+        y = 2
+        z = 1 # comment on z
+
+        [[t.arr]]
+        a = 1
+        b = 2
+        [[t.arr]]
+        c = 3
+        d = 4
+
+"#]]);
+}
+
+#[test]
+fn test_inline_sort_values_recursive_by() {
+    given(r#"a = { z = 1, y = { d = 4, c = 3 }, x = [1] }"#)
+        .running(|root| {
+            let a = root.get_mut("a").unwrap().as_inline_table_mut().unwrap();
+            a.sort_values_recursive_by(|k1, _, k2, _| k1.get().cmp(k2.get()));
+        })
+        .produces_display(str![[r#"
+a = { x = [1], y = { c = 3, d = 4 }, z = 1 }
+
+"#]]);
+}
+
+#[test]
+fn test_sort_by_order() {
+    given(
+        r#"
+        [dependencies]
+        serde = "1"
+        [dependencies.toml]
+        version = "0.8"
+
+        [[bin]]
+        name = "cli"
+
+        [package]
+        version = "1.0" # bumped
+        edition = "2021"
+        name = "demo"
+        [package.metadata]
+        x = 1"#,
+    )
+    .running(|root| {
+        root.sort_by_order_recursive(&[
+            (&[], &["package", "bin", "dependencies"]),
+            (&["package"], &["name", "version"]),
+        ]);
+    })
+    .produces_display(str![[r#"
+
+        [package]
+        name = "demo"
+        version = "1.0" # bumped
+        edition = "2021"
+        [package.metadata]
+        x = 1
+
+        [[bin]]
+        name = "cli"
+
+        [dependencies]
+        serde = "1"
+        [dependencies.toml]
+        version = "0.8"
+
+"#]]);
+}
+
 #[test]
 fn test_set_position() {
     given(
@@ -783,6 +1205,95 @@ fn test_remove_from_array() {
 "#]]);
 }
 
+#[test]
+fn test_insert_remove_multiline_array() {
+    given(
+        r#"
+        a = [
+          # first
+          1,
+          2
+        ]
+        b = [
+          1,
+          2,
+        ]
+        c = [ 1, 2 ]"#,
+    )
+    .running(|root| {
+        let a = root.get_mut("a").unwrap();
+        let a = as_array!(a);
+        a.insert(0, 0);
+        a.insert(3, 3);
+        let b = root.get_mut("b").unwrap();
+        let b = as_array!(b);
+        b.insert(1, "mid");
+        assert_eq!(b.remove(2).as_integer(), Some(2));
+        let c = root.get_mut("c").unwrap();
+        let c = as_array!(c);
+        c.insert(0, 0);
+        assert_eq!(c.remove(2).as_integer(), Some(2));
+    })
+    .produces_display(str![[r#"
+
+        a = [
+          0,
+          # first
+          1,
+          2,
+          3
+        ]
+        b = [
+          1,
+          "mid",
+        ]
+        c = [ 0, 1 ]
+
+"#]]);
+}
+
+#[test]
+fn test_retain_array() {
+    given(
+        r#"
+        a = [1, 2, 3, 4]
+        b = [
+          "x", # keep
+          "y",
+          "z"
+        ]
+        c = [ 1, 2 ] # none left
+        d = [1, 2, 3]"#,
+    )
+    .running(|root| {
+        for (key, value) in root.iter_mut() {
+            let array = as_array!(value);
+            match key.get() {
+                "a" => array.retain(|v| v.as_integer() != Some(1)),
+                "b" => array.retain(|v| v.as_str() != Some("z")),
+                "c" => array.retain(|_| false),
+                _ => array.retain_mut(|v| {
+                    if let Some(i) = v.as_integer() {
+                        *v = (i * 10).into();
+                    }
+                    v.as_integer() != Some(30)
+                }),
+            }
+        }
+    })
+    .produces_display(str![[r#"
+
+        a = [2, 3, 4]
+        b = [
+          "x", # keep
+          "y"
+        ]
+        c = [] # none left
+        d = [10, 20]
+
+"#]]);
+}
+
 #[test]
 fn test_format_array() {
     given(
@@ -807,28 +1318,224 @@ fn test_format_array() {
     "#]]);
 }
 
-macro_rules! as_inline_table {
-    ($entry:ident) => {{
-        assert!($entry.is_value());
-        let a = $entry.as_value_mut().unwrap();
-        assert!(a.is_inline_table());
-        a.as_inline_table_mut().unwrap()
-    }};
-}
-
 #[test]
-fn test_insert_into_inline_table() {
+fn test_set_multiline_array() {
     given(
         r#"
-        a = {a=2,  c = 3}
-        b = {}"#,
+features = ["derive", "std"]
+short = [1, 2]
+empty = []
+wrapped = [
+  "x", # keep
+]
+nested = [
+  [1, 2],
+  [3, 4],
+]
+accent = ["é"]
+"#,
     )
     .running(|root| {
-        {
-            let a = root.get_mut("a").unwrap();
-            let a = as_inline_table!(a);
-            assert_eq!(a.len(), 2);
-            assert!(a.contains_key("a") && a.get("c").is_some() && a.get_mut("c").is_some());
+        let features = &mut root["features"];
+        as_array!(features).set_multiline(true);
+        let short = &mut root["short"];
+        assert!(!as_array!(short).wrap_to_width(6));
+        assert!(as_array!(short).wrap_to_width(5));
+        let empty = &mut root["empty"];
+        assert!(!as_array!(empty).wrap_to_width(0));
+        let wrapped = &mut root["wrapped"];
+        as_array!(wrapped).set_multiline(true);
+        let nested = &mut root["nested"];
+        as_array!(nested)
+            .get_mut(0)
+            .unwrap()
+            .as_array_mut()
+            .unwrap()
+            .set_multiline(true);
+        as_array!(nested)
+            .get_mut(1)
+            .unwrap()
+            .as_array_mut()
+            .unwrap()
+            .set_multiline_with(1);
+        let accent = &mut root["accent"];
+        assert!(!as_array!(accent).wrap_to_width(5));
+    })
+    .produces_display(str![[r#"
+
+features = [
+    "derive",
+    "std",
+]
+short = [
+    1,
+    2,
+]
+empty = []
+wrapped = [
+  "x", # keep
+]
+nested = [
+  [
+    1,
+    2,
+  ],
+  [
+   3,
+   4,
+  ],
+]
+accent = ["é"]
+
+"#]]);
+}
+
+#[test]
+fn test_reindent() {
+    given(
+        r#"
+[[bin]]
+    name = "a"
+    # about b
+    b.c = 1
+    [bin.extra]
+        x = 1
+"#,
+    )
+    .running(|root| {
+        let bin = root["bin"][0].as_table_mut().unwrap();
+        bin["path"] = value("main.rs");
+        bin.insert("other", table());
+        bin["other"]["y"] = value(2);
+        bin.reindent();
+
+        root.insert("flat", table());
+        root["flat"]["z"] = value(3);
+        root["flat"]["nested"] = table();
+        root["flat"]["nested"]["w"] = value(4);
+        root["flat"].as_table_mut().unwrap().set_indent(2);
+    })
+    .produces_display(str![[r#"
+
+[[bin]]
+    name = "a"
+    # about b
+    b.c = 1
+    path = "main.rs"
+    [bin.extra]
+        x = 1
+
+    [bin.other]
+        y = 2
+
+[flat]
+  z = 3
+
+  [flat.nested]
+    w = 4
+
+"#]]);
+}
+
+#[test]
+fn test_set_number_style() {
+    given(
+        r#"
+    mask = 255 # all bits
+    size = 1000000
+    scale = 0.00015
+    offset = -4"#,
+    )
+    .running(|root| {
+        let Some(Value::Integer(mask)) = root.get_mut("mask").and_then(Item::as_value_mut) else {
+            panic!("mask is an integer");
+        };
+        mask.set_radix(Radix::Hex).unwrap();
+        let Some(Value::Integer(size)) = root.get_mut("size").and_then(Item::as_value_mut) else {
+            panic!("size is an integer");
+        };
+        size.set_digit_grouping(3).unwrap();
+        let Some(Value::Float(scale)) = root.get_mut("scale").and_then(Item::as_value_mut) else {
+            panic!("scale is a float");
+        };
+        scale.set_exponent_style(ExponentStyle::Scientific).unwrap();
+        let Some(Value::Integer(offset)) = root.get_mut("offset").and_then(Item::as_value_mut)
+        else {
+            panic!("offset is an integer");
+        };
+        assert!(offset.set_radix(Radix::Octal).is_err());
+    })
+    .produces_display(str![[r#"
+
+    mask = 0xFF # all bits
+    size = 1_000_000
+    scale = 1.5e-4
+    offset = -4
+
+"#]]);
+}
+
+#[test]
+fn test_set_string_style() {
+    given(
+        r#"
+    query = "SELECT *\nFROM users;" # report
+    path = "C:\\Users"
+    quote = "it's""#,
+    )
+    .running(|root| {
+        let Some(Value::String(query)) = root.get_mut("query").and_then(Item::as_value_mut) else {
+            panic!("query is a string");
+        };
+        assert_eq!(query.string_style(), Some(StringStyle::Basic));
+        query.set_string_style(StringStyle::MultilineBasic).unwrap();
+        assert_eq!(query.string_style(), Some(StringStyle::MultilineBasic));
+        let Some(Value::String(path)) = root.get_mut("path").and_then(Item::as_value_mut) else {
+            panic!("path is a string");
+        };
+        path.set_string_style(StringStyle::Literal).unwrap();
+        let Some(Value::String(quote)) = root.get_mut("quote").and_then(Item::as_value_mut) else {
+            panic!("quote is a string");
+        };
+        assert!(quote.set_string_style(StringStyle::Literal).is_err());
+        quote
+            .set_string_style(StringStyle::MultilineLiteral)
+            .unwrap();
+    })
+    .produces_display(str![[r#"
+
+    query = """
+SELECT *
+FROM users;""" # report
+    path = 'C:\Users'
+    quote = '''
+it's'''
+
+"#]]);
+}
+
+macro_rules! as_inline_table {
+    ($entry:ident) => {{
+        assert!($entry.is_value());
+        let a = $entry.as_value_mut().unwrap();
+        assert!(a.is_inline_table());
+        a.as_inline_table_mut().unwrap()
+    }};
+}
+
+#[test]
+fn test_insert_into_inline_table() {
+    given(
+        r#"
+        a = {a=2,  c = 3}
+        b = {}"#,
+    )
+    .running(|root| {
+        {
+            let a = root.get_mut("a").unwrap();
+            let a = as_inline_table!(a);
+            assert_eq!(a.len(), 2);
+            assert!(a.contains_key("a") && a.get("c").is_some() && a.get_mut("c").is_some());
             a.get_or_insert("b", 42);
             assert_eq!(a.len(), 3);
             a.fmt();
@@ -848,6 +1555,28 @@ fn test_insert_into_inline_table() {
 "#]]);
 }
 
+#[test]
+fn test_inline_table_entry() {
+    given(
+        r#"
+        a = {count = 1}"#,
+    )
+    .running(|root| {
+        let a = root.get_mut("a").unwrap();
+        let a = as_inline_table!(a);
+        for key in ["count", "other"] {
+            a.entry(key)
+                .and_modify(|v| *v = (v.as_integer().unwrap() + 1).into())
+                .or_insert(Value::from(0));
+        }
+    })
+    .produces_display(str![[r#"
+
+        a = {count = 2, other = 0 }
+
+"#]]);
+}
+
 #[test]
 fn test_remove_from_inline_table() {
     given(
@@ -935,6 +1664,55 @@ fn test_inline_table_append() {
     assert_eq!(b.len(), 3);
 }
 
+#[test]
+fn test_extend_with_default_decor() {
+    given(
+        r#"
+        [t]
+        a = [1,  2]
+        b = 1"#,
+    )
+    .running(|root| {
+        let other = "x = [ 3 ,4 ]".parse::<DocumentMut>().unwrap();
+        let t = root.get_mut("t").unwrap();
+        let t = as_table!(t);
+        let a = t.get_mut("a").unwrap();
+        let a = as_array!(a);
+        a.extend(other["x"].as_array().unwrap().iter());
+        t.extend([("b", value(2)), ("c", value(3))]);
+    })
+    .produces_display(str![[r#"
+
+        [t]
+        a = [1,  2, 3, 4]
+b = 2
+c = 3
+
+"#]]);
+}
+
+#[test]
+fn test_collect_into_values() {
+    let array = [1, 2, 3].into_iter().collect::<toml_edit::Array>();
+    assert_eq!(array.to_string(), "[1, 2, 3]");
+
+    let table = [("a", 1), ("b", 2)]
+        .into_iter()
+        .collect::<toml_edit::InlineTable>();
+    assert_eq!(table.to_string(), "{ a = 1, b = 2 }");
+
+    given("")
+        .running(|root| {
+            root["array"] = value(array.clone());
+            root["table"] = value(table.clone());
+        })
+        .produces_display(str![[r#"
+array = [1, 2, 3]
+table = { a = 1, b = 2 }
+
+"#]]);
+}
+
 #[test]
 fn test_insert_dotted_into_std_table() {
     given("")
@@ -974,6 +1752,111 @@ src.git = "https://github.com/nixos/nixpkgs"
 "#]]);
 }
 
+#[test]
+fn test_insert_into_interleaved_dotted_keys() {
+    given(
+        r#"
+[package]
+a.b = 1
+c = 2
+a.d = 3"#,
+    )
+    .running(|root| {
+        let package = root["package"].as_table_mut().unwrap();
+        package["a"]["e"] = value(4);
+        package["f"] = value(5);
+    })
+    .produces_display(str![[r#"
+
+[package]
+a.b = 1
+c = 2
+a.d = 3
+a.e = 4
+f = 5
+
+"#]]);
+}
+
+#[test]
+fn test_undot_interleaved_dotted_keys() {
+    given(
+        r#"
+[package]
+a.b = 1
+c = 2
+a.d = 3"#,
+    )
+    .running(|root| {
+        let package = root["package"].as_table_mut().unwrap();
+        package["a"].as_table_mut().unwrap().set_dotted(false);
+    })
+    .produces_display(str![[r#"
+
+[package]
+c = 2
+
+[package.a]
+b = 1
+d = 3
+
+"#]]);
+}
+
+#[test]
+fn test_sort_interleaved_dotted_keys() {
+    given(
+        r#"
+[package]
+a.b = 1
+c = 2
+a.d = 3"#,
+    )
+    .running(|root| {
+        root["package"].as_table_mut().unwrap().sort_values();
+    })
+    .produces_display(str![[r#"
+
+[package]
+a.b = 1
+a.d = 3
+c = 2
+
+"#]]);
+}
+
+#[test]
+fn test_index_array() {
+    given(
+        r#"
+        a = [
+            1, # one
+            "two",
+        ]"#,
+    )
+    .running(|root| {
+        let a = root["a"].as_array_mut().unwrap();
+        assert_eq!(a[1].as_str(), Some("two"));
+        a[0] = Value::from(10);
+        a[1].decor_mut().set_prefix("\n    # two\n    ");
+    })
+    .produces_display(str![[r#"
+
+        a = [10,
+    # two
+    "two",
+        ]
+
+"#]]);
+}
+
+#[test]
+#[should_panic(expected = "index out of bounds: the len is 1 but the index is 1")]
+fn test_index_array_out_of_bounds() {
+    let array = toml_edit::Array::from_iter([1]);
+    let _ = &array[1];
+}
+
 #[test]
 fn sorting_with_references() {
     let values = vec!["foo", "qux", "bar"];
@@ -1026,3 +1909,764 @@ name = "test.swf"
 "#]]
     );
 }
+
+// comments
+
+#[test]
+fn test_table_comments() {
+    let doc = "a = 1\r\n\r\n  # first\r\n  #second  \r\n\r\n  [b]\r\n"
+        .parse::<DocumentMut>()
+        .unwrap();
+    let comments = doc["b"].comments().collect::<Vec<_>>();
+    assert_eq!(comments, ["first", "second"]);
+    assert_eq!(doc["a"].comments().count(), 0);
+}
+
+#[test]
+fn test_set_table_comments() {
+    given(
+        r#"
+        a = 1
+
+        # old
+        # comment
+
+        [b]
+        c = 2
+
+        [d]"#,
+    )
+    .running(|root| {
+        let b = root.get_mut("b").unwrap();
+        as_table!(b).set_comments(["new", "", "comment"]);
+        let d = root.get_mut("d").unwrap();
+        as_table!(d).set_comments(["added"]);
+    })
+    .produces_display(str![[r#"
+
+        a = 1
+
+        # new
+        #
+        # comment
+
+        [b]
+        c = 2
+
+        # added
+        [d]
+
+"#]]);
+}
+
+#[test]
+fn test_set_table_comments_line_endings() {
+    let mut doc = "a = 1\r\n\r\n# old\r\n[b]\r\n"
+        .parse::<DocumentMut>()
+        .unwrap();
+    doc["b"].set_comments(["new"]);
+    let prefix = doc["b"].as_table().unwrap().decor().prefix().unwrap();
+    assert_eq!(prefix.as_str(), Some("\r\n# new\r\n"));
+
+    doc["c"] = table();
+    doc["c"]["d"] = value(2);
+    doc["c"].set_comments(["added"]);
+    assert_data_eq!(
+        doc.to_string(),
+        str!["a = 1\r\n\r\n# new\r\n[b]\r\n\r\n# added\r\n[c]\r\nd = 2\r\n"].raw()
+    );
+}
+
+#[test]
+fn test_trailing_comments() {
+    given(
+        r#"
+        port = 80 # default
+        list = [
+            1, # one
+            2,
+            3
+        ]"#,
+    )
+    .running(|root| {
+        let port = root["port"].as_value_mut().unwrap();
+        assert_eq!(port.trailing_comment(), Some("default"));
+        port.set_trailing_comment(None);
+
+        let list = root["list"].as_array_mut().unwrap();
+        assert_eq!(list.trailing_comment(0), Some("one"));
+        assert_eq!(list.trailing_comment(2), None);
+        list.set_trailing_comment(0, None);
+        list.set_trailing_comment(1, Some("two"));
+        list.set_trailing_comment(2, Some("three"));
+        assert_eq!(list.trailing_comment(1), Some("two"));
+        assert_eq!(list.trailing_comment(2), Some("three"));
+    })
+    .produces_display(str![[r#"
+
+        port = 80
+        list = [
+            1,
+            2, # two
+            3 # three
+        ]
+
+"#]]);
+}
+
+#[test]
+fn test_decor_from_trivia() {
+    assert!(RawString::from_whitespace(" \t\r\n").is_ok());
+    assert!(RawString::from_whitespace("#").is_err());
+    assert!(RawString::from_whitespace("\r").is_err());
+    assert!(RawString::from_comment("\n# one\r\n  # two\n").is_ok());
+    assert!(RawString::from_comment("# bell \u{7}").is_err());
+    assert!(RawString::from_comment("x = 1").is_err());
+
+    given(
+        r#"
+        a = 1
+        b = 2"#,
+    )
+    .running(|root| {
+        let mut b = root.key_mut("b").unwrap();
+        b.leaf_decor_mut()
+            .set_prefix(RawString::from_comment("# about b\n").unwrap());
+        let a = root.get_mut("a").unwrap().as_value_mut().unwrap();
+        a.decor_mut()
+            .set_suffix(RawString::from_comment(" # about a").unwrap());
+    })
+    .produces_display(str![[r#"
+
+        a = 1 # about a
+# about b
+b = 2
+
+"#]]);
+}
+
+#[test]
+fn test_dotted_key_decor() {
+    given(
+        r#"
+        [x]
+        a.b.c = 1"#,
+    )
+    .running(|root| {
+        let x = root.get_mut("x").unwrap().as_table_mut().unwrap();
+        x.decor_mut().set_suffix(" # x");
+        x.key_mut("a").unwrap().dotted_decor_mut().set_suffix(" ");
+        let a = x.get_mut("a").unwrap().as_table_mut().unwrap();
+        let mut b = a.key_mut("b").unwrap();
+        b.dotted_decor_mut().set_prefix(" ");
+        b.dotted_decor_mut().set_suffix(" ");
+        let b = a.get_mut("b").unwrap().as_table_mut().unwrap();
+        let mut c = b.key_mut("c").unwrap();
+        c.dotted_decor_mut().set_prefix(" ");
+        c.leaf_decor_mut().set_prefix("# about c\n");
+    })
+    .produces_display(str![[r#"
+
+        [x] # x
+# about c
+a . b . c = 1
+
+"#]]);
+}
+
+// merge
+
+#[test]
+fn test_merge() {
+    let user = r#"
+[package]
+name = "user" # overridden
+edition = "2021"
+
+[[bin]]
+name = "extra"
+
+[profile.release]
+lto = true
+"#
+    .parse::<DocumentMut>()
+    .unwrap();
+    given(
+        r#"
+# Defaults
+[package]
+# The name
+name = "default"
+version = "1.0"
+inline = { a = 1, b = { c = 2 } }
+
+[[bin]]
+name = "main"
+
+[dependencies]
+serde = "1"
+"#,
+    )
+    .running_on_doc(|doc| {
+        doc.merge(&user);
+        let inline = r#"{ b = { d = 3 }, e = 4 }"#.parse::<Value>().unwrap();
+        let mut other = DocumentMut::new();
+        other["package"] = table();
+        other["package"]["inline"] = value(inline);
+        doc.merge(&other);
+    })
+    .produces_display(str![[r#"
+
+# Defaults
+[package]
+# The name
+name = "user" # overridden
+version = "1.0"
+inline = { a = 1, b = { c = 2 , d = 3 } , e = 4 }
+edition = "2021"
+
+[[bin]]
+name = "main"
+
+[dependencies]
+serde = "1"
+
+[[bin]]
+name = "extra"
+
+[profile.release]
+lto = true
+
+"#]]);
+}
+
+// path
+
+#[test]
+fn test_get_path_mut() {
+    given(
+        r#"
+        [a]
+        b = { c = 1 }
+        d = [1]"#,
+    )
+    .running(|root| {
+        assert!(root.contains_path(&["a", "b", "c"]));
+        assert!(!root.contains_path(&["a", "d", "0"]));
+        assert!(root.get_path_mut(&[]).is_none());
+        *root.get_path_mut(&["a", "b", "c"]).unwrap() = value(2);
+    })
+    .produces_display(str![[r#"
+
+        [a]
+        b = { c = 2 }
+        d = [1]
+
+"#]]);
+}
+
+#[test]
+fn test_get_mut_ignore_case() {
+    given(
+        r#"
+        [Package]
+        Name = "a"
+        name = "b"
+        old = 1"#,
+    )
+    .running(|root| {
+        assert!(root.get_mut_ignore_case("package.name").is_none());
+        let package = root.get_mut_ignore_case("PACKAGE").unwrap();
+        let package = as_table!(package);
+        assert_eq!(package.keys_ignore_case("NAME").len(), 2);
+        assert_eq!(package.keys_ignore_case("name ").len(), 0);
+        *package.get_mut_ignore_case("NAME").unwrap() = value("c");
+        *package.get_mut_ignore_case("name").unwrap() = value("d");
+        package.remove("old");
+        assert!(package.get_mut_ignore_case("Old").is_none());
+    })
+    .produces_display(str![[r#"
+
+        [Package]
+        Name = "c"
+        name = "d"
+
+"#]]);
+}
+
+#[test]
+fn test_get_or_insert_table() {
+    given(
+        r#"
+        [tool]
+        name = "x"
+        [tool.lint]
+        level = 1
+        list = []"#,
+    )
+    .running(|root| {
+        let table = root.get_or_insert_table(&["tool", "lint"]).unwrap();
+        table["extra"] = value(true);
+        let table = root.get_or_insert_table(&["tool", "fmt", "rules"]).unwrap();
+        table["width"] = value(80);
+        root.get_or_insert_table(&["empty"]).unwrap();
+        assert!(root.get_or_insert_table(&["tool", "name"]).is_none());
+        assert!(root
+            .get_or_insert_table(&["tool", "lint", "list", "x"])
+            .is_none());
+    })
+    .produces_display(str![[r#"
+
+        [tool]
+        name = "x"
+        [tool.lint]
+        level = 1
+        list = []
+extra = true
+
+[tool.fmt.rules]
+width = 80
+
+[empty]
+
+"#]]);
+}
+
+#[test]
+fn test_entry_path() {
+    given(
+        r#"
+        [tool]
+        name = "x"
+        [tool.lint]
+        level = 1"#,
+    )
+    .running(|root| {
+        root.entry_path(&["tool", "lint", "level"])
+            .unwrap()
+            .or_insert(value(2));
+        root.entry_path(&["tool", "lint", "extra"])
+            .unwrap()
+            .or_insert(value(true));
+        root.entry_path(&["tool", "fmt", "rules", "width"])
+            .unwrap()
+            .or_insert(value(80));
+        assert!(root.entry_path(&[]).is_none());
+        assert!(root.entry_path(&["tool", "name", "x"]).is_none());
+    })
+    .produces_display(str![[r#"
+
+        [tool]
+        name = "x"
+        [tool.lint]
+        level = 1
+extra = true
+
+[tool.fmt.rules]
+width = 80
+
+"#]]);
+}
+
+#[test]
+fn test_inline_small_arrays_of_tables() {
+    given(
+        r#"
+[[bin]]
+name = "a"
+
+[[bin.test]]
+path = "t.rs"
+
+[[bin]]
+name = "b"
+
+[[example]]
+name = "a-much-longer-name-than-fits"
+
+[package]
+[[package.author]]
+name = "x"
+"#,
+    )
+    .running_on_doc(|doc| doc.inline_small_arrays_of_tables(1, 30))
+    .produces_display(str![[r#"
+
+[[bin]]
+name = "a"
+test = [{ path = "t.rs" }]
+
+[[bin]]
+name = "b"
+
+[[example]]
+name = "a-much-longer-name-than-fits"
+
+[package]
+author = [{ name = "x" }]
+
+"#]]);
+}
+
+#[test]
+fn test_explode_inline_arrays_of_tables() {
+    given(
+        r#"
+bin = [{ name = "a", test = [{ path = "t.rs" }] }, { name = "b" }]
+mixed = [1, { a = 1 }]
+empty = []
+
+[package]
+author = [{ name = "x" }]
+inline = { nested = [{ a = 1 }] }
+"#,
+    )
+    .running_on_doc(|doc| doc.explode_inline_arrays_of_tables())
+    .produces_display(str![[r#"
+mixed = [1, { a = 1 }]
+empty = []
+
+[[bin]]
+name = "a"
+
+[[bin.test]]
+path = "t.rs"
+
+[[bin]]
+name = "b"
+
+[package]
+inline = { nested = [{ a = 1 }] }
+
+[[package.author]]
+name = "x"
+
+"#]]);
+}
+
+#[test]
+fn test_set_formatted_value() {
+    given(
+        r#"
+# the port
+port =   8080   # default
+debug = true
+"#,
+    )
+    .running(|root| {
+        let port = root["port"].as_value_mut().unwrap();
+        let port = port.as_integer_mut().unwrap();
+        port.set_value(9090);
+        assert_eq!(
+            port.decor().suffix().and_then(|s| s.as_str()),
+            Some("   # default")
+        );
+        let debug = root["debug"].as_value_mut().unwrap();
+        debug.as_bool_mut().unwrap().set_value(false);
+        assert!(root["debug"]
+            .as_value_mut()
+            .unwrap()
+            .as_string_mut()
+            .is_none());
+    })
+    .produces_display(str![[r#"
+
+# the port
+port =   9090   # default
+debug = false
+
+"#]]);
+}
+
+#[test]
+fn test_separate_tables() {
+    given(
+        r#"name = "demo"
+[a]
+
+# about b
+
+[b]
+x = 1
+[[c]]
+[[c]]
+[d.e]"#,
+    )
+    .running_on_doc(|doc| {
+        doc["f"] = table();
+        doc["f"]["g"] = table();
+        doc["f"]["g"]["y"] = value(2);
+        doc.separate_tables(2);
+        doc.separate_tables(2);
+    })
+    .produces_display(str![[r#"
+name = "demo"
+
+
+[a]
+
+
+# about b
+
+[b]
+x = 1
+
+
+[[c]]
+
+
+[[c]]
+
+
+[d.e]
+
+
+[f]
+
+
+[f.g]
+y = 2
+
+"#]]);
+}
+
+#[test]
+fn test_table_trailing() {
+    given(
+        r#"root = 1
+[a]
+x = 1
+[[b]]
+[[b]]
+y = 2
+[c.d]
+[e]"#,
+    )
+    .running_on_doc(|doc| {
+        doc.as_table_mut().set_trailing("\n");
+        doc["a"]
+            .as_table_mut()
+            .unwrap()
+            .set_trailing("# end of a\n\n");
+        let b = doc["b"].as_array_of_tables_mut().unwrap();
+        b.get_mut(0).unwrap().set_trailing("\n");
+        // Hidden
+        doc["c"].as_table_mut().unwrap().set_trailing("# c\n");
+        doc["e"].as_table_mut().unwrap().set_trailing("\n");
+    })
+    .produces_display(str![[r#"
+root = 1
+
+[a]
+x = 1
+# end of a
+
+[[b]]
+
+[[b]]
+y = 2
+[c.d]
+[e]
+
+
+"#]]);
+}
+
+#[test]
+fn test_normalize_keys() {
+    given(
+        r#"a.b = 1 # dotted
+"it's" = [{ c = 2 }]
+[[d]]
+  e   = 3
+[f."g h"]"#,
+    )
+    .running_on_doc(|doc| {
+        let invalid = doc.normalize_keys(toml_edit::KeyStyle::LiteralQuoted);
+        assert_eq!(
+            invalid,
+            [vec![toml_edit::PathSegment::Key(Key::new("it's"))]]
+        );
+    })
+    .produces_display(str![[r#"
+'a'.'b' = 1 # dotted
+"it's" = [{ 'c' = 2 }]
+[['d']]
+  'e'   = 3
+['f'.'g h']
+
+"#]]);
+}
+
+#[test]
+fn test_edit_sibling_keeps_repr() {
+    given(
+        r#"a = 0xff
+b = 1
+c = [1e3, 0o7, 'x', { d = 1_000 }]
+[t]
+e = 1979-05-27T07:32:00.5Z"#,
+    )
+    .running_on_doc(|doc| {
+        doc["b"] = value(2);
+        doc["t"]["f"] = value(3.0);
+        doc.as_table_mut().sort_values();
+        doc.as_table_mut().fmt();
+        let mut pinned = Value::from(0.1 + 0.2);
+        pinned.pin_repr();
+        doc["g"] = Item::Value(pinned);
+    })
+    .produces_display(str![[r#"
+a = 0xff
+b = 2
+c = [1e3, 0o7, 'x', { d = 1_000 }]
+g = 0.30000000000000004
+[t]
+e = 1979-05-27T07:32:00.5Z
+f = 3.0
+
+"#]]);
+}
+
+#[test]
+fn test_edit_scalars_in_place() {
+    given(
+        r#"
+        int = 1 # one
+        float = 1.5
+        bool = false
+        string = 'single'
+        date = 1979-05-27"#,
+    )
+    .running(|root| {
+        root["int"].as_integer_mut().unwrap().set_value(2);
+        root["float"].as_float_mut().unwrap().set_value(2.5);
+        root["bool"].as_bool_mut().unwrap().set_value(true);
+        root["string"]
+            .as_string_mut()
+            .unwrap()
+            .set_value("double".to_owned());
+        let date = "2024-02-29".parse().unwrap();
+        root["date"].as_datetime_mut().unwrap().set_value(date);
+        assert!(root["int"].as_float_mut().is_none());
+    })
+    .produces_display(str![[r#"
+
+        int = 2 # one
+        float = 2.5
+        bool = true
+        string = "double"
+        date = 2024-02-29
+
+"#]]);
+}
+
+#[test]
+fn test_remove_empty_tables() {
+    given(
+        r#"name = "demo" # the name
+
+# about a
+[a]
+[a.b]
+[a.c]
+x = 1 # x
+
+[d]
+z = 3
+# kept for later
+
+[[e]]
+[[e]]
+y = 2
+[f.g]
+"#,
+    )
+    .running_on_doc(|doc| {
+        doc["d"].as_table_mut().unwrap().remove("z");
+        doc["h"] = table();
+        doc["h"]["i"] = table();
+        doc.remove_empty_tables();
+        doc.remove_empty_tables();
+    })
+    .produces_display(str![[r#"
+name = "demo" # the name
+
+# about a
+[a]
+[a.c]
+x = 1 # x
+
+[d]
+# kept for later
+
+[[e]]
+[[e]]
+y = 2
+
+"#]]);
+}
+
+#[test]
+fn test_remove_empty_tables_with() {
+    given(
+        r#"[a]
+[b.c]
+[[d]]
+[[d]]
+y = 2
+[[e]]
+[f.g.h]
+"#,
+    )
+    .running_on_doc(|doc| {
+        let options = EmptyTableOptions::new()
+            .keep_explicit(true)
+            .prune_arrays_of_tables(true);
+        doc.remove_empty_tables_with(&options);
+        doc.remove_empty_tables_with(&options);
+    })
+    .produces_display(str![[r#"
+[a]
+[b.c]
+[[d]]
+y = 2
+[f.g.h]
+
+"#]]);
+}
+
+// validate
+
+#[test]
+fn test_validate() {
+    let mut doc = r#"
+root = { a = 1 }
+[[bin]]
+name = "first"
+[[bin]]
+name = "second"
+"#
+    .parse::<DocumentMut>()
+    .unwrap();
+    assert!(doc.validate().is_ok());
+
+    doc["root"]["sub"] = table();
+    doc["bin"][0] = value(1);
+    doc["bin"][1].as_table_mut().unwrap().set_dotted(true);
+    let errors = doc
+        .validate()
+        .unwrap_err()
+        .iter()
+        .map(|err| format!("{err}\n"))
+        .collect::<String>();
+    assert_data_eq!(
+        errors,
+        str![[r#"
+inline table contains a standard table at `root.sub`
+array of tables contains a non-table at `bin[0]`
+table requiring a header is marked as dotted at `bin[1]`
+
+"#]]
+    );
+}
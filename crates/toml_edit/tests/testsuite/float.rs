@@ -58,3 +58,40 @@ fn test_float() {
     float_inf_tests!(f32);
     float_inf_tests!(f64);
 }
+
+#[test]
+fn test_special_float_roundtrip() {
+    let raw = "a = inf\nb = +inf\nc = -inf\nd = nan\ne = +nan\nf = -nan\ng = 1.0\n";
+    let mut document = raw.parse::<DocumentMut>().unwrap();
+    assert_eq!(document.to_string(), raw);
+    assert!(document["b"].as_value().unwrap().is_infinite());
+    assert!(document["f"].as_value().unwrap().is_nan());
+    assert!(!document["g"].as_value().unwrap().is_nan());
+
+    let specials = document
+        .iter()
+        .map(|(_, item)| match item.as_value() {
+            Some(toml_edit::Value::Float(f)) => f.special_float(),
+            _ => unreachable!(),
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        specials,
+        [
+            Some(toml_edit::SpecialFloat::Inf),
+            Some(toml_edit::SpecialFloat::PosInf),
+            Some(toml_edit::SpecialFloat::NegInf),
+            Some(toml_edit::SpecialFloat::Nan),
+            Some(toml_edit::SpecialFloat::PosNan),
+            Some(toml_edit::SpecialFloat::NegNan),
+            None,
+        ]
+    );
+
+    if let Some(toml_edit::Value::Float(g)) = document["g"].as_value_mut() {
+        g.set_special_float(toml_edit::SpecialFloat::NegNan);
+    }
+    let g = document["g"].as_float().unwrap();
+    assert!(g.is_nan() && g.is_sign_negative());
+    assert!(document.to_string().ends_with("g = -nan\n"));
+}
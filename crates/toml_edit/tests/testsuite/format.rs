@@ -0,0 +1,383 @@
+use snapbox::assert_data_eq;
+use snapbox::prelude::*;
+use snapbox::str;
+
+use toml_edit::{
+    value, DocumentBuilder, DocumentMut, ImDocument, InlineTableWrap, KeyStyle, Profile,
+    ToStringOptions, TomlSpec,
+};
+
+const INPUT: &str = r#"# header
+"name" = "demo"
+deps = { 'serde' = { version = "1.0" }, log = "0.4" }
+list = ["one", "two", "three"]
+nested = [
+  # first
+  [1, 2],
+   3
+]
+servers = [{ "host" = "a" }, { host = "b" }]
+"#;
+
+#[test]
+fn default_matches_to_string() {
+    let doc = INPUT.parse::<DocumentMut>().unwrap();
+    assert_eq!(doc.to_string_with(&ToStringOptions::new()), doc.to_string());
+}
+
+#[test]
+fn all_options() {
+    let doc = INPUT.parse::<DocumentMut>().unwrap();
+    let options = ToStringOptions::new()
+        .indent(2)
+        .standard_tables(true)
+        .max_array_width(16)
+        .key_style(KeyStyle::Bare);
+    let actual = doc.to_string_with(&options);
+    actual.parse::<DocumentMut>().unwrap();
+    assert_data_eq!(
+        actual,
+        str![[r##"
+# header
+name = "demo"
+list = [
+  "one",
+  "two",
+  "three",
+]
+nested = [
+  # first
+  [1, 2],
+  3
+]
+
+[deps]
+log = "0.4"
+
+[deps.serde]
+version = "1.0"
+
+[[servers]]
+host = "a"
+
+[[servers]]
+host = "b"
+
+"##]]
+        .raw()
+    );
+}
+
+#[test]
+fn quoted_keys() {
+    let doc = INPUT.parse::<DocumentMut>().unwrap();
+    let options = ToStringOptions::new().key_style(KeyStyle::Quoted);
+    let actual = doc.to_string_with(&options);
+    assert_data_eq!(
+        actual,
+        str![[r##"
+# header
+"name" = "demo"
+"deps" = { "serde" = { "version" = "1.0" }, "log" = "0.4" }
+"list" = ["one", "two", "three"]
+"nested" = [
+  # first
+  [1, 2],
+   3
+]
+"servers" = [{ "host" = "a" }, { "host" = "b" }]
+
+"##]]
+        .raw()
+    );
+}
+
+#[test]
+fn normalize_whitespace() {
+    let input = r#"# header
+
+
+a=1
+  b . c   =   { d=1,e = [1 ,2]  }   # inline
+
+
+
+   # about the table
+  [ t . u ]   # header comment
+x="x"
+list = [
+        1,   # one
+
+
+   # two
+ 2 ,
+        [ 3,4 ],
+]
+[[ bins ]]
+name  =  "a"
+"#;
+    let doc = input.parse::<DocumentMut>().unwrap();
+    let options = ToStringOptions::new()
+        .normalize_whitespace(true)
+        .max_blank_lines(1);
+    let actual = doc.to_string_with(&options);
+    assert_eq!(
+        actual.parse::<DocumentMut>().unwrap().to_string(),
+        actual,
+        "normalized output should be stable"
+    );
+    assert_data_eq!(
+        actual,
+        str![[r##"
+# header
+
+a = 1
+b.c = { d = 1, e = [1, 2] } # inline
+
+# about the table
+[t.u] # header comment
+x = "x"
+list = [
+    1, # one
+
+    # two
+    2,
+    [3, 4],
+]
+[[bins]]
+name = "a"
+
+"##]]
+        .raw()
+    );
+}
+
+#[test]
+fn max_blank_lines() {
+    let input = "a = 1\n\n\n\n  b = 2\n\n\n[t]\nc = [\n  1,\n\n\n  2,\n]\n\n\n";
+    let mut doc = input.parse::<DocumentMut>().unwrap();
+    doc.reformat(&ToStringOptions::new().max_blank_lines(0));
+    assert_data_eq!(
+        doc.to_string(),
+        str![[r#"
+a = 1
+  b = 2
+[t]
+c = [
+  1,
+  2,
+]
+
+"#]]
+        .raw()
+    );
+}
+
+#[test]
+fn compact() {
+    let input = r#"# header
+title = "demo" # trailing
+
+[owner]
+"name" = 'Tom'
+dob.year = 1979
+bio = """
+multi
+line"""
+
+[a.b.c]
+list = [
+    1, # one
+    # two
+    2,
+]
+
+[[a.bins]]
+name = "x"
+[[a.bins]]
+nested = { 'key with space' = [ { x = 1 } ] }
+"#;
+    let doc = input.parse::<DocumentMut>().unwrap();
+    let actual = doc.to_string_compact();
+    assert_eq!(
+        actual.parse::<DocumentMut>().unwrap().to_string(),
+        actual,
+        "compact output should be stable"
+    );
+    assert_data_eq!(
+        actual,
+        str![[r#"
+title="demo"
+owner={name='Tom',dob.year=1979,bio="""
+multi
+line"""}
+a={b={c={list=[1,2]}},bins=[{name="x"},{nested={"key with space"=[{x=1}]}}]}
+
+"#]]
+        .raw()
+    );
+}
+
+#[test]
+fn max_inline_table_width() {
+    let doc = INPUT.parse::<DocumentMut>().unwrap();
+    let options = ToStringOptions::new().max_inline_table_width(20);
+    let actual = doc.to_string_with(&options);
+    actual.parse::<DocumentMut>().unwrap();
+    assert_data_eq!(
+        actual,
+        str![[r##"
+# header
+"name" = "demo"
+list = ["one", "two", "three"]
+nested = [
+  # first
+  [1, 2],
+   3
+]
+servers = [{ "host" = "a" }, { host = "b" }]
+
+[deps]
+'serde' = { version = "1.0" }
+log = "0.4"
+
+"##]]
+        .raw()
+    );
+
+    let options = options
+        .inline_table_wrap(InlineTableWrap::Multiline)
+        .indent(2);
+    let actual = doc.to_string_with(&options);
+    let wrapped = ImDocument::parse_with_spec(actual.as_str(), TomlSpec::V1_1)
+        .unwrap()
+        .into_mut();
+    assert_data_eq!(
+        actual,
+        str![[r##"
+# header
+"name" = "demo"
+deps = {
+  'serde' = { version = "1.0" },
+  log = "0.4",
+}
+list = ["one", "two", "three"]
+nested = [
+  # first
+  [1, 2],
+  3
+]
+servers = [{ "host" = "a" }, { host = "b" }]
+
+"##]]
+        .raw()
+    );
+
+    let options = ToStringOptions::new().normalize_whitespace(true).indent(4);
+    assert_data_eq!(
+        wrapped.to_string_with(&options),
+        str![[r##"
+# header
+"name" = "demo"
+deps = {
+    'serde' = { version = "1.0" },
+    log = "0.4",
+}
+list = ["one", "two", "three"]
+nested = [
+    # first
+    [1, 2],
+    3
+]
+servers = [{ "host" = "a" }, { host = "b" }]
+
+"##]]
+        .raw()
+    );
+}
+
+#[test]
+fn profile() {
+    let mut expanded = DocumentBuilder::new().profile(Profile::Expanded).build();
+    expanded.insert("name", value("demo"));
+    let mut serde = toml_edit::InlineTable::new();
+    serde.insert("version", "1.0".into());
+    let mut deps = toml_edit::Table::new();
+    deps.insert("serde", value(serde));
+    expanded.insert("deps", toml_edit::Item::Table(deps));
+    // Inserted tables lay out what is later inserted into them
+    let deps = expanded["deps"].as_table_mut().unwrap();
+    deps.entry("log").or_insert(value("0.4"));
+    expanded.insert(
+        "list",
+        value(toml_edit::Array::from_iter([
+            "one", "two", "three", "four", "five", "six",
+        ])),
+    );
+    assert_data_eq!(
+        expanded.to_string(),
+        str![[r#"
+name = "demo"
+list = [
+    "one",
+    "two",
+    "three",
+    "four",
+    "five",
+    "six",
+]
+
+[deps]
+log = "0.4"
+
+[deps.serde]
+version = "1.0"
+
+"#]]
+        .raw()
+    );
+
+    let options = ToStringOptions::new()
+        .key_style(KeyStyle::BasicQuoted)
+        .max_array_width(4)
+        .indent(2);
+    let mut custom = DocumentBuilder::new()
+        .profile(Profile::Custom(options))
+        .build();
+    custom.insert("name", value("demo"));
+    custom.insert("wrapped", value(toml_edit::Array::from_iter([1, 2])));
+    // Indexing lays out the key, but not the value assigned to it
+    custom["list"] = value(toml_edit::Array::from_iter([1, 2]));
+    assert_data_eq!(
+        custom.to_string(),
+        str![[r#"
+"name" = "demo"
+"wrapped" = [
+  1,
+  2,
+]
+"list" = [1, 2]
+
+"#]]
+        .raw()
+    );
+
+    // What was laid out is kept, but what is inserted next isn't
+    custom.set_profile(None);
+    assert!(custom.profile().is_none());
+    custom.insert("other", value(1));
+    assert_data_eq!(
+        custom.to_string(),
+        str![[r#"
+"name" = "demo"
+"wrapped" = [
+  1,
+  2,
+]
+"list" = [1, 2]
+other = 1
+
+"#]]
+        .raw()
+    );
+}
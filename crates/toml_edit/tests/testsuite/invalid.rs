@@ -300,3 +300,213 @@ fn fuzzed_68144_error_span() {
     let actual = &input[err.span().unwrap()];
     assert_eq!(actual, "ᾂ");
 }
+
+#[test]
+fn suggestions() {
+    let suggest = |input: &str| {
+        let err = input.parse::<toml_edit::DocumentMut>().unwrap_err();
+        err.suggestions().to_vec()
+    };
+    assert_data_eq!(
+        suggest("my key = 1").join("\n"),
+        str![[r#"did you mean to quote this key? e.g. `"my key" = 1`"#]]
+    );
+    assert_data_eq!(
+        suggest("a = [1 = 2]").join("\n"),
+        str!["arrays use commas to separate values, not `=`"]
+    );
+    assert_data_eq!(
+        suggest("name = hello").join("\n"),
+        str![[r#"did you mean to quote this string? e.g. `key = "value"`"#]]
+    );
+    assert_data_eq!(
+        suggest("name = \"hello\na = 1").join("\n"),
+        str!["did you forget a closing quote?"]
+    );
+    assert_data_eq!(
+        suggest("[my table]").join("\n"),
+        str![[r#"keys with spaces must be quoted, e.g. `["my table"]`"#]]
+    );
+    assert_data_eq!(
+        suggest("asdf").join("\n"),
+        str!["key/value pairs need an `=` between the key and the value"]
+    );
+    assert!(suggest("a = 1\na = 2").is_empty());
+}
+
+#[test]
+fn parse_recover() {
+    let input = r#"a = 1
+b =
+[t]
+c = [
+  1,
+  2 x
+]
+[t]
+d = 2
+[u]
+e = "e"
+"#;
+    let (doc, errors) = toml_edit::ImDocument::parse_recover(input);
+    let errors = errors.iter().map(ToString::to_string).collect::<String>();
+    assert_data_eq!(
+        errors,
+        str![[r#"
+TOML parse error at line 2, column 4
+  |
+2 | b =
+  |    ^
+invalid string
+expected `"`, `'`
+TOML parse error at line 6, column 5
+  |
+6 |   2 x
+  |     ^
+invalid array
+expected `]`
+TOML parse error at line 7, column 1
+  |
+7 | ]
+  | ^
+invalid key
+TOML parse error at line 8, column 1
+  |
+8 | [t]
+  | ^
+invalid table header
+duplicate key `t` in document root
+
+"#]]
+        .raw()
+    );
+    assert_data_eq!(
+        doc.into_mut().to_string(),
+        str![[r#"
+a = 1
+[t]
+[u]
+e = "e"
+
+"#]]
+        .raw()
+    );
+}
+
+#[test]
+fn line_col_and_pretty() {
+    let source = "name = \"ünïcode\" x\n";
+    let err = source.parse::<toml_edit::DocumentMut>().unwrap_err();
+    assert_eq!(err.line_col(), Some((1, 18)));
+    assert_eq!(err.to_string_pretty(source), err.to_string());
+    assert_data_eq!(
+        err.to_string_pretty("a = 1"),
+        str![[r#"
+expected newline, `#`
+
+"#]]
+        .raw()
+    );
+}
+
+#[test]
+fn error_kind() {
+    use toml_edit::ErrorKind;
+
+    let kind = |input: &str| {
+        let err = input.parse::<toml_edit::DocumentMut>().unwrap_err();
+        err.kind().clone()
+    };
+    assert_eq!(
+        kind("[a]\nb = 1\nb = 2"),
+        ErrorKind::DuplicateKey {
+            key: "b".to_owned(),
+            table: Some(vec!["a".to_owned()]),
+        }
+    );
+    assert_eq!(
+        kind("a.b = 1\na.b.c = 2"),
+        ErrorKind::DottedKeyExtendWrongType {
+            key: vec!["a".to_owned(), "b".to_owned()],
+            actual: "integer",
+        }
+    );
+    assert_eq!(
+        kind(r#"a = "\q""#),
+        ErrorKind::InvalidEscape { found: Some('q') }
+    );
+    assert_eq!(
+        kind(r#"a = "\uD800""#),
+        ErrorKind::InvalidEscape { found: Some('u') }
+    );
+    assert_eq!(
+        kind(r#"a = "\é""#),
+        ErrorKind::InvalidEscape { found: Some('é') }
+    );
+    assert_eq!(kind("a = \"abc\nb = 1"), ErrorKind::UnterminatedString);
+    assert_eq!(kind("a = '''abc"), ErrorKind::UnterminatedString);
+    assert_eq!(kind("a = \"a\u{1}\""), ErrorKind::Other);
+    assert_eq!(kind("a = 1979-13-01"), ErrorKind::InvalidDatetime);
+    assert_eq!(kind("a = 07:61:00"), ErrorKind::InvalidDatetime);
+    assert_eq!(kind("a = 0x"), ErrorKind::InvalidNumber);
+    assert_eq!(
+        kind("a = 99999999999999999999"),
+        ErrorKind::IntegerOutOfRange {
+            literal: "99999999999999999999".to_owned(),
+        }
+    );
+    assert_eq!(kind("a 1"), ErrorKind::ExpectedEquals);
+    assert_eq!(kind("a = ?"), ErrorKind::Other);
+
+    // An explicit limit, as the default one is lifted by the `unbounded` feature
+    let options = toml_edit::ParseOptions::new().limits(toml_edit::Limits::new().max_depth(3));
+    let err = toml_edit::ImDocument::parse_with_options("a = [[[[1]]]]", options).unwrap_err();
+    assert_eq!(err.kind(), &ErrorKind::RecursionLimitExceeded);
+
+    let err = "a = { b = 1, }"
+        .parse::<toml_edit::DocumentMut>()
+        .unwrap_err();
+    assert_eq!(
+        err.kind(),
+        &ErrorKind::UnsupportedFeature {
+            feature: "a trailing comma in an inline table",
+            spec: toml_edit::TomlSpec::V1_1,
+        }
+    );
+}
+
+#[test]
+fn invalid_utf8() {
+    let bytes = b"a = 1\nb = \"\xC3\x28\"\n";
+    let err = toml_edit::ImDocument::parse_bytes(bytes).unwrap_err();
+    assert_eq!(err.kind(), &toml_edit::ErrorKind::InvalidUtf8);
+    assert_eq!(err.span(), Some(11..12));
+    assert_data_eq!(
+        err.to_string(),
+        str![[r#"
+TOML parse error at line 2, column 6
+  |
+2 | b = "�("
+  |      ^
+invalid UTF-8 sequence `\xC3`
+
+"#]]
+        .raw()
+    );
+
+    let bytes = b"a = \"\xE2\x82";
+    let err = toml_edit::ImDocument::parse_bytes(bytes).unwrap_err();
+    assert_eq!(err.span(), Some(5..7));
+    assert_data_eq!(
+        err.to_string(),
+        str![[r#"
+TOML parse error at line 1, column 6
+  |
+1 | a = "�
+  |      ^^
+incomplete UTF-8 sequence `\xE2\x82` at the end of the document
+
+"#]]
+        .raw()
+    );
+}
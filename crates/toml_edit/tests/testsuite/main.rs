@@ -2,8 +2,10 @@
 
 mod convert;
 mod datetime;
+mod diff;
 mod edit;
 mod float;
+mod format;
 mod invalid;
 mod parse;
 mod stackoverflow;
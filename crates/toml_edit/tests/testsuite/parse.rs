@@ -1,7 +1,7 @@
 use snapbox::assert_data_eq;
 use snapbox::prelude::*;
 use snapbox::str;
-use toml_edit::{DocumentMut, Key, Value};
+use toml_edit::{DocumentMut, ImDocument, InterpolateError, Key, Value};
 
 macro_rules! parse {
     ($s:expr, $ty:ty) => {{
@@ -40,6 +40,31 @@ fn test_key_from_str() {
     );
 }
 
+#[test]
+fn test_join_dotted_key() {
+    for dotted in ["a", "a.b.c", "a . b", "'a.b'.\"c d\"", "1.2"] {
+        let keys = Key::parse(dotted).unwrap();
+        assert_eq!(Key::join_dotted(&keys), dotted);
+    }
+    let keys = Key::parse(" a.b ").unwrap();
+    assert_eq!(Key::join_dotted(&keys), "a.b");
+    assert_eq!(Key::join_dotted(&[]), "");
+}
+
+#[test]
+fn test_dotted_key_segments() {
+    let keys = Key::parse("a . 'b.c'.\"d e\"").unwrap();
+    assert_eq!(
+        Key::segments(&keys).collect::<Vec<_>>(),
+        ["a", "b.c", "d e"]
+    );
+
+    let keys = Key::from_dotted(Key::segments(&keys)).unwrap();
+    assert_eq!(Key::join_dotted(&keys), "a.\"b.c\".\"d e\"");
+    assert_eq!(Key::parse(&Key::join_dotted(&keys)).unwrap(), keys);
+    assert!(Key::from_dotted(Vec::<String>::new()).unwrap().is_empty());
+}
+
 #[test]
 fn test_value_from_str() {
     assert!(parse_value!("1979-05-27T00:32:00.999999-07:00").is_datetime());
@@ -505,8 +530,19 @@ expected newline, `#`
 TOML parse error at line 1, column 5
   |
 1 | a = 9223372036854775808
-  |     ^
-number too large to fit in target type
+  |     ^^^^^^^^^^^^^^^^^^^
+integer `9223372036854775808` is out of range for a 64-bit signed integer
+
+"#]]
+    );
+    bad!(
+        "a = 0x8000000000000000",
+        str![[r#"
+TOML parse error at line 1, column 5
+  |
+1 | a = 0x8000000000000000
+  |     ^^^^^^^^^^^^^^^^^^
+integer `0x8000000000000000` is out of range for a 64-bit signed integer
 
 "#]]
     );
@@ -516,8 +552,8 @@ number too large to fit in target type
 TOML parse error at line 1, column 5
   |
 1 | a = -9223372036854775809
-  |     ^
-number too small to fit in target type
+  |     ^^^^^^^^^^^^^^^^^^^^
+integer `-9223372036854775809` is out of range for a 64-bit signed integer
 
 "#]]
     );
@@ -993,8 +1029,7 @@ TOML parse error at line 1, column 9
   |
 1 | a = {a=1,}
   |         ^
-invalid inline table
-expected `}`
+a trailing comma in an inline table requires TOML 1.1
 
 "#]]
     );
@@ -1028,8 +1063,7 @@ TOML parse error at line 1, column 6
   |
 1 | a = {
   |      ^
-invalid inline table
-expected `}`
+a newline or comment in an inline table requires TOML 1.1
 
 "#]]
     );
@@ -1605,6 +1639,26 @@ clippy.exhaustive_enums = "warn"
     assert_data_eq!(actual, expected.raw());
 }
 
+#[test]
+fn interleaved_dotted_key_roundtrip() {
+    let input = r#"
+a.b = 1
+c = 2
+a.d.e = 3
+inline = { x.y = 1, z = 2, x.w = 3 }
+[t]
+x.y = 1
+z = 2
+x.w = 3
+"#;
+    let expected = input;
+
+    let manifest: DocumentMut = input.parse().unwrap();
+    let actual = manifest.to_string();
+
+    assert_data_eq!(actual, expected.raw());
+}
+
 #[test]
 fn string_repr_roundtrip() {
     assert_string_repr_roundtrip(r#""""#, str![[r#""""#]]);
@@ -1856,3 +1910,722 @@ fn assert_key_value_roundtrip(input: &str, expected: impl IntoData) {
     let expected = expected.into_data();
     assert_data_eq!(actual, expected);
 }
+
+#[test]
+fn key_and_value_spans() {
+    let input = r#"
+a.b = 1
+[t]
+"quoted" = { c = [1, 2] }
+"#;
+    let doc = ImDocument::parse(input).unwrap();
+
+    let key = |path: &[&str]| doc.key_span(path).map(|s| &input[s]);
+    let value = |path: &[&str]| doc.value_span(path).map(|s| &input[s]);
+    assert_eq!(key(&["a", "b"]), Some("b"));
+    assert_eq!(value(&["a", "b"]), Some("1"));
+    assert_eq!(key(&["t", "quoted"]), Some(r#""quoted""#));
+    assert_eq!(value(&["t", "quoted", "c"]), Some("[1, 2]"));
+    assert_eq!(value(&["t"]), Some("[t]\n\"quoted\" = { c = [1, 2] }"));
+    assert_eq!(key(&["t", "missing"]), None);
+
+    let doc = doc.into_mut();
+    assert_eq!(doc.as_table().get_path(&["t"]).unwrap().span(), None);
+}
+
+#[test]
+fn get_str_borrows_raw() {
+    let input = r#"
+bare = "plain"
+literal = 'C:\path'
+escaped = "tab\there"
+multiline = """
+first"""
+number = 1
+"#;
+    let doc = ImDocument::parse(input).unwrap();
+    let in_raw = |s: &str| input.as_bytes().as_ptr_range().contains(&s.as_ptr());
+
+    let plain = doc.get_str(&["bare"]).unwrap();
+    assert_eq!(plain, "plain");
+    assert!(in_raw(plain));
+    let literal = doc.get_str(&["literal"]).unwrap();
+    assert_eq!(literal, r"C:\path");
+    assert!(in_raw(literal));
+    let escaped = doc.get_str(&["escaped"]).unwrap();
+    assert_eq!(escaped, "tab\there");
+    assert!(!in_raw(escaped));
+    let multiline = doc.get_str(&["multiline"]).unwrap();
+    assert_eq!(multiline, "first");
+    assert!(in_raw(multiline));
+    assert_eq!(doc.get_str(&["number"]), None);
+    assert_eq!(doc.get_str(&["missing"]), None);
+
+    let crlf = ImDocument::parse("crlf = '''\r\nline\r\nnext'''\r\n").unwrap();
+    assert_eq!(crlf.get_str(&["crlf"]), Some("line\nnext"));
+}
+
+#[test]
+fn events() {
+    let input = r#"
+# comment
+a.b = 1
+[t]
+"quoted" = { c = [1, 'x'], d.e = true }
+[[array]]
+"#;
+    let names = |keys: &[Key]| keys.iter().map(|k| k.get().to_owned()).collect::<Vec<_>>();
+    let events = toml_edit::events(input)
+        .map(|event| {
+            let event = event.unwrap();
+            let kind = match &event {
+                toml_edit::Event::TableHeader { keys, .. } => format!("table {:?}", names(keys)),
+                toml_edit::Event::ArrayOfTablesHeader { keys, .. } => {
+                    format!("array of tables {:?}", names(keys))
+                }
+                toml_edit::Event::Key { keys, .. } => format!("key {:?}", names(keys)),
+                toml_edit::Event::Value { value, .. } => format!("value {value}"),
+                toml_edit::Event::ArrayStart { .. } => "array start".to_owned(),
+                toml_edit::Event::ArrayEnd { .. } => "array end".to_owned(),
+                toml_edit::Event::InlineTableStart { .. } => "inline table start".to_owned(),
+                toml_edit::Event::InlineTableEnd { .. } => "inline table end".to_owned(),
+                _ => unreachable!(),
+            };
+            format!("{kind}: {:?}\n", &input[event.span()])
+        })
+        .collect::<String>();
+    assert_data_eq!(
+        events,
+        str![[r#"
+key ["a", "b"]: "a.b"
+value 1: "1"
+table ["t"]: "[t]"
+key ["quoted"]: "/"quoted/""
+inline table start: "{"
+key ["c"]: "c"
+array start: "["
+value 1: "1"
+value 'x': "'x'"
+array end: "]"
+key ["d", "e"]: "d.e"
+value true: "true"
+inline table end: "}"
+array of tables ["array"]: "[[array]]"
+
+"#]]
+    );
+
+    let mut events = toml_edit::events("a = 1\nb = \n");
+    assert!(events.next().unwrap().is_ok());
+    assert!(events.next().unwrap().is_ok());
+    let err = events.next().unwrap().unwrap_err();
+    assert_eq!(err.span(), Some(10..11));
+    assert!(events.next().is_none());
+}
+
+#[test]
+fn value_eq_primitives() {
+    let doc = parse!(
+        "int = 3 # three\nfloat = 3.0\nbool = true\nstr = 'x'\n",
+        DocumentMut
+    );
+    assert!(doc["int"] == 3);
+    assert!(3 == doc["int"]);
+    assert!(doc["int"] != 3.0);
+    assert!(doc["float"] == 3.0);
+    assert!(doc["float"] != 3);
+    assert!(doc["bool"] == true);
+    assert!(doc["str"] == "x");
+    assert!(doc["str"] == *"x");
+    let owned = String::from("x");
+    assert!(doc["str"] == owned);
+    assert!(toml_edit::Item::None != "x");
+
+    let value = doc["int"].as_value().unwrap();
+    assert!(value == 3);
+    assert!(value != "3");
+    let array = parse_value!("[1, 'two']");
+    let array = array.as_array().unwrap();
+    assert!(array.get(0).unwrap() == 1);
+    assert!(array.get(1).unwrap() == "two");
+}
+
+#[test]
+fn flatten_iter() {
+    let doc = parse!(
+        r#"
+a.b = 1
+empty = []
+nested = [[1, { c = 2 }]]
+[t]
+[[bin]]
+name = "x"
+[[bin]]
+"#,
+        DocumentMut
+    );
+    let mut flattened = String::new();
+    for (path, value) in doc.flatten_iter() {
+        let path = path
+            .iter()
+            .map(|segment| match segment {
+                toml_edit::PathSegment::Key(key) => key.get().to_owned(),
+                toml_edit::PathSegment::Index(index) => index.to_string(),
+            })
+            .collect::<Vec<_>>();
+        flattened.push_str(&format!("{path:?}: {}\n", value.to_string().trim()));
+    }
+    assert_data_eq!(
+        flattened,
+        str![[r#"
+["a", "b"]: 1
+["nested", "0", "0"]: 1
+["nested", "0", "1", "c"]: 2
+["bin", "0", "name"]: "x"
+
+"#]]
+    );
+}
+
+#[test]
+fn parse_fragments() {
+    let table = parse!("{ a = 1, b = [2] }", toml_edit::InlineTable);
+    assert_eq!(table.get("a").and_then(Value::as_integer), Some(1));
+    assert_eq!(table.to_string(), "{ a = 1, b = [2] }");
+
+    let array = parse!("[ 1, 'two' ]", toml_edit::Array);
+    assert_eq!(array.len(), 2);
+    assert_eq!(array.to_string(), "[ 1, 'two' ]");
+
+    let date = parse_value!("2021-01-01");
+    assert!(date.is_datetime());
+
+    let table = parse!("a = 1\n[b]\nc = 2\n# trailing\n", toml_edit::Table);
+    assert_eq!(table["b"]["c"].as_integer(), Some(2));
+
+    let err = "[1]".parse::<toml_edit::InlineTable>().unwrap_err();
+    assert_eq!(err.to_string(), "expected inline table, found array\n");
+    let err = "1".parse::<toml_edit::Array>().unwrap_err();
+    assert_eq!(err.to_string(), "expected array, found integer\n");
+}
+
+#[test]
+fn into_mut_owns_formatting() {
+    use toml_edit::visit_mut::{self, VisitMut};
+    use toml_edit::{Array, Decor, InlineTable, KeyMut, Table};
+
+    #[derive(Default)]
+    struct Spans(usize);
+
+    impl Spans {
+        fn decor(&mut self, decor: &Decor) {
+            if !decor.is_despanned() {
+                self.0 += 1;
+            }
+        }
+    }
+
+    impl VisitMut for Spans {
+        fn visit_table_mut(&mut self, node: &mut Table) {
+            self.decor(node.decor());
+            visit_mut::visit_table_mut(self, node);
+        }
+        fn visit_inline_table_mut(&mut self, node: &mut InlineTable) {
+            self.decor(node.decor());
+            self.0 += usize::from(node.preamble().span().is_some());
+            visit_mut::visit_inline_table_mut(self, node);
+        }
+        fn visit_array_mut(&mut self, node: &mut Array) {
+            self.decor(node.decor());
+            self.0 += usize::from(node.trailing().span().is_some());
+            visit_mut::visit_array_mut(self, node);
+        }
+        fn visit_table_like_kv_mut(&mut self, key: KeyMut<'_>, node: &mut toml_edit::Item) {
+            self.decor(key.leaf_decor());
+            self.decor(key.dotted_decor());
+            if let Some(value) = node.as_value() {
+                self.decor(value.decor());
+                self.0 += usize::from(value.span().is_some());
+            }
+            visit_mut::visit_table_like_kv_mut(self, key, node);
+        }
+    }
+
+    let raw = r#"
+# leading
+a . b = 1 # one
+c = [ 1, { d = 'x' }, # trailing
+]
+e = {  }
+[t]   # table
+[[array]]
+"#;
+    let mut spans = Spans::default();
+    let doc = parse!(raw, ImDocument<String>);
+    let mut doc_mut = doc.clone().into_mut();
+    spans.visit_document_mut(&mut doc_mut);
+    assert_eq!(spans.0, 0);
+    assert!(doc_mut.trailing().span().is_none());
+    assert!(!doc["a"]["b"].as_value().unwrap().decor().is_despanned());
+    drop(doc);
+    assert_eq!(doc_mut.to_string(), raw);
+}
+
+#[test]
+fn crlf_roundtrip() {
+    let raw = "# leading\r\na = 1 # one\r\n\r\n[t]\r\nb = \"\"\"\r\nx\r\n\"\"\"\r\nc = [\r\n  1,\r\n]\r\n";
+    let mut doc = parse!(raw, DocumentMut);
+    assert_eq!(doc.line_ending(), toml_edit::LineEnding::Crlf);
+    assert_eq!(doc.to_string(), raw);
+
+    doc["t"]["d"] = toml_edit::value("y");
+    doc["u"] = toml_edit::table();
+    doc["u"]["e"] = toml_edit::value(2);
+    assert_eq!(
+        doc.to_string(),
+        "# leading\r\na = 1 # one\r\n\r\n[t]\r\nb = \"\"\"\r\nx\r\n\"\"\"\r\nc = [\r\n  1,\r\n]\r\nd = \"y\"\r\n\r\n[u]\r\ne = 2\r\n"
+    );
+
+    doc.set_line_ending(toml_edit::LineEnding::Lf);
+    let lf = doc.to_string();
+    assert!(!lf.contains('\r'));
+    assert_eq!(parse!(&lf, DocumentMut)["t"]["b"].as_str(), Some("x\n"));
+}
+
+#[test]
+fn mixed_line_endings_roundtrip() {
+    let raw = "a = 1\r\nb = 2\nc = 3\n\n# lf\n[t] # crlf\r\nd.e = \"\"\"\nx\r\ny\"\"\"\r\nf = [\n  1,\r\n]\n\r\n[[u]]\nv = 1\r\n[[u]]\r\nv = 2\n";
+    let mut doc = parse!(raw, DocumentMut);
+    assert_eq!(doc.line_ending(), toml_edit::LineEnding::Crlf);
+    assert_eq!(doc.to_string(), raw);
+    // The line endings are kept by the document, not in the decor
+    let lf = parse!(&raw.replace("\r\n", "\n"), DocumentMut);
+    assert_eq!(
+        doc["a"].as_value().unwrap().decor(),
+        lf["a"].as_value().unwrap().decor()
+    );
+
+    doc["t"]["g"] = toml_edit::value(4);
+    doc["w"] = toml_edit::table();
+    doc["w"]["h"] = toml_edit::value(5);
+    assert_eq!(
+        doc.to_string(),
+        format!("{raw}\r\n[w]\r\nh = 5\r\n").replace("\r\n]\n", "\r\n]\ng = 4\r\n")
+    );
+
+    doc.set_line_ending(toml_edit::LineEnding::Lf);
+    assert!(!doc.to_string().contains('\r'));
+}
+
+#[test]
+fn bom_roundtrip() {
+    let raw = "\u{feff} \t\n\n  # leading\n  a = 1\n";
+    let mut doc = parse!(raw, DocumentMut);
+    assert!(doc.has_bom());
+    assert_eq!(doc.to_string(), raw);
+
+    doc["b"] = toml_edit::value(2);
+    assert_eq!(
+        doc.to_string(),
+        "\u{feff} \t\n\n  # leading\n  a = 1\nb = 2\n"
+    );
+
+    doc.set_bom(false);
+    assert_eq!(doc.to_string(), " \t\n\n  # leading\n  a = 1\nb = 2\n");
+    assert!(!parse!("a = 1", DocumentMut).has_bom());
+}
+
+#[test]
+fn inline_table_trailing_comma() {
+    let options = toml_edit::ParseOptions::new().allow_inline_trailing_comma(true);
+    let raw = "a = { x = 1, y.z = 2 ,  }\nb = { c = [{ d = 3,}], }\n";
+    assert!(ImDocument::parse(raw).is_err());
+    let doc = ImDocument::parse_with_options(raw, options).unwrap();
+    assert!(doc["a"].as_inline_table().unwrap().trailing_comma());
+    assert_eq!(doc["b"]["c"][0]["d"].as_integer(), Some(3));
+
+    let mut doc = doc.into_mut();
+    assert_eq!(doc.to_string(), raw);
+    doc["a"]["w"] = toml_edit::value(4);
+    doc["b"]
+        .as_inline_table_mut()
+        .unwrap()
+        .set_trailing_comma(false);
+    assert_eq!(
+        doc.to_string(),
+        "a = { x = 1, y.z = 2 , w = 4,  }\nb = { c = [{ d = 3,}]}\n"
+    );
+
+    for invalid in ["a = {,}", "a = { x = 1,, }", "a = { , x = 1 }"] {
+        assert!(
+            ImDocument::parse_with_options(invalid, options).is_err(),
+            "{invalid}"
+        );
+    }
+}
+
+#[test]
+fn parse_with_spec() {
+    use toml_edit::TomlSpec;
+
+    let raw = "a = { b = { c = 1, }, }";
+    let err = ImDocument::parse_with_spec(raw, TomlSpec::V1_0).unwrap_err();
+    assert_eq!(err.span(), Some(17..18));
+    assert_eq!(
+        err.message(),
+        "a trailing comma in an inline table requires TOML 1.1"
+    );
+    let doc = ImDocument::parse_with_spec(raw, TomlSpec::V1_1).unwrap();
+    assert!(doc["a"]["b"].as_inline_table().unwrap().trailing_comma());
+    assert!(TomlSpec::V1_0 < TomlSpec::V1_1);
+
+    let options = toml_edit::ParseOptions::new()
+        .allow_inline_trailing_comma(true)
+        .spec(TomlSpec::V1_0);
+    assert!(ImDocument::parse_with_options(raw, options).is_ok());
+}
+
+#[test]
+fn inline_table_newlines() {
+    use toml_edit::{ParseOptions, TomlSpec};
+
+    let raw = r#"a = {
+    b = 1, # one
+    c.d = 2
+}
+"#;
+    let err = ImDocument::parse_with_spec(raw, TomlSpec::V1_0).unwrap_err();
+    assert_eq!(err.span(), Some(5..6));
+    assert_eq!(
+        err.message(),
+        "a newline or comment in an inline table requires TOML 1.1"
+    );
+    let options = ParseOptions::new().allow_inline_newlines(true);
+    let doc = ImDocument::parse_with_options(raw, options).unwrap();
+    assert_eq!(doc["a"].as_inline_table().unwrap().get_values().len(), 2);
+
+    let mut doc = doc.into_mut();
+    assert_eq!(doc.to_string(), raw);
+    let table = doc["a"].as_inline_table_mut().unwrap();
+    assert!(table.is_multiline());
+    table.set_multiline(false);
+    assert!(!table.is_multiline());
+    assert_eq!(doc.to_string(), "a = { b = 1, c.d = 2 }\n");
+}
+
+#[test]
+fn inline_table_newlines_unclosed() {
+    use toml_edit::{Limits, ParseOptions};
+
+    // TOML 1.1 wouldn't accept these either, so the newline isn't to blame
+    for (raw, span) in [
+        ("a={b=1\n", 6..7),
+        ("x = [{ key = 42\n", 15..16),
+        ("x = { a = {\n} \n", 11..12),
+        ("t = {\n,\n}\n", 5..6),
+    ] {
+        let err = ImDocument::parse(raw).unwrap_err();
+        assert_eq!(err.span(), Some(span), "{raw:?}");
+        assert_eq!(
+            err.message(),
+            "invalid inline table\nexpected `}`",
+            "{raw:?}"
+        );
+    }
+
+    // Parsing the table again doesn't count its items twice
+    let raw = "a = { b = 1, c = 2\n";
+    let options = ParseOptions::new().limits(Limits::new().max_items(2));
+    let err = ImDocument::parse_with_options(raw, options).unwrap_err();
+    assert_eq!(err.message(), "invalid inline table\nexpected `}`");
+}
+
+#[test]
+fn query() {
+    let doc = parse!(
+        r#"
+a = { "b.c" = [1, { d = 2 }] }
+
+[[bin]]
+name = "x"
+
+[[bin]]
+name = "y"
+"#,
+        DocumentMut
+    );
+    let ints = |expr: &str| {
+        doc.query(expr)
+            .unwrap()
+            .iter()
+            .filter_map(|i| i.as_integer())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(ints(r#"a."b.c"[0]"#), [1]);
+    assert_eq!(ints("a.'b.c'[1].d"), [2]);
+    assert_eq!(ints("a.*[*].d"), [2]);
+    assert_eq!(ints("*.*[5]"), Vec::<i64>::new());
+    let names = doc.query("bin[*].name").unwrap();
+    let names = names.iter().filter_map(|i| i.as_str()).collect::<Vec<_>>();
+    assert_eq!(names, ["x", "y"]);
+    assert_eq!(doc.query("*").unwrap().len(), 2);
+    assert!(doc.query("bin.name").unwrap().is_empty());
+    assert!(doc.query("missing[0]").unwrap().is_empty());
+
+    for (expr, position) in [
+        ("", 0),
+        ("a.", 2),
+        ("a..b", 2),
+        ("a[x]", 2),
+        ("'a", 2),
+        ("a b", 1),
+    ] {
+        assert_eq!(doc.query(expr).unwrap_err().position(), position, "{expr}");
+    }
+}
+
+#[test]
+fn typed_getters() {
+    let doc = parse!(
+        "when = 1979-05-27\nratio = 0.5\nlist = [1]\n[t]\n",
+        DocumentMut
+    );
+    assert!(doc.get_as::<&toml_edit::Datetime>("when").is_ok());
+    assert_eq!(doc.get_as::<f64>("ratio"), Ok(0.5));
+    assert_eq!(doc.get_as::<&toml_edit::Array>("list").unwrap().len(), 1);
+
+    let err = doc.get_as::<&str>("t").unwrap_err();
+    assert_eq!(err.key(), Some("t"));
+    assert_eq!(err.expected(), toml_edit::ItemKind::String);
+    assert_eq!(err.found(), toml_edit::ItemKind::Table);
+
+    let ratio = i64::try_from(&doc["ratio"]);
+    assert_eq!(
+        ratio.unwrap_err().to_string(),
+        "expected integer, found float"
+    );
+}
+
+#[test]
+fn interpolate() {
+    let lookup = |name: &str| (name == "A_1").then(|| "x".to_owned());
+    let interpolate = |s: &str| Value::from(s).interpolate(lookup);
+    assert_eq!(interpolate("$A_1.${A_1}").unwrap(), "x.x");
+    assert_eq!(
+        interpolate("$$A_1 $ $1 ${A_1}${A_1}$").unwrap(),
+        "$A_1 $ $1 xx$"
+    );
+    assert_eq!(
+        interpolate("$B").unwrap_err(),
+        InterpolateError::Undefined {
+            name: "B".to_owned()
+        }
+    );
+    assert_eq!(
+        interpolate("a ${A_1").unwrap_err(),
+        InterpolateError::Malformed { position: 2 }
+    );
+    assert_eq!(
+        interpolate("${}").unwrap_err(),
+        InterpolateError::Malformed { position: 0 }
+    );
+    assert_eq!(
+        Value::from(1).interpolate(lookup).unwrap_err().to_string(),
+        "expected string, found integer"
+    );
+}
+
+#[test]
+#[cfg(feature = "json")]
+fn json_roundtrip() {
+    let doc = parse!(
+        r#"
+a = [1, 2.5, "x", { b = true }]
+special = [nan, -inf]
+date = 1979-05-27
+
+[[c]]
+d = { e = 1 }
+"#,
+        DocumentMut
+    );
+    let json = toml_edit::to_json_value(&doc);
+    let roundtrip = toml_edit::from_json_value(&json).unwrap();
+    assert_eq!(json["special"], serde_json::json!(["nan", "-inf"]));
+    assert_eq!(json["date"], "1979-05-27");
+    assert_eq!(roundtrip["c"][0]["d"]["e"].as_integer(), Some(1));
+    assert!(roundtrip["c"].is_array_of_tables());
+    assert_eq!(json, toml_edit::to_json_value(&roundtrip));
+
+    let err = toml_edit::from_json_value(&serde_json::json!([1])).unwrap_err();
+    assert_eq!(err, toml_edit::FromJsonError::NotObject);
+}
+
+#[test]
+fn table_trailing_comments() {
+    let raw = r#"root = 1
+# end of root
+
+[a]
+x = 1
+  # end of a
+# still a
+
+# about b
+[[b]]
+y = 2
+# about the next b
+[[b]]
+z = 3
+# end of the document
+"#;
+    let mut doc = parse!(raw, DocumentMut);
+    assert_eq!(doc.to_string(), raw);
+    fn trailing(table: &toml_edit::Table) -> Vec<&str> {
+        table.trailing_comments().collect()
+    }
+    assert_eq!(trailing(doc.as_table()), ["end of root"]);
+    assert_eq!(
+        trailing(doc["a"].as_table().unwrap()),
+        ["end of a", "still a"]
+    );
+    let b = doc["b"].as_array_of_tables().unwrap();
+    assert!(trailing(b.get(0).unwrap()).is_empty());
+    assert_eq!(
+        b.get(1).unwrap().comments().collect::<Vec<_>>(),
+        ["about the next b"]
+    );
+    assert!(trailing(b.get(1).unwrap()).is_empty());
+    assert_eq!(doc.trailing().as_str(), Some("# end of the document\n"));
+
+    let a = doc["a"].as_table_mut().unwrap();
+    a.remove("x");
+    a.set_trailing_comments(["a is empty"]);
+    assert_eq!(
+        doc.to_string(),
+        r#"root = 1
+# end of root
+
+[a]
+# a is empty
+
+# about b
+[[b]]
+y = 2
+# about the next b
+[[b]]
+z = 3
+# end of the document
+"#
+    );
+}
+
+#[test]
+fn unicode_keys() {
+    let raw = r#""café" = 1
+'🦀' = "crab"
+"été" = 2
+"日本"."語" = 3
+"\u00FCber" = 4
+
+["ключ"]
+'emoji 🎉' = true
+"#;
+    let doc = parse!(raw, DocumentMut);
+    assert_eq!(doc.to_string(), raw);
+    assert_eq!(doc["café"].as_integer(), Some(1));
+    assert_eq!(doc["🦀"].as_str(), Some("crab"));
+    assert_eq!(doc["été"].as_integer(), Some(2));
+    assert_eq!(doc["日本"]["語"].as_integer(), Some(3));
+    assert_eq!(doc["über"].as_integer(), Some(4));
+    assert_eq!(doc["ключ"]["emoji 🎉"].as_bool(), Some(true));
+
+    // TOML 1.0 only allows ASCII letters, digits, `-` and `_` in bare keys
+    for (key, expected) in [
+        ("café", r#""café""#),
+        ("🦀", r#""🦀""#),
+        ("日本", r#""日本""#),
+        ("a-b_C9", "a-b_C9"),
+    ] {
+        assert_eq!(Key::new(key).display_repr(), expected);
+    }
+    assert!("café = 1".parse::<DocumentMut>().is_err());
+
+    let mut doc = DocumentMut::new();
+    doc["café"] = toml_edit::value(1);
+    doc["ключ"]["🦀"] = toml_edit::value("crab");
+    let rendered = doc.to_string();
+    assert_eq!(rendered, "\"café\" = 1\n\"ключ\" = { \"🦀\" = \"crab\" }\n");
+    let reparsed = parse!(rendered, DocumentMut);
+    assert_eq!(reparsed["ключ"]["🦀"].as_str(), Some("crab"));
+}
+
+#[test]
+fn to_writer() {
+    struct Full;
+
+    impl std::io::Write for Full {
+        fn write(&mut self, _: &[u8]) -> std::io::Result<usize> {
+            Err(std::io::ErrorKind::WriteZero.into())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    let doc = parse!(
+        "# leading\r\na = [\r\n  1,\r\n]\r\n[t]\r\nb = 2\r\n",
+        DocumentMut
+    );
+    let mut output = Vec::new();
+    doc.to_writer(&mut output).unwrap();
+    assert_eq!(String::from_utf8(output).unwrap(), doc.to_string());
+
+    let err = doc.to_writer(&mut Full).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::WriteZero);
+}
+
+#[test]
+fn table_styles() {
+    let doc = parse!(
+        r#"
+inline = { a = 1 }
+dotted.a = 1
+inline-array = [{ a = 1 }, { a = 2 }]
+mixed-array = [{ a = 1 }, 2]
+empty-array = []
+
+[standard]
+[implicit.child]
+[[array]]
+"#,
+        DocumentMut
+    );
+    let styles = doc
+        .iter()
+        .map(|(key, item)| {
+            (
+                key,
+                item.is_standard_table(),
+                item.is_dotted_table(),
+                item.is_inline_table(),
+                item.is_array_of_tables(),
+                item.is_array_of_inline_tables(),
+            )
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        styles,
+        [
+            ("inline", false, false, true, false, false),
+            ("dotted", false, true, false, false, false),
+            ("inline-array", false, false, false, false, true),
+            ("mixed-array", false, false, false, false, false),
+            ("empty-array", false, false, false, false, false),
+            ("standard", true, false, false, false, false),
+            ("implicit", true, false, false, false, false),
+            ("array", false, false, false, true, false),
+        ]
+    );
+}
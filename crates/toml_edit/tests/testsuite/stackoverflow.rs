@@ -52,3 +52,54 @@ fn inline_dotted_key_recursion_limit() {
         assert_eq!(document.is_ok(), is_ok, "depth: {}", depth);
     }
 }
+
+#[test]
+fn custom_limits() {
+    let limits = toml_edit::Limits::new().max_depth(4).max_len(64);
+    let depths = [(3, true), (4, false)];
+    for (depth, is_ok) in depths {
+        let arrays = format!("x={}{}", &"[".repeat(depth), &"]".repeat(depth));
+        let document = toml_edit::ImDocument::parse_with_limits(arrays, limits);
+        assert_eq!(document.is_ok(), is_ok, "depth: {}", depth);
+
+        let keys = format!("x{} = true", &".x".repeat(depth - 1));
+        let document = toml_edit::ImDocument::parse_with_limits(keys, limits);
+        assert_eq!(document.is_ok(), is_ok, "depth: {}", depth);
+    }
+
+    let long = format!("x = '{}'", "a".repeat(64));
+    let err = toml_edit::ImDocument::parse_with_limits(long, limits).unwrap_err();
+    assert_eq!(
+        err.message(),
+        "document is 70 bytes long, exceeding the limit of 64"
+    );
+    assert_eq!(
+        err.kind(),
+        &toml_edit::ErrorKind::LengthLimitExceeded { len: 70, max: 64 }
+    );
+}
+
+#[test]
+fn item_limit() {
+    let limits = toml_edit::Limits::new().max_items(4);
+    let document = toml_edit::ImDocument::parse_with_limits("a = [1, 2]\n[b]\n", limits);
+    assert_eq!(document.unwrap().item_count(), 4);
+
+    let document = toml_edit::ImDocument::parse_with_limits("a = [1, 2, 3, 4]\n", limits);
+    let err = document.unwrap_err();
+    assert_eq!(
+        err.message(),
+        "document exceeds the limit of 4 values and table headers"
+    );
+    // Values are counted once parsed, so the array holding them is the one over the limit
+    assert_eq!(err.span(), Some(4..5));
+
+    // The value expected after a trailing comma doesn't count
+    let document = toml_edit::ImDocument::parse_with_limits("a = [1, 2, 3,]\n", limits);
+    assert!(document.is_ok());
+    let document = toml_edit::ImDocument::parse_with_limits("a = { b = [1, 2,] }\n", limits);
+    assert!(document.is_ok());
+
+    let document = toml_edit::ImDocument::parse_with_limits("a = [1, 2]\n[b]\n[c]\n", limits);
+    assert!(document.is_err());
+}